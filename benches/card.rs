@@ -0,0 +1,129 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use set_game_solver::{is_set_batch, Card, CardProperties, Color, Line, Triple};
+
+/// Every card, parsed from notation since the deck itself is not public.
+fn deck() -> Vec<Card> {
+    let mut cards = Vec::new();
+    for count in ["1", "2", "3"] {
+        for color in ["R", "G", "P"] {
+            for shade in ["#", "~", "="] {
+                for shape in ["D", "S", "O"] {
+                    cards.push(format!("{count}{color}{shade}{shape}").parse().unwrap());
+                }
+            }
+        }
+    }
+    cards
+}
+
+fn decode(c: &mut Criterion) {
+    let deck = deck();
+    let mut group = c.benchmark_group("count red cards");
+    group.bench_function("properties", |b| {
+        b.iter(|| {
+            black_box(&deck)
+                .iter()
+                .filter(|card| CardProperties::from(**card).color == Color::Red)
+                .count()
+        })
+    });
+    group.bench_function("getter", |b| {
+        b.iter(|| {
+            black_box(&deck)
+                .iter()
+                .filter(|card| card.color() == Color::Red)
+                .count()
+        })
+    });
+    group.finish();
+}
+
+/// Every triple of the first twelve cards, a full board's worth.
+fn board_triples(deck: &[Card]) -> Vec<Triple> {
+    let board = &deck[..12];
+    let mut triples = Vec::new();
+    for a in 0..board.len() {
+        for b in a + 1..board.len() {
+            for c in b + 1..board.len() {
+                triples.push(Triple::from((board[a], board[b], board[c])));
+            }
+        }
+    }
+    triples
+}
+
+fn is_set(c: &mut Criterion) {
+    let triples = board_triples(&deck());
+    let mut group = c.benchmark_group("check a board's triples");
+    group.bench_function("broken attributes", |b| {
+        b.iter(|| {
+            black_box(&triples)
+                .iter()
+                .filter(|triple| triple.broken_attributes().next().is_none())
+                .count()
+        })
+    });
+    group.bench_function("is_set", |b| {
+        b.iter(|| {
+            black_box(&triples)
+                .iter()
+                .filter(|triple| triple.is_set())
+                .count()
+        })
+    });
+    let tuples: Vec<_> = triples
+        .iter()
+        .map(|triple| {
+            let [a, b, c] = triple.cards();
+            (a, b, c)
+        })
+        .collect();
+    group.bench_function("is_set_batch", |b| {
+        b.iter(|| is_set_batch(black_box(&tuples)).count_ones())
+    });
+    group.finish();
+}
+
+fn arithmetic(c: &mut Criterion) {
+    let deck = deck();
+    let pairs: Vec<_> = deck
+        .iter()
+        .flat_map(|a| deck.iter().map(move |b| (*a, *b)))
+        .collect();
+    let mut group = c.benchmark_group("card arithmetic on every pair");
+    group.bench_function("add", |b| {
+        b.iter(|| {
+            black_box(&pairs)
+                .iter()
+                .map(|(a, b)| u8::from(*a + *b) as u32)
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("sub", |b| {
+        b.iter(|| {
+            black_box(&pairs)
+                .iter()
+                .map(|(a, b)| u8::from(*a - *b) as u32)
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("complete a set", |b| {
+        b.iter(|| {
+            black_box(&pairs)
+                .iter()
+                .map(|(a, b)| u8::from(-(*a + *b)) as u32)
+                .sum::<u32>()
+        })
+    });
+    group.finish();
+}
+
+fn lines(c: &mut Criterion) {
+    let line = Line::all().nth(500).unwrap();
+    c.bench_function("lines parallel to one", |b| {
+        b.iter(|| black_box(line).parallel_class().count())
+    });
+}
+
+criterion_group!(benches, decode, is_set, arithmetic, lines);
+criterion_main!(benches);
@@ -0,0 +1,173 @@
+//! Fair ordering of claims that arrive close together, for networked play.
+//!
+//! Over a network, the claim that arrives first is not always the one made first. The arbiter
+//! holds claims for a short window after the first one, then applies them in the order they were
+//! made, breaking exact ties by the lower player id so every server decides the same way.
+
+use crate::{
+    core::{Card, Triple},
+    game::{ClaimError, GameEvent, GameState, PlayerId},
+};
+use std::time::Duration;
+
+/// A claim waiting for its window to close.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingClaim {
+    pub player: PlayerId,
+    pub triple: Triple,
+    /// When the player made the claim, on a clock shared by every claim in the game.
+    pub at: Duration,
+}
+
+/// Collects claims and settles them once their window has closed.
+#[derive(Debug, Clone)]
+pub struct Arbiter {
+    window: Duration,
+    pending: Vec<PendingClaim>,
+}
+
+impl Arbiter {
+    /// Claims made within `window` of the first pending claim are settled together.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn submit(&mut self, claim: PendingClaim) {
+        self.pending.push(claim);
+    }
+
+    /// When the current window closes, if any claim is waiting.
+    pub fn deadline(&self) -> Option<Duration> {
+        self.pending
+            .iter()
+            .map(|claim| claim.at)
+            .min()
+            .map(|first| first + self.window)
+    }
+
+    /// Apply every claim whose window closed by `now` to the game, earliest first.
+    ///
+    /// Returns an event for each: the claim, or why it was refused. A claim that lost cards to an
+    /// earlier one in the same window is refused with [`ClaimError::Beaten`].
+    pub fn settle(&mut self, game: &mut GameState, now: Duration) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        while let Some(deadline) = self.deadline().filter(|deadline| *deadline <= now) {
+            let (mut window, rest) = std::mem::take(&mut self.pending)
+                .into_iter()
+                .partition::<Vec<_>, _>(|claim| claim.at < deadline);
+            self.pending = rest;
+            window.sort_by_key(|claim| (claim.at, claim.player.0));
+
+            let mut taken: Vec<(Card, PlayerId)> = Vec::new();
+            for PendingClaim { player, triple, at } in window {
+                let beaten_by = triple.cards().iter().find_map(|card| {
+                    taken
+                        .iter()
+                        .find(|(taken, _)| taken == card)
+                        .map(|(_, by)| *by)
+                });
+                let result = match beaten_by {
+                    Some(by) => {
+                        // The game never saw this claim, so tell its subscribers here
+                        let error = ClaimError::Beaten { by };
+                        game.emit(GameEvent::Refused {
+                            player,
+                            error: error.clone(),
+                        });
+                        Err(error)
+                    }
+                    None => game.try_claim_at(player, triple.clone(), at),
+                };
+                events.push(match result {
+                    Ok(claim) => {
+                        taken.extend(claim.cards.iter().map(|card| (*card, player)));
+                        GameEvent::Claimed { player, claim }
+                    }
+                    Err(error) => GameEvent::Refused { player, error },
+                });
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{game, triple, BOARD};
+    use pretty_assertions::assert_eq;
+
+    fn claim(player: u32, notation: &str, at: u64) -> PendingClaim {
+        PendingClaim {
+            player: PlayerId(player),
+            triple: triple(notation),
+            at: Duration::from_millis(at),
+        }
+    }
+
+    fn settled(events: &[GameEvent]) -> Vec<(u32, Result<(), ClaimError>)> {
+        events
+            .iter()
+            .map(|event| match event {
+                GameEvent::Claimed { player, .. } => (player.0, Ok(())),
+                GameEvent::Refused { player, error } => (player.0, Err(error.clone())),
+                event => panic!("unexpected {event:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn earliest_claim_wins_regardless_of_arrival() {
+        let mut game = game(BOARD);
+        let mut arbiter = Arbiter::new(Duration::from_millis(100));
+        // Player 2 arrives first but claimed later
+        arbiter.submit(claim(2, "1R#D 1G#D 1P#D", 40));
+        arbiter.submit(claim(1, "1R#D 2R#D 3R#D", 30));
+        arbiter.submit(claim(3, "1R#S 1R#O 1R#D", 200));
+        assert_eq!(arbiter.deadline(), Some(Duration::from_millis(130)));
+        assert_eq!(
+            arbiter.settle(&mut game, Duration::from_millis(129)),
+            vec![]
+        );
+
+        let events = arbiter.settle(&mut game, Duration::from_millis(130));
+        assert_eq!(
+            settled(&events),
+            vec![
+                (1, Ok(())),
+                (2, Err(ClaimError::Beaten { by: PlayerId(1) })),
+            ]
+        );
+        assert_eq!(game.score(PlayerId(1)), 1);
+        assert_eq!(arbiter.deadline(), Some(Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn ties_go_to_the_lower_player_and_windows_settle_in_turn() {
+        let mut game = game(BOARD);
+        let mut arbiter = Arbiter::new(Duration::from_millis(100));
+        arbiter.submit(claim(7, "1R#D 2G~S 3P=O", 10));
+        arbiter.submit(claim(4, "3P=O 2G~S 1R#D", 10));
+        arbiter.submit(claim(5, "2R#D 2G#D 2P#D", 50));
+        arbiter.submit(claim(6, "1R#S 1R#O 1R#D", 150));
+        let subscribed = game.subscribe();
+        let events = arbiter.settle(&mut game, Duration::from_secs(1));
+        assert_eq!(
+            settled(&events),
+            vec![
+                (4, Ok(())),
+                (7, Err(ClaimError::Beaten { by: PlayerId(4) })),
+                (5, Ok(())),
+                (6, Err(ClaimError::GameOver)),
+            ]
+        );
+        assert_eq!(arbiter.deadline(), None);
+        // Subscribers also see the claims that never reached the game, and the end of it
+        let mut expected = events;
+        expected.insert(3, GameEvent::GameOver);
+        assert_eq!(subscribed.try_iter().collect::<Vec<_>>(), expected);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    core::{Card, Triple},
+    game::{ClaimAccepted, ClaimError, GameEvent, GameState, PlayerId},
+};
+use std::sync::{mpsc::Receiver, Arc};
+use tokio::sync::{broadcast, Mutex};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+/// Events kept for subscribers that have not caught up yet.
+const EVENT_BUFFER: usize = 256;
+
+/// A game shared between tasks, with claims as futures and events as a stream.
+///
+/// Clones refer to the same game. Waiting for the game never blocks a runtime thread.
+#[derive(Debug, Clone)]
+pub struct AsyncGame {
+    game: Arc<Mutex<Subscribed>>,
+    events: broadcast::Sender<GameEvent>,
+}
+
+/// The game and its event channel, which are only ever used together.
+#[derive(Debug)]
+struct Subscribed {
+    game: GameState,
+    events: Receiver<GameEvent>,
+}
+
+impl From<GameState> for AsyncGame {
+    fn from(mut game: GameState) -> Self {
+        Self {
+            game: Arc::new(Mutex::new(Subscribed {
+                events: game.subscribe(),
+                game,
+            })),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        }
+    }
+}
+
+impl AsyncGame {
+    /// See [`GameState::try_claim`].
+    pub async fn try_claim(
+        &self,
+        player: PlayerId,
+        triple: Triple,
+    ) -> Result<ClaimAccepted, ClaimError> {
+        let mut subscribed = self.game.lock().await;
+        let result = subscribed.game.try_claim(player, triple);
+        self.forward(&subscribed);
+        result
+    }
+
+    /// See [`GameState::deal_extra`].
+    pub async fn deal_extra(&self) -> Vec<Card> {
+        let mut subscribed = self.game.lock().await;
+        let dealt = subscribed.game.deal_extra();
+        self.forward(&subscribed);
+        dealt
+    }
+
+    /// A copy of the game as it is now.
+    pub async fn snapshot(&self) -> GameState {
+        self.game.lock().await.game.clone()
+    }
+
+    /// Every event from now on.
+    ///
+    /// A subscriber more than 256 events behind skips the ones it missed, and can take a
+    /// [`AsyncGame::snapshot`] to catch up.
+    pub fn subscribe(&self) -> impl Stream<Item = GameEvent> {
+        BroadcastStream::new(self.events.subscribe()).filter_map(Result::ok)
+    }
+
+    /// Pass on the game's events, while still holding it so they stay in the order they happened.
+    fn forward(&self, subscribed: &Subscribed) {
+        for event in subscribed.events.try_iter() {
+            // Nobody listening is fine
+            let _ = self.events.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::Deck, test_util::cards};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn claims_are_streamed_to_subscribers() {
+        let mut deck = cards("1R#D 2G~S 3P=O 1R#S 1R#O 2R#D");
+        deck.reverse();
+        let game = AsyncGame::from(GameState::new(Deck(deck)));
+        let events = game.subscribe();
+
+        let player = PlayerId(1);
+        let claim = game
+            .try_claim(
+                player,
+                Triple::from((cards("1R#D")[0], cards("2G~S")[0], cards("3P=O")[0])),
+            )
+            .await
+            .unwrap();
+        assert_eq!(game.deal_extra().await, vec![]);
+        let claimed = cards("1R#S 1R#O 2R#D");
+        assert_eq!(
+            game.try_claim(player, Triple::from((claimed[0], claimed[1], claimed[2])))
+                .await,
+            Err(ClaimError::GameOver)
+        );
+        drop(game);
+
+        assert_eq!(
+            events.collect::<Vec<_>>().await,
+            vec![
+                GameEvent::Claimed { player, claim },
+                GameEvent::GameOver,
+                GameEvent::Refused {
+                    player,
+                    error: ClaimError::GameOver,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Recovery files, so that a game survives the terminal going away, e.g. when an SSH connection
+//! drops.
+//!
+//! The game in progress is kept in the data directory as a Set Game Notation record, saved after
+//! every move, every few seconds to keep the clock, and from a panic hook. Finishing the game
+//! removes it, so a record found on the next launch is a game that was cut short.
+//!
+//! Suspending a paused game saves it the same way, marked to carry on paused. Comments before
+//! the record keep the clock and when each move was made, so that hint cooldowns and speed
+//! bonuses pick up where they left off, and the scoring and hint rules, so that the game carries
+//! on and is verified under the rules it was started with.
+//!
+//! The first comment gives the version of the file. Files from older versions are upgraded as
+//! they are loaded, and files from newer versions are refused rather than misread.
+
+use crate::{error::Error, App, Scoring};
+use set_game_solver::{GameRecord, HintRules};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// How often to save when nothing else has changed, to keep the elapsed time.
+const INTERVAL: Duration = Duration::from_secs(5);
+
+/// The version of recovery file written, and the newest that can be loaded.
+///
+/// Version 1 kept only the elapsed time. Version 2 added when each move was made and whether the
+/// game was suspended, version 3 gives its version, and version 4 keeps the scoring and hint rules.
+const VERSION: u32 = 4;
+
+/// A game that was interrupted or suspended, and how long it had been played for.
+#[derive(Debug)]
+pub struct Recovery {
+    pub path: PathBuf,
+    pub record: GameRecord,
+    pub elapsed: Duration,
+    /// How long into the game each move was made, if saved by a version of `sgs` that kept them.
+    pub times: Vec<Duration>,
+    /// Whether the game was paused and suspended, rather than cut short.
+    pub suspended: bool,
+    /// The scoring the game was played with, if saved by a version of `sgs` that kept it.
+    pub scoring: Option<Scoring>,
+    /// The hint rules the game was played with, if saved by a version of `sgs` that kept them.
+    pub hints: Option<HintRules>,
+}
+
+fn recovery_file(data_dir: &Path) -> PathBuf {
+    data_dir.join("recovery.sgn")
+}
+
+/// The times are kept in comments, so the file is still plain SGN.
+fn to_text(app: &App, record: &GameRecord, suspended: bool) -> String {
+    let times: Vec<_> = app
+        .move_times()
+        .map(|at| at.as_millis().to_string())
+        .collect();
+    let HintRules {
+        allowed,
+        cooldown,
+        cost,
+    } = app.settings.hints;
+    let mut text = format!(
+        "; recovery {VERSION}\n; elapsed_ms {}\n; move_ms {}\n; scoring {}\n\
+         ; hints allowed={allowed},cooldown_ms={},cost={cost}\n",
+        app.elapsed().as_millis(),
+        times.join(" "),
+        app.settings.scoring,
+        cooldown.as_millis(),
+    );
+    if suspended {
+        text.push_str("; suspended\n");
+    }
+    text.push_str(&record.to_string());
+    text
+}
+
+impl Recovery {
+    /// The interrupted game in the data directory, if there is one.
+    pub fn load(data_dir: &Path) -> Result<Option<Self>, Error> {
+        let path = recovery_file(data_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::read(path).map(Some)
+    }
+
+    /// A game saved the same way anywhere, or any other record in Set Game Notation, which reads
+    /// as a game with no times.
+    pub fn read(path: PathBuf) -> Result<Self, Error> {
+        let text = fs::read_to_string(&path)
+            .map_err(Error::io(format!("failed to read {}", path.display())))?;
+        let record = text
+            .parse()
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        let comment = |name: &str| {
+            text.lines()
+                .find_map(|line| line.strip_prefix("; ")?.strip_prefix(name))
+        };
+        // Files from before the version comment are told apart by what they kept
+        let version = match comment("recovery ") {
+            Some(version) => version
+                .trim()
+                .parse()
+                .map_err(|_| format!("{}: invalid version '{}'", path.display(), version.trim()))?,
+            None if comment("move_ms").is_some() => 2,
+            None => 1,
+        };
+        if version > VERSION {
+            Err(format!(
+                "{}: saved by a newer version of sgs (recovery file version {version}, expected up \
+                 to {VERSION})",
+                path.display()
+            ))?;
+        }
+        let elapsed = comment("elapsed_ms ")
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or_default();
+        // Version 1 did not keep move times, so the game resumes with only the elapsed time
+        let times = match version {
+            1 => Vec::new(),
+            _ => comment("move_ms")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(|millis| millis.parse().map(Duration::from_millis))
+                .collect::<Result<_, _>>()
+                .map_err(|error| format!("{}: invalid move time: {error}", path.display()))?,
+        };
+        let scoring = comment("scoring ")
+            .map(|scoring| scoring.trim().parse())
+            .transpose()
+            .map_err(|error| format!("{}: invalid scoring: {error}", path.display()))?;
+        let hints = comment("hints ")
+            .map(|hints| {
+                parse_hint_rules(hints.trim())
+                    .ok_or_else(|| format!("{}: invalid hints '{}'", path.display(), hints.trim()))
+            })
+            .transpose()?;
+        Ok(Self {
+            path,
+            record,
+            elapsed,
+            times,
+            suspended: comment("suspended").is_some(),
+            scoring,
+            hints,
+        })
+    }
+}
+
+/// Hint rules as saved, e.g. `allowed=3,cooldown_ms=30000,cost=1`.
+fn parse_hint_rules(s: &str) -> Option<HintRules> {
+    let mut rules = HintRules::default();
+    let (mut allowed, mut cooldown, mut cost) = (false, false, false);
+    for rule in s.split(',') {
+        match rule.split_once('=')? {
+            ("allowed", value) if !allowed => {
+                rules.allowed = value.parse().ok()?;
+                allowed = true;
+            }
+            ("cooldown_ms", value) if !cooldown => {
+                rules.cooldown = Duration::from_millis(value.parse().ok()?);
+                cooldown = true;
+            }
+            ("cost", value) if !cost => {
+                rules.cost = value.parse().ok()?;
+                cost = true;
+            }
+            _ => return None,
+        }
+    }
+    (allowed && cooldown && cost).then_some(rules)
+}
+
+/// Keeps the recovery file up to date with a game in progress.
+pub struct Autosave {
+    path: PathBuf,
+    /// What to write if the game panics, shared with the panic hook.
+    latest: Arc<Mutex<Option<String>>>,
+    saved_at: Instant,
+    /// The record last saved, to save again as soon as it changes.
+    saved: Option<GameRecord>,
+}
+
+impl Autosave {
+    /// Save to the data directory, including from a panic hook installed now.
+    pub fn install(data_dir: &Path) -> Result<Self, Error> {
+        fs::create_dir_all(data_dir).map_err(Error::io(format!(
+            "failed to create {}",
+            data_dir.display()
+        )))?;
+        let path = recovery_file(data_dir);
+        let latest = Arc::new(Mutex::new(None::<String>));
+        let previous = std::panic::take_hook();
+        let (hook_path, hook_latest) = (path.clone(), latest.clone());
+        std::panic::set_hook(Box::new(move |info| {
+            let latest = hook_latest.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(text) = latest.as_deref() {
+                // Already panicking, so there is nothing more to do if this fails too
+                let _ = write(&hook_path, text);
+            }
+            previous(info);
+        }));
+        Ok(Self {
+            path,
+            latest,
+            saved_at: Instant::now(),
+            saved: None,
+        })
+    }
+
+    /// Save the game if it has changed or the interval has passed, or remove the recovery file
+    /// once the game is over.
+    pub fn update(&mut self, app: &App) -> Result<(), Error> {
+        let mut latest = self.latest.lock().unwrap_or_else(PoisonError::into_inner);
+        if app.game_over.is_some() {
+            self.saved = None;
+            if latest.take().is_some() && self.path.exists() {
+                fs::remove_file(&self.path).map_err(Error::io(format!(
+                    "failed to remove {}",
+                    self.path.display()
+                )))?;
+            }
+            return Ok(());
+        }
+        let record = app.record();
+        let text = to_text(app, &record, false);
+        if self.saved.as_ref() != Some(&record) || self.saved_at.elapsed() >= INTERVAL {
+            write(&self.path, &text)
+                .map_err(Error::io(format!("failed to save {}", self.path.display())))?;
+            tracing::debug!(path = %self.path.display(), "saved recovery file");
+            self.saved_at = Instant::now();
+            self.saved = Some(record);
+        }
+        *latest = Some(text);
+        Ok(())
+    }
+
+    /// Save the paused game to carry on next time, returning where it was saved.
+    pub fn suspend(&mut self, app: &App) -> Result<PathBuf, Error> {
+        let text = to_text(app, &app.record(), true);
+        write(&self.path, &text)
+            .map_err(Error::io(format!("failed to save {}", self.path.display())))?;
+        tracing::info!(path = %self.path.display(), "suspended game");
+        *self.latest.lock().unwrap_or_else(PoisonError::into_inner) = Some(text);
+        Ok(self.path.clone())
+    }
+}
+
+/// Write to a temporary file first, so a crash mid-write leaves the previous save intact.
+fn write(path: &Path, text: &str) -> std::io::Result<()> {
+    let temporary = path.with_extension("sgn.tmp");
+    fs::write(&temporary, text)?;
+    fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn read(version: u32) -> Recovery {
+        let path = format!(
+            "{}/fixtures/recovery/v{version}.sgn",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        Recovery::read(PathBuf::from(path)).unwrap()
+    }
+
+    #[test]
+    fn older_versions_are_read() {
+        let times: Vec<_> = [9000, 15500, 21000, 38250]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+        let v1 = read(1);
+        assert_eq!(v1.elapsed, Duration::from_millis(41250));
+        assert_eq!(v1.times, vec![]);
+        assert!(!v1.suspended);
+        assert_eq!(v1.record.moves.len(), 4);
+
+        let v2 = read(2);
+        assert_eq!(v2.record, v1.record);
+        assert_eq!(v2.times, times);
+        assert!(!v2.suspended);
+
+        let v3 = read(3);
+        assert_eq!(v3.record, v1.record);
+        assert_eq!(v3.times, times);
+        assert!(v3.suspended);
+        assert_eq!((v3.scoring, v3.hints), (None, None));
+
+        let current = read(VERSION);
+        assert_eq!(current.record, v1.record);
+        assert_eq!(current.times, times);
+        assert_eq!(current.scoring, Some(Scoring::Speed));
+        assert_eq!(
+            current.hints,
+            Some(HintRules {
+                allowed: 2,
+                cooldown: Duration::from_millis(12500),
+                cost: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn hint_rules_need_every_rule_once() {
+        assert_eq!(
+            parse_hint_rules("allowed=3,cooldown_ms=30000,cost=1"),
+            Some(HintRules::default())
+        );
+        assert_eq!(parse_hint_rules("allowed=3,cost=1"), None);
+        assert_eq!(
+            parse_hint_rules("allowed=3,allowed=4,cooldown_ms=0,cost=1"),
+            None
+        );
+        assert_eq!(parse_hint_rules("allowed=3,cooldown_ms=1s,cost=1"), None);
+    }
+}
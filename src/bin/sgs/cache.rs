@@ -0,0 +1,131 @@
+//! A cache on disk for results that are slow to work out, such as puzzles with tight constraints.
+//!
+//! Each result is kept in a file named by a hash of its key, a canonical description of every
+//! input that decides it. The file starts with the key itself, so a hash collision reads as a
+//! miss rather than as someone else's result. Entries are kept per version of `sgs`, and writing
+//! one removes those of every other version, whose results may differ.
+
+use crate::error::Error;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Cached results of one kind, e.g. `puzzle`.
+#[derive(Debug)]
+pub struct Cache {
+    /// Where every version's entries of this kind are kept.
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(data_dir: &Path, kind: &str) -> Self {
+        Self {
+            dir: data_dir.join("cache").join(kind),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(VERSION).join(format!("{:016x}", hash(key)))
+    }
+
+    /// The result kept for the key, if there is one.
+    pub fn get(&self, key: &str) -> Option<String> {
+        let entry = fs::read_to_string(self.path(key)).ok()?;
+        let (kept, value) = entry.split_once("\n\n")?;
+        (kept == key).then(|| value.to_owned())
+    }
+
+    /// Keep the result for the key, dropping entries from other versions.
+    pub fn put(&self, key: &str, value: &str) -> Result<(), Error> {
+        let path = self.path(key);
+        let dir = path.parent().expect("entries are in a version's directory");
+        fs::create_dir_all(dir).map_err(Error::io(format!(
+            "failed to create cache directory {}",
+            dir.display()
+        )))?;
+        fs::write(&path, format!("{key}\n\n{value}")).map_err(Error::io(format!(
+            "failed to write cache entry {}",
+            path.display()
+        )))?;
+        let stale = fs::read_dir(&self.dir)
+            .map_err(Error::io(format!("failed to read {}", self.dir.display())))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name() != VERSION);
+        for entry in stale {
+            fs::remove_dir_all(entry.path()).map_err(Error::io(format!(
+                "failed to remove stale cache entries {}",
+                entry.path().display()
+            )))?;
+        }
+        Ok(())
+    }
+}
+
+/// 64-bit FNV-1a, which unlike the standard library's hasher is the same on every platform and
+/// release, so entries are found again.
+fn hash(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// A data directory of its own for each test, removed at the end.
+    struct DataDir(PathBuf);
+
+    impl DataDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("sgs-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            Self(dir)
+        }
+    }
+
+    impl Drop for DataDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn hashes_are_stable() {
+        // Published FNV-1a test vectors
+        assert_eq!(hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(hash("foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn entries_are_kept_by_key() {
+        let data_dir = DataDir::new("cache-keys");
+        let cache = Cache::new(&data_dir.0, "puzzle");
+        assert_eq!(cache.get("seed 1"), None);
+        cache.put("seed 1", "1R#D 2G~S 3P=O").unwrap();
+        assert_eq!(cache.get("seed 1"), Some("1R#D 2G~S 3P=O".to_owned()));
+
+        // Another key with the same hash is a miss, not the result kept
+        fs::write(cache.path("seed 2"), "seed 1\n\n1R#D 2G~S 3P=O").unwrap();
+        assert_eq!(cache.get("seed 2"), None);
+    }
+
+    #[test]
+    fn writing_drops_other_versions() {
+        let data_dir = DataDir::new("cache-versions");
+        let cache = Cache::new(&data_dir.0, "puzzle");
+        let old = cache.dir.join("0.0.1");
+        fs::create_dir_all(&old).unwrap();
+        fs::write(old.join("0000000000000000"), "seed 1\n\nold").unwrap();
+
+        cache.put("seed 1", "new").unwrap();
+        assert!(!old.exists());
+        assert!(cache.dir.join(VERSION).exists());
+        assert_eq!(cache.get("seed 1"), Some("new".to_owned()));
+    }
+}
@@ -0,0 +1,297 @@
+//! Shell completion scripts, generated from the argument definitions so they never drift.
+
+use argh::{ArgsInfo, CommandInfoWithArgs, FlagInfoKind, FromArgs};
+use std::{fmt::Write, str::FromStr};
+
+/// Print a shell completion script, e.g. 'sgs completions bash > /etc/bash_completion.d/sgs'
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "completions")]
+pub struct CompletionsCommand {
+    /// shell to complete for: 'bash', 'zsh' or 'fish'
+    #[argh(positional)]
+    shell: Shell,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            _ => Err(format!("expected 'bash', 'zsh' or 'fish', got '{s}'")),
+        }
+    }
+}
+
+/// A command and everything that can follow it, flattened out of argh's argument info.
+struct Node {
+    /// Names from the binary down to this command, e.g. `["sgs", "config", "show"]`.
+    path: Vec<String>,
+    description: String,
+    flags: Vec<Flag>,
+    /// Whether the command takes positional arguments, completed as files.
+    positionals: bool,
+    children: Vec<Node>,
+}
+
+struct Flag {
+    long: String,
+    description: String,
+    takes_value: bool,
+}
+
+impl Node {
+    fn new(path: Vec<String>, info: &CommandInfoWithArgs) -> Self {
+        let children = info
+            .commands
+            .iter()
+            .map(|command| {
+                let mut path = path.clone();
+                path.push(command.name.to_owned());
+                Node::new(path, &command.command)
+            })
+            .collect();
+        Self {
+            description: one_line(info.description),
+            flags: info
+                .flags
+                .iter()
+                .filter(|flag| !flag.hidden)
+                .map(|flag| Flag {
+                    long: flag.long.to_owned(),
+                    description: one_line(flag.description),
+                    takes_value: matches!(flag.kind, FlagInfoKind::Option { .. }),
+                })
+                .collect(),
+            positionals: info.positionals.iter().any(|positional| !positional.hidden),
+            children,
+            path,
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.path.last().map(String::as_str).unwrap_or_default()
+    }
+
+    /// This node and all of its descendants, parents first.
+    fn walk(&self) -> Vec<&Node> {
+        let mut nodes = vec![self];
+        for child in &self.children {
+            nodes.extend(child.walk());
+        }
+        nodes
+    }
+}
+
+fn one_line(description: &str) -> String {
+    description.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl CompletionsCommand {
+    pub fn run(&self) {
+        let root = Node::new(vec!["sgs".to_owned()], &crate::Cli::get_args_info());
+        print!(
+            "{}",
+            match self.shell {
+                Shell::Bash => bash(&root),
+                Shell::Zsh => zsh(&root),
+                Shell::Fish => fish(&root),
+            }
+        );
+    }
+}
+
+fn bash(root: &Node) -> String {
+    let mut out = String::new();
+    writeln!(out, "_sgs() {{").unwrap();
+    writeln!(out, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"").unwrap();
+    writeln!(out, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"").unwrap();
+    writeln!(out, "    local path=sgs word").unwrap();
+    writeln!(
+        out,
+        "    for word in \"${{COMP_WORDS[@]:1:COMP_CWORD-1}}\"; do"
+    )
+    .unwrap();
+    writeln!(out, "        case \"$path $word\" in").unwrap();
+    let commands: Vec<_> = root.walk()[1..]
+        .iter()
+        .map(|node| format!("\"{}\"", node.path.join(" ")))
+        .collect();
+    if !commands.is_empty() {
+        writeln!(
+            out,
+            "            {}) path=\"$path $word\" ;;",
+            commands.join("|")
+        )
+        .unwrap();
+    }
+    writeln!(out, "        esac").unwrap();
+    writeln!(out, "    done").unwrap();
+    writeln!(out, "    case \"$path\" in").unwrap();
+    for node in root.walk() {
+        writeln!(out, "        \"{}\")", node.path.join(" ")).unwrap();
+        let options: Vec<_> = node
+            .flags
+            .iter()
+            .filter(|flag| flag.takes_value)
+            .map(|flag| flag.long.as_str())
+            .collect();
+        if !options.is_empty() {
+            // Leave option values to the default completion, which offers files
+            writeln!(out, "            case \"$prev\" in").unwrap();
+            writeln!(out, "                {}) return ;;", options.join("|")).unwrap();
+            writeln!(out, "            esac").unwrap();
+        }
+        if node.positionals && node.children.is_empty() {
+            // Positional arguments are files, so only offer flags once one is started
+            writeln!(out, "            [[ \"$cur\" == -* ]] || return").unwrap();
+        }
+        let words: Vec<_> = node
+            .flags
+            .iter()
+            .map(|flag| flag.long.as_str())
+            .chain(node.children.iter().map(Node::name))
+            .collect();
+        writeln!(
+            out,
+            "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;",
+            words.join(" ")
+        )
+        .unwrap();
+    }
+    writeln!(out, "    esac").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out, "complete -o default -F _sgs sgs").unwrap();
+    out
+}
+
+/// Quote for use inside single quotes in zsh `_arguments` specs.
+fn zsh_escape(s: &str) -> String {
+    s.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh(root: &Node) -> String {
+    let mut out = String::new();
+    writeln!(out, "#compdef sgs").unwrap();
+    for node in root.walk() {
+        writeln!(out).unwrap();
+        writeln!(out, "{}() {{", node.path.join("_").replace("sgs", "_sgs")).unwrap();
+        writeln!(out, "    local line state").unwrap();
+        writeln!(out, "    _arguments -C \\").unwrap();
+        for flag in &node.flags {
+            let value = if flag.takes_value {
+                format!(":{}:_files", flag.long.trim_start_matches('-'))
+            } else {
+                String::new()
+            };
+            writeln!(
+                out,
+                "        '{}[{}]{value}' \\",
+                flag.long,
+                zsh_escape(&flag.description)
+            )
+            .unwrap();
+        }
+        if !node.children.is_empty() {
+            writeln!(out, "        '1: :->command' \\").unwrap();
+            writeln!(out, "        '*:: :->args' \\").unwrap();
+        } else if node.positionals {
+            writeln!(out, "        '*:file:_files' \\").unwrap();
+        }
+        writeln!(out, "        && return").unwrap();
+        if node.children.is_empty() {
+            writeln!(out, "}}").unwrap();
+            continue;
+        }
+        writeln!(out, "    case $state in").unwrap();
+        writeln!(out, "        command)").unwrap();
+        writeln!(out, "            local -a commands=(").unwrap();
+        for child in &node.children {
+            writeln!(
+                out,
+                "                '{}:{}'",
+                child.name(),
+                zsh_escape(&child.description)
+            )
+            .unwrap();
+        }
+        writeln!(out, "            )").unwrap();
+        writeln!(out, "            _describe command commands ;;").unwrap();
+        writeln!(out, "        args)").unwrap();
+        writeln!(out, "            case $line[1] in").unwrap();
+        for child in &node.children {
+            writeln!(
+                out,
+                "                {}) {} ;;",
+                child.name(),
+                child.path.join("_").replace("sgs", "_sgs")
+            )
+            .unwrap();
+        }
+        writeln!(out, "            esac ;;").unwrap();
+        writeln!(out, "    esac").unwrap();
+        writeln!(out, "}}").unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "_sgs \"$@\"").unwrap();
+    out
+}
+
+fn fish_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish(root: &Node) -> String {
+    let mut out = String::new();
+    for node in root.walk() {
+        // Complete a command's own flags and subcommands only once it is the last one typed
+        let condition = match &node.path[1..] {
+            [] => "__fish_use_subcommand".to_owned(),
+            [.., name] => {
+                let mut condition = format!("__fish_seen_subcommand_from {name}");
+                if !node.children.is_empty() {
+                    let children: Vec<_> = node.children.iter().map(Node::name).collect();
+                    write!(
+                        condition,
+                        "; and not __fish_seen_subcommand_from {}",
+                        children.join(" ")
+                    )
+                    .unwrap();
+                }
+                condition
+            }
+        };
+        for child in &node.children {
+            writeln!(
+                out,
+                "complete -c sgs -n '{condition}' -f -a {} -d '{}'",
+                child.name(),
+                fish_escape(&child.description)
+            )
+            .unwrap();
+        }
+        for flag in &node.flags {
+            writeln!(
+                out,
+                "complete -c sgs -n '{condition}' -l {}{} -d '{}'",
+                flag.long.trim_start_matches('-'),
+                if flag.takes_value { " -r" } else { "" },
+                fish_escape(&flag.description)
+            )
+            .unwrap();
+        }
+    }
+    out
+}
@@ -0,0 +1,727 @@
+//! Layered configuration: defaults, then the config file, then `SGS_*` environment variables,
+//! then command line flags.
+
+use crate::{
+    error::Error, locale::Language, paths, Bells, BoardLayout, Glyphs, Grid, Scoring, Settings,
+    Symbols, TileSize,
+};
+use argh::{ArgsInfo, FromArgs};
+use serde::Deserialize;
+use set_game_solver::HintRules;
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// Where a configuration value was set.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env(&'static str),
+    Cli,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::File(path) => write!(f, "file {}", path.display()),
+            Self::Env(key) => write!(f, "env {key}"),
+            Self::Cli => f.write_str("command line"),
+        }
+    }
+}
+
+/// A configuration value, and the layer that set it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Value<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Value<T> {
+    fn default(value: T) -> Self {
+        Self {
+            value,
+            source: Source::Default,
+        }
+    }
+
+    fn set(&mut self, value: Option<T>, source: &Source) {
+        if let Some(value) = value {
+            self.value = value;
+            self.source = source.clone();
+        }
+    }
+}
+
+/// Glyphs to draw cards with, or `auto` to detect them from the terminal.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GlyphsChoice(pub Option<Glyphs>);
+
+impl fmt::Display for GlyphsChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(glyphs) => glyphs.fmt(f),
+            None => f.write_str("auto"),
+        }
+    }
+}
+
+impl FromStr for GlyphsChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self(None)),
+            _ => s.parse().map(|glyphs| Self(Some(glyphs))),
+        }
+    }
+}
+
+/// Language to show the UI in, or `auto` to detect it from the locale.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LanguageChoice(pub Option<Language>);
+
+impl fmt::Display for LanguageChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(language) => language.fmt(f),
+            None => f.write_str("auto"),
+        }
+    }
+}
+
+impl FromStr for LanguageChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self(None)),
+            _ => s.parse().map(|language| Self(Some(language))),
+        }
+    }
+}
+
+/// How to pick the seed for a new game when none is given on the command line.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SeedPolicy {
+    Random,
+    Fixed(u64),
+}
+
+impl SeedPolicy {
+    pub fn seed(&self) -> u64 {
+        match self {
+            Self::Random => {
+                use rand::Rng;
+                rand::thread_rng().gen()
+            }
+            Self::Fixed(seed) => *seed,
+        }
+    }
+}
+
+impl fmt::Display for SeedPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Random => f.write_str("random"),
+            Self::Fixed(seed) => seed.fmt(f),
+        }
+    }
+}
+
+impl FromStr for SeedPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(Self::Random),
+            _ => s
+                .parse()
+                .map(Self::Fixed)
+                .map_err(|_| format!("expected 'random' or a number, got '{s}'")),
+        }
+    }
+}
+
+/// Environment variables read when loading configuration, and what they set.
+pub const ENV_VARS: &[(&str, &str)] = &[
+    ("SGS_CONFIG", "path to a config file to load"),
+    ("SGS_SEED", "'random', or a fixed seed for every new game"),
+    (
+        "SGS_DATA_DIR",
+        "directory for snapshots and other generated files",
+    ),
+    ("SGS_GLYPHS", "'auto', 'unicode', 'ascii' or 'braille'"),
+    (
+        "SGS_BELL",
+        "bells per event, e.g. 'accepted=1,rejected=2,deal=1'",
+    ),
+    ("SGS_QUIET", "'true' to never ring the bell"),
+    ("SGS_NOTATION", "'true' to show card notation on tiles"),
+    (
+        "SGS_MONOCHROME",
+        "'true' to draw without color, bracketing cards by color instead",
+    ),
+    (
+        "NO_COLOR",
+        "set to anything to draw without color, as SGS_MONOCHROME",
+    ),
+    (
+        "SGS_GRID",
+        "'auto', 'wide', 'tall' or 'row' arrangement of the tiles",
+    ),
+    ("SGS_TILE_SIZE", "'compact', 'normal' or 'large' tiles"),
+    ("SGS_GAP", "blank columns and rows between tiles"),
+    ("SGS_HINTS", "hints allowed each game"),
+    ("SGS_HINT_COOLDOWN_SECS", "seconds to wait between hints"),
+    ("SGS_HINT_COST", "points taken from the score for each hint"),
+    (
+        "SGS_SCORING",
+        "'classic', 'speed', 'strict' or 'streak' scoring",
+    ),
+    (
+        "SGS_PLAYER",
+        "name of the player whose profile keeps their results",
+    ),
+    ("SGS_TICK_RATE_MS", "milliseconds between UI updates"),
+    ("SGS_LANGUAGE", "'auto', 'en' or 'de'"),
+    (
+        "SGS_LOG",
+        "log filter for --log-file, e.g. 'info' or 'set_game_solver=trace'",
+    ),
+];
+
+/// The config file format. Every key is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    /// Either a string or an integer.
+    seed: Option<toml::Value>,
+    glyphs: Option<String>,
+    bell: Option<String>,
+    quiet: Option<bool>,
+    notation: Option<bool>,
+    monochrome: Option<bool>,
+    grid: Option<String>,
+    tile_size: Option<String>,
+    gap: Option<u16>,
+    hints: Option<u32>,
+    hint_cooldown_secs: Option<u64>,
+    hint_cost: Option<u32>,
+    scoring: Option<String>,
+    player: Option<String>,
+    tick_rate_ms: Option<u64>,
+    data_dir: Option<PathBuf>,
+    language: Option<String>,
+    /// Replacement glyphs, keyed by shade and shape.
+    symbols: Option<BTreeMap<String, String>>,
+}
+
+/// Values given as command line flags.
+#[derive(Debug, Default)]
+pub struct Flags {
+    pub config: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub data_dir: Option<PathBuf>,
+    pub glyphs: Option<Glyphs>,
+    pub bell: Option<Bells>,
+    pub quiet: bool,
+    pub notation: bool,
+    pub monochrome: bool,
+    pub language: Option<Language>,
+    pub player: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Config {
+    pub seed: Value<SeedPolicy>,
+    pub glyphs: Value<GlyphsChoice>,
+    pub bell: Value<Bells>,
+    pub quiet: Value<bool>,
+    pub notation: Value<bool>,
+    pub monochrome: Value<bool>,
+    pub grid: Value<Grid>,
+    pub tile_size: Value<TileSize>,
+    pub gap: Value<u16>,
+    pub hints: Value<u32>,
+    pub hint_cooldown_secs: Value<u64>,
+    pub hint_cost: Value<u32>,
+    pub scoring: Value<Scoring>,
+    /// Empty for whoever played last.
+    pub player: Value<String>,
+    pub tick_rate_ms: Value<u64>,
+    pub data_dir: Value<PathBuf>,
+    pub language: Value<LanguageChoice>,
+    pub symbols: Value<Symbols>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            seed: Value::default(SeedPolicy::Random),
+            glyphs: Value::default(GlyphsChoice(None)),
+            bell: Value::default(Bells::default()),
+            quiet: Value::default(false),
+            notation: Value::default(false),
+            monochrome: Value::default(false),
+            grid: Value::default(Grid::Auto),
+            tile_size: Value::default(TileSize::Normal),
+            gap: Value::default(0),
+            hints: Value::default(HintRules::default().allowed),
+            hint_cooldown_secs: Value::default(HintRules::default().cooldown.as_secs()),
+            hint_cost: Value::default(HintRules::default().cost),
+            scoring: Value::default(Scoring::default()),
+            player: Value::default(String::new()),
+            tick_rate_ms: Value::default(250),
+            data_dir: Value::default(paths::data_dir()),
+            language: Value::default(LanguageChoice(None)),
+            symbols: Value::default(Symbols::default()),
+        }
+    }
+}
+
+impl Config {
+    /// Resolve the effective configuration from every layer.
+    pub fn load(flags: Flags) -> Result<Self, Error> {
+        fn read(path: PathBuf) -> Result<(PathBuf, String), Error> {
+            let contents = fs::read_to_string(&path).map_err(Error::io(format!(
+                "failed to read config {}",
+                path.display()
+            )))?;
+            Ok((path, contents))
+        }
+
+        // The default config file is optional, but one asked for explicitly must exist
+        let path = flags
+            .config
+            .clone()
+            .or_else(|| std::env::var_os("SGS_CONFIG").map(PathBuf::from));
+        let file = match path {
+            Some(path) => Some(read(path)?),
+            None => paths::config_file()
+                .filter(|path| path.exists())
+                .map(read)
+                .transpose()?,
+        };
+        let file = file.as_ref().map(|(path, contents)| (&**path, &**contents));
+        Self::layered(file, &|key| std::env::var_os(key), flags)
+    }
+
+    /// The defaults, overridden by the config file's contents, then by the environment variables
+    /// `env` looks up, then by flags.
+    fn layered(
+        file: Option<(&Path, &str)>,
+        env: &dyn Fn(&str) -> Option<OsString>,
+        flags: Flags,
+    ) -> Result<Self, Error> {
+        let mut config = Self::default();
+        if let Some((path, contents)) = file {
+            config.apply_file(path, contents)?;
+        }
+        config.apply_env(env)?;
+        config.apply_flags(flags);
+        Ok(config)
+    }
+
+    fn apply_file(&mut self, path: &Path, contents: &str) -> Result<(), Error> {
+        let file: File = toml::from_str(contents)
+            .map_err(|error| format!("invalid config {}: {error}", path.display()))?;
+        fn parse<T>(path: &Path, key: &str, value: Option<String>) -> Result<Option<T>, Error>
+        where
+            T: FromStr<Err = String>,
+        {
+            value
+                .map(|value| value.parse())
+                .transpose()
+                .map_err(|error| format!("invalid {key} in {}: {error}", path.display()).into())
+        }
+
+        let source = Source::File(path.to_owned());
+        let seed = match file.seed {
+            None => None,
+            Some(toml::Value::String(seed)) => parse(path, "seed", Some(seed))?,
+            Some(toml::Value::Integer(seed)) => {
+                Some(SeedPolicy::Fixed(u64::try_from(seed).map_err(|_| {
+                    format!("seed must not be negative, got {seed}")
+                })?))
+            }
+            Some(seed) => Err(format!(
+                "expected seed to be a string or number, got {seed}"
+            ))?,
+        };
+        self.seed.set(seed, &source);
+        self.glyphs
+            .set(parse(path, "glyphs", file.glyphs)?, &source);
+        self.bell.set(parse(path, "bell", file.bell)?, &source);
+        self.quiet.set(file.quiet, &source);
+        self.notation.set(file.notation, &source);
+        self.monochrome.set(file.monochrome, &source);
+        self.grid.set(parse(path, "grid", file.grid)?, &source);
+        self.tile_size
+            .set(parse(path, "tile_size", file.tile_size)?, &source);
+        self.gap.set(file.gap, &source);
+        self.hints.set(file.hints, &source);
+        self.hint_cooldown_secs
+            .set(file.hint_cooldown_secs, &source);
+        self.hint_cost.set(file.hint_cost, &source);
+        self.scoring
+            .set(parse(path, "scoring", file.scoring)?, &source);
+        self.player.set(file.player, &source);
+        self.tick_rate_ms.set(file.tick_rate_ms, &source);
+        self.data_dir.set(file.data_dir, &source);
+        self.language
+            .set(parse(path, "language", file.language)?, &source);
+        let symbols = file
+            .symbols
+            .map(|table| {
+                Symbols::from_table(table.iter().map(|(key, symbol)| (&**key, &**symbol)))
+                    .map_err(|error| format!("invalid symbols in {}: {error}", path.display()))
+            })
+            .transpose()?;
+        self.symbols.set(symbols, &source);
+        Ok(())
+    }
+
+    fn apply_env(&mut self, env: &dyn Fn(&str) -> Option<OsString>) -> Result<(), Error> {
+        fn var<T>(
+            env: &dyn Fn(&str) -> Option<OsString>,
+            key: &'static str,
+        ) -> Result<(Option<T>, Source), Error>
+        where
+            T: FromStr,
+            T::Err: fmt::Display,
+        {
+            let value = match env(key).map(OsString::into_string) {
+                Some(Ok(value)) => Some(
+                    value
+                        .parse()
+                        .map_err(|error| format!("invalid {key} '{value}': {error}"))?,
+                ),
+                _ => None,
+            };
+            Ok((value, Source::Env(key)))
+        }
+
+        let (seed, source) = var(env, "SGS_SEED")?;
+        self.seed.set(seed, &source);
+        let (glyphs, source) = var(env, "SGS_GLYPHS")?;
+        self.glyphs.set(glyphs, &source);
+        let (bell, source) = var(env, "SGS_BELL")?;
+        self.bell.set(bell, &source);
+        let (quiet, source) = var(env, "SGS_QUIET")?;
+        self.quiet.set(quiet, &source);
+        let (notation, source) = var(env, "SGS_NOTATION")?;
+        self.notation.set(notation, &source);
+        // See https://no-color.org, though the more specific variable wins
+        let no_color = env("NO_COLOR").filter(|value| !value.is_empty());
+        self.monochrome
+            .set(no_color.map(|_| true), &Source::Env("NO_COLOR"));
+        let (monochrome, source) = var(env, "SGS_MONOCHROME")?;
+        self.monochrome.set(monochrome, &source);
+        let (grid, source) = var(env, "SGS_GRID")?;
+        self.grid.set(grid, &source);
+        let (tile_size, source) = var(env, "SGS_TILE_SIZE")?;
+        self.tile_size.set(tile_size, &source);
+        let (gap, source) = var(env, "SGS_GAP")?;
+        self.gap.set(gap, &source);
+        let (hints, source) = var(env, "SGS_HINTS")?;
+        self.hints.set(hints, &source);
+        let (hint_cooldown_secs, source) = var(env, "SGS_HINT_COOLDOWN_SECS")?;
+        self.hint_cooldown_secs.set(hint_cooldown_secs, &source);
+        let (hint_cost, source) = var(env, "SGS_HINT_COST")?;
+        self.hint_cost.set(hint_cost, &source);
+        let (scoring, source) = var(env, "SGS_SCORING")?;
+        self.scoring.set(scoring, &source);
+        let (player, source) = var(env, "SGS_PLAYER")?;
+        self.player.set(player, &source);
+        let (tick_rate_ms, source) = var(env, "SGS_TICK_RATE_MS")?;
+        self.tick_rate_ms.set(tick_rate_ms, &source);
+        let (data_dir, source) = var(env, "SGS_DATA_DIR")?;
+        self.data_dir.set(data_dir, &source);
+        let (language, source) = var(env, "SGS_LANGUAGE")?;
+        self.language.set(language, &source);
+        Ok(())
+    }
+
+    fn apply_flags(&mut self, flags: Flags) {
+        let source = Source::Cli;
+        self.seed.set(flags.seed.map(SeedPolicy::Fixed), &source);
+        self.glyphs.set(
+            flags.glyphs.map(|glyphs| GlyphsChoice(Some(glyphs))),
+            &source,
+        );
+        self.bell.set(flags.bell, &source);
+        self.data_dir.set(flags.data_dir, &source);
+        self.player.set(flags.player, &source);
+        self.language.set(
+            flags
+                .language
+                .map(|language| LanguageChoice(Some(language))),
+            &source,
+        );
+        // Switches can only turn things on
+        self.quiet.set(flags.quiet.then_some(true), &source);
+        self.notation.set(flags.notation.then_some(true), &source);
+        self.monochrome
+            .set(flags.monochrome.then_some(true), &source);
+    }
+
+    pub fn settings(&self) -> Settings {
+        Settings {
+            glyphs: self.glyphs.value.0.unwrap_or_else(Glyphs::detect),
+            symbols: self.symbols.value.clone(),
+            layout: BoardLayout {
+                grid: self.grid.value,
+                tile_size: self.tile_size.value,
+                gap: self.gap.value,
+            },
+            bells: if self.quiet.value {
+                Bells::quiet()
+            } else {
+                self.bell.value.clone()
+            },
+            show_notation: self.notation.value,
+            monochrome: self.monochrome.value,
+            show_history: false,
+            show_metrics: false,
+            find_all: false,
+            chain: false,
+            count_sets: false,
+            edit: false,
+            opening: None,
+            hints: HintRules {
+                allowed: self.hints.value,
+                cooldown: Duration::from_secs(self.hint_cooldown_secs.value),
+                cost: self.hint_cost.value,
+            },
+            scoring: self.scoring.value,
+            // Chosen once the profiles in the data directory can be looked at
+            player: None,
+            data_dir: self.data_dir.value.clone(),
+            language: self.language.value.0.unwrap_or_else(Language::detect),
+        }
+    }
+
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_rate_ms.value)
+    }
+}
+
+/// Renders as a valid config file, commenting where each value came from.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Self {
+            seed,
+            glyphs,
+            bell,
+            quiet,
+            notation,
+            monochrome,
+            grid,
+            tile_size,
+            gap,
+            hints,
+            hint_cooldown_secs,
+            hint_cost,
+            scoring,
+            player,
+            tick_rate_ms,
+            data_dir,
+            language,
+            symbols,
+        } = self;
+        writeln!(f, "seed = \"{}\" # {}", seed.value, seed.source)?;
+        writeln!(f, "glyphs = \"{}\" # {}", glyphs.value, glyphs.source)?;
+        writeln!(f, "bell = \"{}\" # {}", bell.value, bell.source)?;
+        writeln!(f, "quiet = {} # {}", quiet.value, quiet.source)?;
+        writeln!(f, "notation = {} # {}", notation.value, notation.source)?;
+        writeln!(
+            f,
+            "monochrome = {} # {}",
+            monochrome.value, monochrome.source
+        )?;
+        writeln!(f, "grid = \"{}\" # {}", grid.value, grid.source)?;
+        writeln!(
+            f,
+            "tile_size = \"{}\" # {}",
+            tile_size.value, tile_size.source
+        )?;
+        writeln!(f, "gap = {} # {}", gap.value, gap.source)?;
+        writeln!(f, "hints = {} # {}", hints.value, hints.source)?;
+        writeln!(
+            f,
+            "hint_cooldown_secs = {} # {}",
+            hint_cooldown_secs.value, hint_cooldown_secs.source
+        )?;
+        writeln!(f, "hint_cost = {} # {}", hint_cost.value, hint_cost.source)?;
+        writeln!(f, "scoring = \"{}\" # {}", scoring.value, scoring.source)?;
+        // Names are free text, so quoted and escaped as TOML strings
+        let name = toml::Value::String(player.value.clone());
+        writeln!(f, "player = {name} # {}", player.source)?;
+        writeln!(
+            f,
+            "tick_rate_ms = {} # {}",
+            tick_rate_ms.value, tick_rate_ms.source
+        )?;
+        // Quote paths as TOML strings, escaping backslashes on Windows
+        let path = toml::Value::String(data_dir.value.display().to_string());
+        writeln!(f, "data_dir = {path} # {}", data_dir.source)?;
+        writeln!(f, "language = \"{}\" # {}", language.value, language.source)?;
+        // Tables come after every plain key
+        writeln!(f, "[symbols] # {}", symbols.source)?;
+        write!(f, "{}", symbols.value)
+    }
+}
+
+/// Inspect configuration
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "config")]
+pub struct ConfigCommand {
+    #[argh(subcommand)]
+    command: ConfigSubcommand,
+}
+
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand)]
+enum ConfigSubcommand {
+    Show(ShowCommand),
+    Env(EnvCommand),
+}
+
+/// Print the effective configuration and where each value came from
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "show")]
+struct ShowCommand {}
+
+/// List the environment variables that configure sgs, and their current values
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "env")]
+struct EnvCommand {}
+
+impl ConfigCommand {
+    pub fn run(&self, config: &Config) {
+        match self.command {
+            ConfigSubcommand::Show(_) => print!("{config}"),
+            ConfigSubcommand::Env(_) => {
+                // Descriptions line up two spaces after the longest name
+                let width = ENV_VARS.iter().map(|(key, _)| key.len()).max().unwrap_or(0) + 2;
+                for (key, description) in ENV_VARS {
+                    print!("{key:<width$}{description}");
+                    if let Some(value) = std::env::var_os(key) {
+                        print!(" (currently '{}')", value.to_string_lossy());
+                    }
+                    println!();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+        move |key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.into())
+        }
+    }
+
+    const FILE: &str = "hints = 1\ngap = 2\nplayer = \"file\"\nscoring = \"speed\"\n";
+
+    #[test]
+    fn later_layers_win() {
+        let path = Path::new("sgs.toml");
+        let flags = Flags {
+            player: Some("cli".to_owned()),
+            quiet: true,
+            ..Flags::default()
+        };
+        let vars = [("SGS_HINTS", "2"), ("SGS_PLAYER", "env")];
+        let config = Config::layered(Some((path, FILE)), &env(&vars), flags).unwrap();
+        assert_eq!(config.hints.value, 2);
+        assert_eq!(config.hints.source, Source::Env("SGS_HINTS"));
+        assert_eq!(config.gap.value, 2);
+        assert_eq!(config.gap.source, Source::File(path.to_owned()));
+        assert_eq!(config.scoring.value, Scoring::Speed);
+        assert_eq!(config.player.value, "cli");
+        assert_eq!(config.player.source, Source::Cli);
+        assert_eq!(config.quiet.value, true);
+        assert_eq!(config.hint_cost, Config::default().hint_cost);
+
+        // Switches left off do not turn off what other layers turned on
+        let vars = [("SGS_QUIET", "true")];
+        let config = Config::layered(None, &env(&vars), Flags::default()).unwrap();
+        assert_eq!(config.quiet.value, true);
+        assert_eq!(config.quiet.source, Source::Env("SGS_QUIET"));
+    }
+
+    #[test]
+    fn no_color_gives_way_to_monochrome() {
+        let layered = |vars: &[(&str, &str)]| {
+            Config::layered(None, &env(vars), Flags::default())
+                .unwrap()
+                .monochrome
+        };
+        assert_eq!(layered(&[]), Value::default(false));
+        assert_eq!(layered(&[("NO_COLOR", "")]), Value::default(false));
+        assert_eq!(
+            layered(&[("NO_COLOR", "1")]).source,
+            Source::Env("NO_COLOR")
+        );
+        let both = layered(&[("NO_COLOR", "1"), ("SGS_MONOCHROME", "false")]);
+        assert_eq!(both.value, false);
+        assert_eq!(both.source, Source::Env("SGS_MONOCHROME"));
+    }
+
+    #[test]
+    fn invalid_values_name_their_layer() {
+        let error = |file: Option<&str>, vars: &[(&str, &str)]| {
+            let file = file.map(|contents| (Path::new("sgs.toml"), contents));
+            Config::layered(file, &env(vars), Flags::default())
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(error(Some("grid = \"round\""), &[]).contains("invalid grid in sgs.toml"));
+        assert!(error(Some("colour = true"), &[]).contains("invalid config sgs.toml"));
+        assert!(error(None, &[("SGS_GAP", "wide")]).contains("invalid SGS_GAP 'wide'"));
+    }
+
+    #[test]
+    fn display_is_a_config_file() {
+        let flags = Flags {
+            player: Some("Tom \"Set\" O'Brien \\ 2".to_owned()),
+            seed: Some(7),
+            ..Flags::default()
+        };
+        let vars = [("SGS_HINT_COST", "3")];
+        let config =
+            Config::layered(Some((Path::new("sgs.toml"), FILE)), &env(&vars), flags).unwrap();
+        let shown = config.to_string();
+        let path = Path::new("shown.toml");
+        let reloaded = Config::layered(Some((path, &shown)), &env(&[]), Flags::default())
+            .unwrap_or_else(|error| panic!("{error}\n{shown}"));
+        let values = |config: &Config| {
+            (
+                config.seed.value,
+                config.player.value.clone(),
+                config.hints.value,
+                config.hint_cost.value,
+                config.scoring.value,
+                config.data_dir.value.clone(),
+            )
+        };
+        assert_eq!(values(&reloaded), values(&config));
+        assert_eq!(reloaded.player.source, Source::File(path.to_owned()));
+    }
+}
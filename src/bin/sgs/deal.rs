@@ -0,0 +1,107 @@
+//! Print the cards a seed deals, without starting the terminal UI.
+
+use crate::config::Config;
+use argh::{ArgsInfo, FromArgs};
+use set_game_solver::{Card, Deck};
+use std::{fmt::Write, str::FromStr};
+
+/// Cards printed per line for the full deck, so it fits in a narrow terminal.
+const DECK_ROW: usize = 9;
+
+/// Print the deck order or opening board dealt for a seed
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "deal")]
+pub struct DealCommand {
+    /// seed to deal (default: the configured seed policy)
+    #[argh(option)]
+    seed: Option<u64>,
+
+    /// what to print: 'board', 'deck' or 'all' (default: board)
+    #[argh(option, default = "Show::Board")]
+    show: Show,
+
+    /// output format: 'notation' or 'json' (default: notation)
+    #[argh(option, default = "Format::Notation")]
+    format: Format,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Show {
+    Board,
+    Deck,
+    All,
+}
+
+impl FromStr for Show {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "board" => Ok(Self::Board),
+            "deck" => Ok(Self::Deck),
+            "all" => Ok(Self::All),
+            _ => Err(format!("expected 'board', 'deck' or 'all', got '{s}'")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Format {
+    Notation,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "notation" => Ok(Self::Notation),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("expected 'notation' or 'json', got '{s}'")),
+        }
+    }
+}
+
+impl DealCommand {
+    pub fn run(&self, config: &Config) {
+        let seed = self.seed.unwrap_or_else(|| config.seed.value.seed());
+        print!("{}", self.render(seed));
+    }
+
+    fn render(&self, seed: u64) -> String {
+        // Deal through the same path as the game, so this shows exactly what a player would see
+        let mut deck = Deck::new_from_seed(seed);
+        let order: Vec<Card> = std::iter::from_fn(|| deck.deal()).collect();
+        let board = &order[..crate::BOARD_SIZE.min(order.len())];
+        let sections: Vec<(&str, &[Card], usize)> = match self.show {
+            Show::Board => vec![("board", board, 3)],
+            Show::Deck => vec![("deck", &order, DECK_ROW)],
+            Show::All => vec![("board", board, 3), ("deck", &order, DECK_ROW)],
+        };
+
+        let mut out = String::new();
+        match self.format {
+            Format::Notation => {
+                writeln!(out, "seed {seed}").unwrap();
+                for (name, cards, row) in sections {
+                    writeln!(out, "{name}").unwrap();
+                    for line in cards.chunks(row) {
+                        let line: Vec<_> = line.iter().map(Card::to_string).collect();
+                        writeln!(out, "{}", line.join(" ")).unwrap();
+                    }
+                }
+            }
+            Format::Json => {
+                // Notation never needs escaping, so there is no need for a JSON library
+                write!(out, "{{\"seed\":{seed}").unwrap();
+                for (name, cards, _) in sections {
+                    let cards: Vec<_> = cards.iter().map(|card| format!("\"{card}\"")).collect();
+                    write!(out, ",\"{name}\":[{}]", cards.join(",")).unwrap();
+                }
+                writeln!(out, "}}").unwrap();
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,82 @@
+//! A board editor, for assembling any board by hand and then solving, sharing or playing it.
+//!
+//! Each card on the board is edited in place by stepping one of its attributes to the next
+//! value, skipping values that would put a card on the board twice. The board is independent
+//! of any deck, so it can be any size and hold any cards.
+
+use crate::format_board;
+use set_game_solver::{
+    find_sets, Attribute, Card, CardProperties, CardSet, Color, Count, Shade, Shape, Triple,
+};
+
+/// A board being edited.
+#[derive(Debug, Clone)]
+pub struct Editor {
+    pub cards: Vec<Card>,
+}
+
+impl Editor {
+    pub fn new(cards: Vec<Card>) -> Self {
+        Self { cards }
+    }
+
+    /// Step an attribute of the card at `position` to its next value, or its previous one if
+    /// `back`, returning whether the card changed. Values whose card is already on the board
+    /// are skipped.
+    pub fn cycle(&mut self, position: usize, attribute: Attribute, back: bool) -> bool {
+        let Some(card) = self.cards.get(position).copied() else {
+            return false;
+        };
+        let on_board = CardSet::from(&self.cards[..]);
+        let value = attribute.index(card);
+        let changed = (1..3)
+            .map(|step| if back { value + 3 - step } else { value + step })
+            .map(|value| value % 3)
+            .map(|value| with_value(card, attribute, value))
+            .find(|changed| !on_board.contains(*changed));
+        match changed {
+            Some(changed) => {
+                self.cards[position] = changed;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Add the first card not on the board to the end of it, returning its position, or none if
+    /// every card is on the board already.
+    pub fn add(&mut self) -> Option<usize> {
+        let on_board = CardSet::from(&self.cards[..]);
+        let card = on_board.complement().iter().next()?;
+        self.cards.push(card);
+        Some(self.cards.len() - 1)
+    }
+
+    pub fn remove(&mut self, position: usize) {
+        if position < self.cards.len() {
+            self.cards.remove(position);
+        }
+    }
+
+    /// Every set on the board.
+    pub fn sets(&self) -> Vec<[Card; 3]> {
+        find_sets(&self.cards).iter().map(Triple::cards).collect()
+    }
+
+    /// A command line that opens the editor on this board, to share it or come back to it.
+    pub fn command(&self) -> String {
+        format!("sgs --edit --board {}", format_board(&self.cards))
+    }
+}
+
+/// The card with one attribute changed to the value at `value`, e.g. in [`Color::ALL`].
+fn with_value(card: Card, attribute: Attribute, value: usize) -> Card {
+    let mut properties = CardProperties::from(card);
+    match attribute {
+        Attribute::Color => properties.color = Color::ALL[value],
+        Attribute::Count => properties.count = Count::ALL[value],
+        Attribute::Shade => properties.shade = Shade::ALL[value],
+        Attribute::Shape => properties.shape = Shape::ALL[value],
+    }
+    Card::from(properties)
+}
@@ -0,0 +1,49 @@
+//! Errors surfaced by `sgs`, and the exit code for each kind so scripts can tell them apart.
+
+use std::{io, process::ExitCode};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Bad flags, config, recordings or boards. Exits with 2.
+    #[error("{0}")]
+    Input(String),
+    /// Reading or writing a file, or stdin and stdout. Exits with 3.
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: io::Error,
+    },
+    /// Drawing the terminal UI or reading events from it. Exits with 4.
+    #[error("terminal error: {0}")]
+    Terminal(#[from] io::Error),
+}
+
+impl Error {
+    /// Wrap an IO error with what was being done, e.g. `.map_err(Error::io("failed to open x"))`.
+    pub fn io(context: impl Into<String>) -> impl FnOnce(io::Error) -> Self {
+        let context = context.into();
+        move |source| Self::Io { context, source }
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            Self::Input(_) => 2,
+            Self::Io { .. } => 3,
+            Self::Terminal(_) => 4,
+        })
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Self::Input(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Self::Input(message.to_owned())
+    }
+}
@@ -0,0 +1,332 @@
+//! Translated strings for the terminal UI.
+//!
+//! Card notation such as `2R~O` is an interchange format read by `sgs solve` and recordings, so
+//! it stays the same in every language. Command line help comes from argh at compile time and is
+//! English only.
+
+use set_game_solver::Attribute;
+use std::{fmt, str::FromStr};
+
+/// A language the UI can be shown in.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    /// Pick the language from the locale's messages category, falling back to English.
+    pub fn detect() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .map(|key| std::env::var(key).unwrap_or_default())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        match locale.get(..2) {
+            Some("de") => Self::German,
+            _ => Self::English,
+        }
+    }
+
+    pub fn strings(&self) -> &'static Strings {
+        match self {
+            Self::English => &ENGLISH,
+            Self::German => &GERMAN,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::English => "en",
+            Self::German => "de",
+        })
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "en" => Ok(Self::English),
+            "de" => Ok(Self::German),
+            _ => Err(format!("expected 'en' or 'de', got '{s}'")),
+        }
+    }
+}
+
+/// The catalog of UI strings for one language.
+///
+/// Templates name their placeholders, e.g. `{cards}`, and are filled in with [`fill`].
+pub struct Strings {
+    pub color: &'static str,
+    pub count: &'static str,
+    pub shade: &'static str,
+    pub shape: &'static str,
+    pub mode_play: &'static str,
+    pub mode_record: &'static str,
+    pub mode_playback: &'static str,
+    pub mode_watch: &'static str,
+    /// `{seed}`, `{deck}`, `{score}` and `{time}`.
+    pub status: &'static str,
+    /// `{cards}`.
+    pub is_a_set: &'static str,
+    /// `{cards}` and `{attribute}`, the first attribute that is neither all the same nor all
+    /// different.
+    pub not_a_set: &'static str,
+    pub deck_empty: &'static str,
+    /// `{card}`, one of the cards in a set on the board.
+    pub hint: &'static str,
+    pub no_hints_left: &'static str,
+    /// `{seconds}` until the next hint may be had.
+    pub hint_cooling_down: &'static str,
+    pub no_set_on_board: &'static str,
+    pub all_sets_shown: &'static str,
+    /// `{command}`.
+    pub copied: &'static str,
+    /// `{path}`.
+    pub saved: &'static str,
+    pub terminal_too_small: &'static str,
+    /// Title of the side panel, and its counters.
+    pub panel: &'static str,
+    pub deck: &'static str,
+    pub sets_found: &'static str,
+    pub extra_deals: &'static str,
+    /// Title of the list of sets found.
+    pub history: &'static str,
+    /// Title of the metrics panel, and its measures.
+    pub metrics: &'static str,
+    pub sets_per_minute: &'static str,
+    pub accuracy: &'static str,
+    pub time_per_set: &'static str,
+    pub hints: &'static str,
+    /// A counter in the panel when playing as someone.
+    pub best: &'static str,
+    /// `{page}` and `{pages}`, when the board does not fit on screen at once.
+    pub page: &'static str,
+    pub game_over: &'static str,
+    /// `{time}` and `{deck}`.
+    pub finished: &'static str,
+    /// `{board}` and `{command}`.
+    pub play_again: &'static str,
+    /// `{score}`, `{sets}`, `{bonus}` and `{misclaims}`.
+    pub score_summary: &'static str,
+    /// `{hints}` and `{points}`, when any hints were used.
+    pub hints_used: &'static str,
+    pub game_over_keys: &'static str,
+    /// `{cards}`, `{found}` and `{sets}`, in the "find them all" puzzle.
+    pub found_one: &'static str,
+    /// `{cards}`, claimed again in the "find them all" puzzle.
+    pub already_found: &'static str,
+    /// `{cards}` and `{last}`, the set found before, claimed out of order in a chain puzzle.
+    pub not_next: &'static str,
+    /// `{cards}`, `{found}` and `{sets}`, when no set left follows on from the one just found.
+    pub chain_stuck: &'static str,
+    /// Asking to deal or for a hint in the "find them all" puzzle.
+    pub board_stays: &'static str,
+    /// `{found}`, `{sets}` and `{command}`, on the game over screen of the puzzle.
+    pub found_all: &'static str,
+    pub quiz: &'static str,
+    /// `{guess}`, the count typed so far.
+    pub how_many_sets: &'static str,
+    pub board_hidden: &'static str,
+    /// `{sets}`, `{guess}` and `{points}`.
+    pub quiz_answer: &'static str,
+    /// `{points}`, `{max}`, `{exact}` and `{boards}`, on the game over screen of the quiz.
+    pub quiz_summary: &'static str,
+    /// Counters in the panel during the quiz.
+    pub board: &'static str,
+    pub points: &'static str,
+    /// Title of the board editor, and the keys to edit with.
+    pub editor: &'static str,
+    pub editor_keys: &'static str,
+    /// Counters in the panel while editing.
+    pub cards: &'static str,
+    pub sets: &'static str,
+    /// `{count}` and `{sets}`, every set on the board being edited.
+    pub editor_sets: &'static str,
+    /// Asking to play an edited board without a set.
+    pub no_set_to_play: &'static str,
+    /// `{player}`.
+    pub playing_as: &'static str,
+    pub no_other_players: &'static str,
+    /// `{player}`, when a finished game beats their best score.
+    pub new_best: &'static str,
+    /// `{players}` and `{last}`, asked before the game starts.
+    pub who_is_playing: &'static str,
+    pub paused: &'static str,
+    pub paused_keys: &'static str,
+    /// The keys while paused, when the game cannot be saved to carry on later.
+    pub paused_keys_unsaved: &'static str,
+    /// `{path}`, after quitting from the pause screen.
+    pub suspended: &'static str,
+    /// `{path}`, asked before the game starts.
+    pub resume: &'static str,
+    /// Answers to [`Strings::resume`] that mean yes, in lower case.
+    pub yes: &'static [&'static str],
+}
+
+impl Strings {
+    pub fn attribute(&self, attribute: Attribute) -> &'static str {
+        match attribute {
+            Attribute::Color => self.color,
+            Attribute::Count => self.count,
+            Attribute::Shade => self.shade,
+            Attribute::Shape => self.shape,
+        }
+    }
+}
+
+static ENGLISH: Strings = Strings {
+    color: "color",
+    count: "count",
+    shade: "shading",
+    shape: "shape",
+    mode_play: "PLAY",
+    mode_record: "RECORD",
+    mode_playback: "PLAYBACK",
+    mode_watch: "LIVE",
+    status: "seed {seed} | deck {deck} | score {score} | {time}",
+    is_a_set: "{cards} is a set!",
+    not_a_set: "{cards} is not a set, check the {attribute}",
+    deck_empty: "the deck is empty",
+    hint: "hint: {card} is in a set",
+    no_hints_left: "no hints left",
+    hint_cooling_down: "the next hint is ready in {seconds}s",
+    no_set_on_board: "there is no set on the board, press + to deal more",
+    all_sets_shown: "every set on the board has been shown already",
+    copied: "copied '{command}'",
+    saved: "saved {path}",
+    terminal_too_small: "Terminal too small",
+    panel: "Game",
+    deck: "Deck",
+    sets_found: "Sets found",
+    extra_deals: "Extra deals",
+    history: "Sets",
+    metrics: "Pace",
+    sets_per_minute: "Sets/min",
+    accuracy: "Accuracy",
+    time_per_set: "Per set",
+    hints: "Hints",
+    best: "Best",
+    page: "page {page}/{pages}",
+    game_over: "Game over",
+    finished: "Finished in {time} with {deck} cards left in the deck.",
+    play_again: "{board} cards were left on the board. Play this game again with '{command}'.",
+    score_summary: "Score {score}: sets {sets}, bonus {bonus}, misclaims {misclaims}.",
+    hints_used: "Hints used: {hints}, costing {points} points.",
+    game_over_keys: "r: replay this seed | n: new game | q: quit",
+    found_one: "{cards} is a set! {found}/{sets} found",
+    already_found: "{cards} has already been found",
+    not_next: "{cards} shares neither a card nor its alike attributes with {last}",
+    chain_stuck: "{cards} is a set, but no set left follows on from it. r: start the chain again",
+    board_stays: "the board stays as it is in this puzzle",
+    found_all: "Found {found} of {sets} sets. Play this board again with '{command}'.",
+    quiz: "Count the sets",
+    how_many_sets: "How many sets? Type the count and press Enter: {guess}",
+    board_hidden: "The board is hidden, how many sets were there?",
+    quiz_answer: "{sets} sets, you said {guess}: +{points}",
+    quiz_summary: "Scored {points} of {max} points, counting {exact} of {boards} boards exactly.",
+    board: "Board",
+    points: "Points",
+    editor: "Edit the board",
+    editor_keys: "c/n/f/s: next color/number/fill/shape (shifted: previous) | a: add a card | \
+        d: remove it | v: solve | y: copy | Enter: play | q: quit",
+    cards: "Cards",
+    sets: "Sets",
+    editor_sets: "{count} sets: {sets}",
+    no_set_to_play: "there is no set on the board to find",
+    playing_as: "playing as {player}",
+    no_other_players: "no other players, start with --player <name> to add one",
+    new_best: "a new best score for {player}!",
+    who_is_playing: "Who is playing? {players} [{last}] ",
+    paused: "Paused",
+    paused_keys: "Esc: carry on | q: save the game and quit",
+    paused_keys_unsaved: "Esc: carry on | q: end the game",
+    suspended: "Saved the game in {path}. Carry on with 'sgs --resume'.",
+    resume: "An unfinished game was saved in {path}. Resume it? [Y/n] ",
+    yes: &["y", "yes"],
+};
+
+static GERMAN: Strings = Strings {
+    color: "Farbe",
+    count: "Anzahl",
+    shade: "Füllung",
+    shape: "Form",
+    mode_play: "SPIEL",
+    mode_record: "AUFNAHME",
+    mode_playback: "WIEDERGABE",
+    mode_watch: "LIVE",
+    status: "Seed {seed} | Stapel {deck} | Punkte {score} | {time}",
+    is_a_set: "{cards} ist ein Set!",
+    not_a_set: "{cards} ist kein Set, prüfe die {attribute}",
+    deck_empty: "der Stapel ist leer",
+    hint: "Tipp: {card} gehört zu einem Set",
+    no_hints_left: "keine Tipps mehr",
+    hint_cooling_down: "der nächste Tipp ist in {seconds}s bereit",
+    no_set_on_board: "kein Set auf dem Tisch, + legt nach",
+    all_sets_shown: "jedes Set auf dem Tisch wurde schon gezeigt",
+    copied: "'{command}' kopiert",
+    saved: "{path} gespeichert",
+    terminal_too_small: "Terminal zu klein",
+    panel: "Spiel",
+    deck: "Stapel",
+    sets_found: "Gefundene Sets",
+    extra_deals: "Nachgelegt",
+    history: "Sets",
+    metrics: "Tempo",
+    sets_per_minute: "Sets/Min",
+    accuracy: "Trefferquote",
+    time_per_set: "Pro Set",
+    hints: "Tipps",
+    best: "Bestwert",
+    page: "Seite {page}/{pages}",
+    game_over: "Spielende",
+    finished: "Beendet in {time} mit {deck} Karten im Stapel.",
+    play_again: "{board} Karten lagen noch aus. Spiele diese Partie erneut mit '{command}'.",
+    score_summary: "Punkte {score}: Sets {sets}, Bonus {bonus}, Fehlgriffe {misclaims}.",
+    hints_used: "Tipps genutzt: {hints}, für {points} Punkte.",
+    game_over_keys: "r: diesen Seed wiederholen | n: neues Spiel | q: beenden",
+    found_one: "{cards} ist ein Set! {found}/{sets} gefunden",
+    already_found: "{cards} wurde schon gefunden",
+    not_next: "{cards} hat weder eine Karte noch die gleichen Merkmale mit {last} gemeinsam",
+    chain_stuck:
+        "{cards} ist ein Set, aber kein übriges Set schließt daran an. r: Kette neu beginnen",
+    board_stays: "die Auslage bleibt in diesem Rätsel, wie sie ist",
+    found_all: "{found} von {sets} Sets gefunden. Nochmal spielen mit '{command}'.",
+    quiz: "Sets zählen",
+    how_many_sets: "Wie viele Sets? Anzahl eingeben und Enter drücken: {guess}",
+    board_hidden: "Die Auslage ist verdeckt, wie viele Sets waren es?",
+    quiz_answer: "{sets} Sets, geschätzt {guess}: +{points}",
+    quiz_summary: "{points} von {max} Punkten, {exact} von {boards} Auslagen genau gezählt.",
+    board: "Auslage",
+    points: "Punkte",
+    editor: "Auslage bearbeiten",
+    editor_keys: "c/n/f/s: nächste Farbe/Anzahl/Füllung/Form (groß: vorige) | a: Karte dazu | \
+        d: entfernen | v: lösen | y: kopieren | Enter: spielen | q: beenden",
+    cards: "Karten",
+    sets: "Sets",
+    editor_sets: "{count} Sets: {sets}",
+    no_set_to_play: "in der Auslage gibt es kein Set zu finden",
+    playing_as: "{player} spielt",
+    no_other_players: "keine anderen Spieler, --player <Name> legt einen an",
+    new_best: "ein neuer Bestwert für {player}!",
+    who_is_playing: "Wer spielt? {players} [{last}] ",
+    paused: "Pause",
+    paused_keys: "Esc: weiterspielen | q: Spiel speichern und beenden",
+    paused_keys_unsaved: "Esc: weiterspielen | q: Spiel beenden",
+    suspended: "Das Spiel wurde in {path} gespeichert. Weiter mit 'sgs --resume'.",
+    resume: "Ein unbeendetes Spiel wurde in {path} gespeichert. Fortsetzen? [J/n] ",
+    yes: &["j", "ja", "y", "yes"],
+};
+
+/// Replace each `{name}` placeholder in `template` with its value.
+pub fn fill(template: &str, values: &[(&str, &dyn fmt::Display)]) -> String {
+    values
+        .iter()
+        .fold(template.to_owned(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
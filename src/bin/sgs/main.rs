@@ -0,0 +1,3676 @@
+mod autosave;
+mod cache;
+mod completions;
+mod config;
+mod deal;
+mod editor;
+mod error;
+mod locale;
+mod metrics;
+mod paths;
+mod profile;
+mod puzzle;
+mod report;
+mod schema;
+mod solve;
+mod stats;
+mod swap;
+mod verify;
+
+use crate::{
+    autosave::{Autosave, Recovery},
+    completions::CompletionsCommand,
+    config::{Config, ConfigCommand, Flags},
+    deal::DealCommand,
+    editor::Editor,
+    error::Error,
+    locale::{fill, Language, Strings},
+    metrics::Metrics,
+    profile::{GameResult, Player, Profiles},
+    puzzle::PuzzleCommand,
+    schema::SchemaCommand,
+    solve::SolveCommand,
+    stats::StatsCommand,
+    swap::SwapCommand,
+    verify::VerifyCommand,
+};
+use argh::{ArgsInfo, FromArgs};
+use crossterm::{
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
+    execute,
+    terminal::{
+        self as term, disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    },
+    ExecutableCommand,
+};
+use set_game_solver::{
+    Attribute, Card, CardProperties, CardRenderer, ChainPuzzle, Chained, ClaimError,
+    Color as CardColor, Count, CountQuiz, CountRules, Deck, FindAll, GameEvent, GameRecord,
+    GameState, HintError, HintRules, Hints, Move, PlayerId, Position, ScoringPolicy, Shade, Shape,
+    Start, StreakBonus, TimeBonus, TrackedDeck, Triple, BOARD_SIZE, SHUFFLE_VERSION,
+};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::VecDeque,
+    fmt, fs,
+    io::{self, BufRead, BufWriter, IsTerminal, Read, Seek, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+    sync::mpsc::Receiver,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tui::{
+    backend::{Backend, CrosstermBackend, TestBackend},
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction as LayoutDirection, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Paragraph, Widget, Wrap},
+    Frame, Terminal,
+};
+use unicode_width::UnicodeWidthStr;
+
+pub fn draw<B>(f: &mut Frame<B>, app: &mut App)
+where
+    B: Backend,
+{
+    let mut legend = Vec::new();
+    if app.settings.glyphs == Glyphs::Braille {
+        legend.push(BRAILLE_LEGEND);
+    }
+    if app.settings.monochrome {
+        legend.push(BRACKET_LEGEND);
+    }
+    let chunks = Layout::default()
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(legend.len().min(1) as u16),
+            Constraint::Length(1),
+        ])
+        .split(f.size());
+    // Only take room for the panel from boards that can spare it, unless asked for more of it
+    let asked = app.settings.show_history || app.settings.show_metrics;
+    let (main, panel) = if chunks[0].width >= MIN_WIDTH_WITH_PANEL || asked {
+        let columns = Layout::default()
+            .direction(LayoutDirection::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(PANEL_WIDTH)])
+            .split(chunks[0]);
+        (columns[0], Some(columns[1]))
+    } else {
+        (chunks[0], None)
+    };
+    if app.game_over.is_some() {
+        draw_game_over(f, app, main);
+    } else if app.paused.is_some() {
+        draw_paused(f, app, main);
+    } else if app.quiz.is_some() {
+        draw_quiz(f, app, main);
+    } else if app.editor.is_some() {
+        draw_editor(f, app, main);
+    } else {
+        draw_board(f, app, main);
+    }
+    if let Some(area) = panel {
+        draw_panel(f, app, area);
+    }
+    if !legend.is_empty() {
+        let text = Span::styled(legend.join(" |"), Style::default().fg(Color::DarkGray));
+        f.render_widget(Paragraph::new(Spans::from(text)), chunks[1]);
+    }
+    draw_status_bar(f, app, chunks[2]);
+    if app.settings.monochrome {
+        f.render_widget(StripColors, f.size());
+    }
+}
+
+const PANEL_WIDTH: u16 = 22;
+const MIN_WIDTH_WITH_PANEL: u16 = 60;
+
+/// Counters for the game so far, kept up to date from the moves the game reports.
+fn draw_panel<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let strings = app.strings();
+    let count =
+        |kind: fn(&Move) -> bool| app.moves.iter().filter(|(played, _)| kind(played)).count();
+    let sets = match &app.find_all {
+        Some(puzzle) => format!("{}/{}", puzzle.found().len(), puzzle.total()),
+        None => count(|played| matches!(played, Move::Set(_))).to_string(),
+    };
+    let deals = count(|played| matches!(played, Move::DealExtra));
+    let counters = match (&app.quiz, &app.editor) {
+        (_, Some(editor)) => vec![
+            (strings.cards, editor.cards.len().to_string()),
+            (strings.sets, editor.sets().len().to_string()),
+        ],
+        (Some(quiz), None) => vec![
+            (
+                strings.board,
+                format!(
+                    "{}/{}",
+                    (quiz.answers().len() + 1).min(quiz.boards()),
+                    quiz.boards()
+                ),
+            ),
+            (strings.points, quiz.points().to_string()),
+        ],
+        (None, None) => vec![
+            (strings.deck, app.game.deck().0.len().to_string()),
+            (strings.sets_found, sets),
+            (strings.extra_deals, deals.to_string()),
+        ],
+    };
+    let mut rest = match &app.settings.player {
+        Some(player) => {
+            let best = player
+                .best
+                .map_or_else(|| "-".to_owned(), |best| best.to_string());
+            let counters: Vec<_> = counters.into_iter().chain([(strings.best, best)]).collect();
+            draw_counters(f, area, &player.name, &counters)
+        }
+        None => draw_counters(f, area, strings.panel, &counters),
+    };
+    if app.settings.show_metrics {
+        let metrics = &app.metrics;
+        let none = || "-".to_owned();
+        let measures = [
+            (
+                strings.sets_per_minute,
+                metrics
+                    .sets_per_minute(app.elapsed())
+                    .map_or_else(none, |rate| format!("{rate:.1}")),
+            ),
+            (
+                strings.accuracy,
+                metrics
+                    .accuracy()
+                    .map_or_else(none, |accuracy| format!("{:.0}%", accuracy * 100.0)),
+            ),
+            (
+                strings.time_per_set,
+                metrics.time_per_set().map_or_else(none, mm_ss),
+            ),
+            (
+                strings.hints,
+                format!("{}/{}", app.hints.used(PLAYER), app.hints.rules().allowed),
+            ),
+        ];
+        rest = draw_counters(f, rest, strings.metrics, &measures);
+    }
+    if app.settings.show_history {
+        draw_history(f, app, rest);
+    }
+}
+
+/// A block of labelled values at the top of `area`, returning the area left beneath it.
+fn draw_counters<B>(f: &mut Frame<B>, area: Rect, title: &str, counters: &[(&str, String)]) -> Rect
+where
+    B: Backend,
+{
+    let inner = usize::from(area.width.saturating_sub(2));
+    let text: Vec<_> = counters
+        .iter()
+        .map(|(label, value)| {
+            let padding = inner.saturating_sub(label.width() + value.width());
+            Spans::from(format!("{label}{}{value}", " ".repeat(padding)))
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(title.to_owned(), panel_title()));
+    let height = area.height.min(text.len() as u16 + 2);
+    f.render_widget(Paragraph::new(text).block(block), Rect { height, ..area });
+    Rect {
+        y: area.y + height,
+        height: area.height - height,
+        ..area
+    }
+}
+
+fn panel_title() -> Style {
+    Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::BOLD)
+}
+
+/// Every set found so far, oldest first, each with the time it was found and its cards in
+/// miniature. The newest sets are shown unless the player has scrolled back.
+fn draw_history<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let visible = usize::from(area.height.saturating_sub(2));
+    let total = app.history.len();
+    app.history_scroll = app.history_scroll.min(total.saturating_sub(visible));
+    let end = total - app.history_scroll;
+    let start = end.saturating_sub(visible);
+    let renderer = CustomGlyphs {
+        glyphs: app.settings.glyphs,
+        symbols: &app.settings.symbols,
+        miniature: true,
+    };
+    let text: Vec<_> = app.history[start..end]
+        .iter()
+        .map(|found| {
+            let time = found.at.map_or_else(|| "--:--".to_owned(), mm_ss);
+            let mut spans = vec![Span::raw(time)];
+            for card in found.cards {
+                spans.push(Span::raw(" "));
+                spans.push(if app.settings.monochrome {
+                    Bracketed(renderer).render(card)
+                } else {
+                    renderer.render(card)
+                });
+            }
+            Spans::from(spans)
+        })
+        .collect();
+    let mut title = app.strings().history.to_owned();
+    if total > visible {
+        title = format!("{title} {}-{end}/{total}", start + 1);
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(title, panel_title()));
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// How [`Bracketed`] marks each color.
+const BRACKET_LEGEND: &str = " (R) [G] {P}";
+
+/// Removes every color from what has been drawn, so that the screen looks the same on a
+/// monochrome terminal. Backgrounds become reversed text, to keep e.g. the mode label legible.
+struct StripColors;
+
+impl Widget for StripColors {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                if cell.bg != Color::Reset {
+                    cell.modifier.insert(Modifier::REVERSED);
+                }
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+        }
+    }
+}
+
+/// Each row's dots for the first, second and third values, then the values in card notation.
+const BRAILLE_LEGEND: &str = " \u{2801}\u{2808}\u{2809} 1 2 3 | \u{2802}\u{2810}\u{2812} D S O | \
+    \u{2804}\u{2820}\u{2824} # ~ = | \u{2840}\u{2880}\u{28C0} R G P";
+
+fn draw_game_over<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let strings = app.strings();
+    let mut text = vec![
+        Spans::from(fill(
+            strings.finished,
+            &[("time", &app.clock()), ("deck", &app.game.deck().0.len())],
+        )),
+        Spans::from(fill(
+            strings.play_again,
+            &[
+                ("board", &app.board_len()),
+                ("command", &app.game_command()),
+            ],
+        )),
+    ];
+    let tally = app.game.tally(PLAYER);
+    // Puzzles are not scored, and have no hints
+    if let Some(quiz) = &app.quiz {
+        let exact = quiz
+            .answers()
+            .iter()
+            .filter(|answer| answer.guess == answer.sets)
+            .count();
+        text[1] = Spans::from(fill(
+            strings.quiz_summary,
+            &[
+                ("points", &quiz.points()),
+                ("max", &quiz.max_points()),
+                ("exact", &exact),
+                ("boards", &quiz.boards()),
+            ],
+        ));
+    } else if let Some(puzzle) = &app.find_all {
+        text[1] = Spans::from(fill(
+            strings.found_all,
+            &[
+                ("found", &puzzle.found().len()),
+                ("sets", &puzzle.total()),
+                ("command", &app.puzzle_command()),
+            ],
+        ));
+    } else {
+        text.push(Spans::from(fill(
+            strings.score_summary,
+            &[
+                ("score", &app.game.score(PLAYER)),
+                ("sets", &tally.sets),
+                ("bonus", &tally.bonus),
+                ("misclaims", &tally.misclaims),
+            ],
+        )));
+        let hints = app.hints.used(PLAYER);
+        if hints > 0 {
+            text.push(Spans::from(fill(
+                strings.hints_used,
+                &[("hints", &hints), ("points", &tally.penalty)],
+            )));
+        }
+    }
+    text.extend([Spans::from(""), Spans::from(strings.game_over_keys)]);
+    let chunks = Layout::default()
+        .constraints([
+            Constraint::Length(text.len() as u16 + 2),
+            Constraint::Min(0),
+        ])
+        .split(area);
+    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+        strings.game_over,
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    ));
+    f.render_widget(Paragraph::new(text).block(block), chunks[0]);
+    draw_board(f, app, chunks[1]);
+}
+
+/// Asks how many sets are on the board, showing it only briefly.
+fn draw_quiz<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let strings = app.strings();
+    let chunks = Layout::default()
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+        strings.quiz,
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    ));
+    let prompt = fill(
+        strings.how_many_sets,
+        &[("guess", &format!("{}_", app.guess))],
+    );
+    f.render_widget(Paragraph::new(prompt).block(block), chunks[0]);
+    if app.elapsed().saturating_sub(app.shown_at) < QUIZ_SHOWN_FOR {
+        draw_board(f, app, chunks[1]);
+    } else {
+        let block = Block::default().borders(Borders::ALL);
+        f.render_widget(Paragraph::new(strings.board_hidden).block(block), chunks[1]);
+    }
+}
+
+fn draw_editor<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let strings = app.strings();
+    // Room for the keys to wrap onto a second line
+    let chunks = Layout::default()
+        .constraints([Constraint::Length(4), Constraint::Min(0)])
+        .split(area);
+    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+        strings.editor,
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    ));
+    let keys = Paragraph::new(strings.editor_keys)
+        .block(block)
+        .wrap(Wrap { trim: true });
+    f.render_widget(keys, chunks[0]);
+    draw_board(f, app, chunks[1]);
+}
+
+/// Hides the board while paused, so the clock cannot be cheated.
+fn draw_paused<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let strings = app.strings();
+    let keys = if app.can_suspend() {
+        strings.paused_keys
+    } else {
+        strings.paused_keys_unsaved
+    };
+    let block = Block::default().borders(Borders::ALL).title(Span::styled(
+        strings.paused,
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    ));
+    f.render_widget(Paragraph::new(keys).block(block), area);
+}
+
+fn draw_board<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    // The content and any brackets, inside the tile borders
+    let brackets = if app.settings.monochrome { 2 } else { 0 };
+    let mut min_width = app.settings.glyphs.content_width() + brackets + 2;
+    // Room for a two digit title and a hint's mark between the corners
+    min_width = min_width.max(5);
+    if app.settings.show_notation {
+        // Never cut the notation short, even in the title of the shortest tiles, e.g. `11 2R~O`
+        min_width = min_width.max(9);
+    }
+    let count = app.board_len();
+    let layout = &app.settings.layout;
+    let paged = || {
+        // Keep a row for the page number, and show as many cards as fit on each page
+        let area = Rect {
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        (1..count)
+            .rev()
+            .find_map(|per_page| tile_layout(area, per_page, min_width as u16, layout))
+    };
+    match tile_layout(area, count, min_width as u16, layout).or_else(paged) {
+        Some(tiles) => {
+            let per_page = tiles.len().max(1);
+            let page = app.selected_card / per_page;
+            app.first_tile = page * per_page;
+            app.columns = tiles.iter().take_while(|tile| tile.y == tiles[0].y).count();
+            let tiles: Vec<_> = tiles
+                .into_iter()
+                .take(count.saturating_sub(app.first_tile))
+                .collect();
+            if per_page < count {
+                let pages = count.div_ceil(per_page);
+                let text = fill(
+                    app.strings().page,
+                    &[("page", &(page + 1)), ("pages", &pages)],
+                );
+                let row = Rect {
+                    y: area.bottom() - 1,
+                    height: 1,
+                    ..area
+                };
+                let text = Span::styled(text, Style::default().fg(Color::DarkGray));
+                f.render_widget(Paragraph::new(Spans::from(text)), row);
+            }
+            app.tiles = tiles.clone();
+            draw_cards(f, app, tiles)
+        }
+        None => {
+            app.first_tile = 0;
+            app.tiles.clear();
+            let message = Paragraph::new(app.strings().terminal_too_small)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            f.render_widget(message, area);
+        }
+    }
+}
+
+/// Arrange `count` card tiles within `area`, or `None` if they cannot fit legibly.
+///
+/// Follows the chosen grid if it fits, and otherwise picks the column count giving the largest
+/// tiles, preferring three columns when tied.
+fn tile_layout(
+    area: Rect,
+    count: usize,
+    min_width: u16,
+    layout: &BoardLayout,
+) -> Option<Vec<Rect>> {
+    const MIN_CARD_HEIGHT: u16 = 3;
+    const PREFERRED_COLUMNS: u16 = 3;
+    let (card_width, card_height) = match layout.tile_size {
+        TileSize::Compact => (min_width, MIN_CARD_HEIGHT),
+        TileSize::Normal => (min_width.max(12), 5),
+        TileSize::Large => (min_width.max(16), 7),
+    };
+    let gap = layout.gap;
+
+    let count = u16::try_from(count).ok()?;
+    let fit = |columns: u16| {
+        let rows = count.div_ceil(columns).max(1);
+        let width = (area.width.saturating_sub(gap * (columns - 1)) / columns).min(card_width);
+        let height = (area.height.saturating_sub(gap * (rows - 1)) / rows).min(card_height);
+        (width >= min_width && height >= MIN_CARD_HEIGHT).then_some((columns, width, height))
+    };
+    let chosen = match layout.grid {
+        Grid::Auto => None,
+        Grid::Wide => Some(count.div_ceil(3)),
+        Grid::Tall => Some(3),
+        Grid::Row => Some(count),
+    };
+    let columns = chosen
+        .map(|columns| columns.clamp(1, count.max(1)))
+        .and_then(fit)
+        .or_else(|| {
+            (1..=count.max(1))
+                .filter_map(fit)
+                .max_by_key(|(columns, width, height)| {
+                    (
+                        width * height,
+                        std::cmp::Reverse(columns.abs_diff(PREFERRED_COLUMNS)),
+                    )
+                })
+        });
+    let (columns, width, height) = columns?;
+    Some(
+        (0..count)
+            .map(|i| Rect {
+                x: area.x + (i % columns) * (width + gap),
+                y: area.y + (i / columns) * (height + gap),
+                width,
+                height,
+            })
+            .collect(),
+    )
+}
+
+fn draw_status_bar<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let strings = app.strings();
+    let mut spans = vec![
+        Span::styled(
+            format!(" {} ", app.mode.label(strings)),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::from(format!(
+            " {}",
+            fill(
+                strings.status,
+                &[
+                    ("seed", &app.seed),
+                    ("deck", &app.game.deck().0.len()),
+                    ("score", &app.game.score(PLAYER)),
+                    ("time", &app.clock()),
+                ],
+            )
+        )),
+    ];
+    if let Some(message) = &app.status_message {
+        spans.push(Span::from(" | "));
+        spans.push(Span::styled(
+            message.as_str(),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    f.render_widget(Paragraph::new(Spans::from(spans)), area);
+}
+
+fn draw_cards<B>(f: &mut Frame<B>, app: &mut App, tiles: Vec<Rect>)
+where
+    B: Backend,
+{
+    let cards = app.arrangement.iter().enumerate().skip(app.first_tile);
+    for (area, (i, card)) in tiles.into_iter().zip(cards) {
+        // Each highlight has its own border too, so they differ without color
+        let highlight = if app.game_over.is_some() {
+            None
+        } else if app.dragged_cards.contains(&i) {
+            Some((Color::Cyan, BorderType::Double))
+        } else if i == app.selected_card {
+            Some((Color::Yellow, BorderType::Thick))
+        } else if app.hovered_card == Some(i) {
+            Some((Color::Blue, BorderType::Rounded))
+        } else {
+            None
+        };
+        let tile = Tile {
+            card,
+            // Marked without color too, for monochrome terminals
+            title: if app.hinted.contains(card) {
+                format!("{i}?")
+            } else {
+                i.to_string()
+            },
+            highlight,
+            caption: app.settings.show_notation.then(|| card.to_string()),
+        };
+        let renderer = CustomGlyphs {
+            glyphs: app.settings.glyphs,
+            symbols: &app.settings.symbols,
+            miniature: false,
+        };
+        if app.settings.monochrome {
+            draw_card(f, area, tile, &Bracketed(renderer));
+        } else {
+            draw_card(f, area, tile, &renderer);
+        }
+    }
+}
+
+/// A card as it should appear on the board.
+struct Tile<'a> {
+    card: &'a Card,
+    title: String,
+    /// Border color and type, if the tile should stand out.
+    highlight: Option<(Color, BorderType)>,
+    /// Extra text shown beneath the symbols, space permitting.
+    caption: Option<String>,
+}
+
+fn draw_card<B>(
+    f: &mut Frame<B>,
+    area: Rect,
+    tile: Tile,
+    renderer: &impl CardRenderer<Output = Span<'static>>,
+) where
+    B: Backend,
+{
+    let Tile {
+        card,
+        title,
+        highlight,
+        caption,
+    } = tile;
+    let inner_height = area.height.saturating_sub(2);
+    // Fall back to showing the caption in the title if there's no room below the symbols
+    let (title, caption) = match caption {
+        Some(caption) if inner_height < 2 => (format!("{title} {caption}"), None),
+        caption => (title, caption),
+    };
+    let mut block = Block::default().borders(Borders::ALL).title(Span::styled(
+        title,
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    ));
+    if let Some((color, border)) = highlight {
+        block = block
+            .border_type(border)
+            .border_style(Style::default().fg(color));
+    }
+    // Vertically center the symbols and caption within the tile's borders
+    let lines = 1 + u16::from(caption.is_some());
+    let padding = inner_height.saturating_sub(lines) / 2;
+    let mut text = vec![Spans::from(""); padding.into()];
+    text.push(Spans::from(renderer.render(*card)));
+    if let Some(caption) = caption {
+        text.push(Spans::from(Span::styled(
+            caption,
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .alignment(Alignment::Center);
+    f.render_widget(paragraph, area);
+}
+
+/// The set of symbols used to draw shapes on cards.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Glyphs {
+    /// Geometric shapes from Unicode, one column wide each.
+    Unicode,
+    /// The shade and shape from the card notation, e.g. `~O` for a striped oval.
+    Ascii,
+    /// The whole card in one braille cell, for small terminals and braille displays.
+    ///
+    /// Each row of dots is an attribute: count, shape, shading and color from the top. The left
+    /// dot is the attribute's first value, the right dot its second, and both dots its third.
+    Braille,
+}
+
+impl Glyphs {
+    /// Pick the richest glyphs the current terminal can be expected to render.
+    ///
+    /// Falls back to ASCII on the Windows console host, the Linux virtual console, non-UTF-8
+    /// locales, and locales where the Unicode glyphs would be drawn double width.
+    pub fn detect() -> Self {
+        let env = |key| std::env::var(key).unwrap_or_default();
+        let locale = [env("LC_ALL"), env("LC_CTYPE"), env("LANG")]
+            .into_iter()
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        let utf8 = cfg!(windows) || {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        };
+        let east_asian = ["zh", "ja", "ko"]
+            .iter()
+            .any(|prefix| locale.starts_with(prefix));
+        let conhost = cfg!(windows) && std::env::var_os("WT_SESSION").is_none();
+        let linux_console = env("TERM") == "linux";
+        if !utf8 || conhost || linux_console || Self::Unicode.width(east_asian) != 1 {
+            Self::Ascii
+        } else {
+            Self::Unicode
+        }
+    }
+
+    pub fn symbol(&self, shape: Shape, shade: Shade) -> &'static str {
+        match self {
+            Self::Unicode => match shape {
+                Shape::Diamond => match shade {
+                    Shade::Solid => "\u{25C6}",
+                    Shade::Striped => "\u{2B16}",
+                    Shade::Open => "\u{25C7}",
+                },
+                Shape::Oval => match shade {
+                    Shade::Solid => "\u{25CF}",
+                    Shade::Striped => "\u{25D0}",
+                    Shade::Open => "\u{25CB}",
+                },
+                Shape::Squiggle => match shade {
+                    Shade::Solid => "\u{29D3}",
+                    Shade::Striped => "\u{29D1}",
+                    Shade::Open => "\u{22C8}",
+                },
+            },
+            // Only the shape and shading rows, as the other rows do not depend on them
+            Self::Braille => match shape {
+                Shape::Diamond => match shade {
+                    Shade::Solid => "\u{2806}",
+                    Shade::Striped => "\u{2822}",
+                    Shade::Open => "\u{2826}",
+                },
+                Shape::Squiggle => match shade {
+                    Shade::Solid => "\u{2814}",
+                    Shade::Striped => "\u{2830}",
+                    Shade::Open => "\u{2834}",
+                },
+                Shape::Oval => match shade {
+                    Shade::Solid => "\u{2816}",
+                    Shade::Striped => "\u{2832}",
+                    Shade::Open => "\u{2836}",
+                },
+            },
+            Self::Ascii => match shape {
+                Shape::Diamond => match shade {
+                    Shade::Solid => "#D",
+                    Shade::Striped => "~D",
+                    Shade::Open => "=D",
+                },
+                Shape::Oval => match shade {
+                    Shade::Solid => "#O",
+                    Shade::Striped => "~O",
+                    Shade::Open => "=O",
+                },
+                Shape::Squiggle => match shade {
+                    Shade::Solid => "#S",
+                    Shade::Striped => "~S",
+                    Shade::Open => "=S",
+                },
+            },
+        }
+    }
+
+    /// The widest a card's content is drawn, in terminal columns.
+    pub fn content_width(&self) -> usize {
+        match self {
+            Self::Braille => self.width(false),
+            Self::Unicode | Self::Ascii => 3 * self.width(false) + 2,
+        }
+    }
+
+    /// The widest any symbol is drawn, in terminal columns.
+    pub fn width(&self, east_asian: bool) -> usize {
+        Shape::iter()
+            .flat_map(|shape| Shade::iter().map(move |shade| (shape, shade)))
+            .map(|(shape, shade)| {
+                let symbol = self.symbol(shape, shade);
+                if east_asian {
+                    symbol.width_cjk()
+                } else {
+                    symbol.width()
+                }
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Another renderer's symbols in brackets that show the card's color: `()` for red, `[]` for
+/// green and `{}` for purple, for monochrome terminals and players who cannot tell the colors
+/// apart.
+struct Bracketed<R>(R);
+
+impl<R: CardRenderer<Output = Span<'static>>> CardRenderer for Bracketed<R> {
+    type Output = Span<'static>;
+
+    fn render(&self, card: Card) -> Span<'static> {
+        let (open, close) = match card.color() {
+            CardColor::Red => ('(', ')'),
+            CardColor::Green => ('[', ']'),
+            CardColor::Purple => ('{', '}'),
+        };
+        let span = self.0.render(card);
+        Span::styled(format!("{open}{}{close}", span.content), span.style)
+    }
+}
+
+/// One symbol per shape on the card, in the card's color.
+impl CardRenderer for Glyphs {
+    type Output = Span<'static>;
+
+    fn render(&self, card: Card) -> Span<'static> {
+        CustomGlyphs {
+            glyphs: *self,
+            symbols: &Symbols::default(),
+            miniature: false,
+        }
+        .render(card)
+    }
+}
+
+/// [`Glyphs`] with the player's own [`Symbols`] in place of some of them.
+#[derive(Clone, Copy)]
+struct CustomGlyphs<'a> {
+    glyphs: Glyphs,
+    symbols: &'a Symbols,
+    /// Draw the count as a digit before a single symbol, e.g. to list cards in a narrow panel.
+    miniature: bool,
+}
+
+impl CardRenderer for CustomGlyphs<'_> {
+    type Output = Span<'static>;
+
+    fn render(&self, card: Card) -> Span<'static> {
+        let CardProperties {
+            color,
+            count,
+            shade,
+            shape,
+        } = CardProperties::from(card);
+        let symbol = self
+            .symbols
+            .get(shape, shade)
+            .unwrap_or_else(|| self.glyphs.symbol(shape, shade));
+        let text = match (self.glyphs, count) {
+            (Glyphs::Braille, _) => braille(card).to_string(),
+            (_, count) if self.miniature => format!("{count}{symbol}"),
+            (_, Count::One) => symbol.to_string(),
+            (_, Count::Two) => format!("{symbol} {symbol}"),
+            (_, Count::Three) => format!("{symbol} {symbol} {symbol}"),
+        };
+        let color = match color {
+            CardColor::Red => Color::Red,
+            CardColor::Green => Color::Green,
+            CardColor::Purple => Color::Magenta,
+        };
+        Span::styled(text, Style::default().fg(color))
+    }
+}
+
+/// Symbols chosen in the config file for some shape and shade combinations, e.g. because the
+/// terminal's font lacks the Unicode ones. Braille glyphs ignore them, as they draw whole cards.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Symbols([[Option<String>; 3]; 3]);
+
+impl Symbols {
+    pub fn get(&self, shape: Shape, shade: Shade) -> Option<&str> {
+        self.0[shape as usize][shade as usize].as_deref()
+    }
+
+    /// Read symbols keyed by shade and shape in card notation, e.g. `"#D" = "*"`. Each must be
+    /// one column wide, so that tiles line up.
+    pub fn from_table<'a>(
+        table: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, String> {
+        let mut symbols = Self::default();
+        for (key, symbol) in table {
+            let (shape, shade) = Shape::iter()
+                .flat_map(|shape| Shade::iter().map(move |shade| (shape, shade)))
+                .find(|(shape, shade)| format!("{shade}{shape}") == key)
+                .ok_or_else(|| {
+                    format!("unknown symbol '{key}', expected a shade and shape such as '#D'")
+                })?;
+            let width = symbol.width();
+            if width != 1 {
+                return Err(format!(
+                    "symbol for '{key}' must be one column wide, got '{symbol}' ({width} columns)"
+                ));
+            }
+            symbols.0[shape as usize][shade as usize] = Some(symbol.to_owned());
+        }
+        Ok(symbols)
+    }
+}
+
+/// Renders as the entries of a TOML table.
+impl fmt::Display for Symbols {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for shape in Shape::iter() {
+            for shade in Shade::iter() {
+                if let Some(symbol) = self.get(shape, shade) {
+                    let symbol = toml::Value::String(symbol.to_owned());
+                    writeln!(f, "\"{shade}{shape}\" = {symbol}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The card's braille cell, as described for [`Glyphs::Braille`].
+fn braille(card: Card) -> char {
+    // The dots of each row, left then right, as bits of the Unicode braille pattern
+    const ROWS: [(Attribute, u32, u32); 4] = [
+        (Attribute::Count, 0x01, 0x08),
+        (Attribute::Shape, 0x02, 0x10),
+        (Attribute::Shade, 0x04, 0x20),
+        (Attribute::Color, 0x40, 0x80),
+    ];
+    let dots = ROWS
+        .iter()
+        .map(|(attribute, left, right)| match attribute.index(card) {
+            0 => *left,
+            1 => *right,
+            _ => left | right,
+        })
+        .fold(0, |dots, row| dots | row);
+    char::from_u32(0x2800 + dots).unwrap_or(' ')
+}
+
+impl fmt::Display for Glyphs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unicode => "unicode",
+            Self::Ascii => "ascii",
+            Self::Braille => "braille",
+        })
+    }
+}
+
+impl FromStr for Glyphs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unicode" => Ok(Self::Unicode),
+            "ascii" => Ok(Self::Ascii),
+            "braille" => Ok(Self::Braille),
+            _ => Err(format!(
+                "unknown glyphs '{s}', expected 'unicode', 'ascii' or 'braille'"
+            )),
+        }
+    }
+}
+
+/// A game event worth drawing the player's attention to.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Notification {
+    Accepted,
+    Rejected,
+    ExtraDeal,
+}
+
+/// How many times to ring the terminal bell for each notification.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Bells {
+    pub accepted: u8,
+    pub rejected: u8,
+    pub extra_deal: u8,
+}
+
+impl Bells {
+    pub fn quiet() -> Self {
+        Self {
+            accepted: 0,
+            rejected: 0,
+            extra_deal: 0,
+        }
+    }
+
+    pub fn count(&self, notification: Notification) -> u8 {
+        match notification {
+            Notification::Accepted => self.accepted,
+            Notification::Rejected => self.rejected,
+            Notification::ExtraDeal => self.extra_deal,
+        }
+    }
+}
+
+impl Default for Bells {
+    fn default() -> Self {
+        Self {
+            accepted: 1,
+            rejected: 2,
+            extra_deal: 1,
+        }
+    }
+}
+
+impl fmt::Display for Bells {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "accepted={},rejected={},deal={}",
+            self.accepted, self.rejected, self.extra_deal
+        )
+    }
+}
+
+/// How to arrange and size the card tiles on the board.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct BoardLayout {
+    pub grid: Grid,
+    pub tile_size: TileSize,
+    /// Blank columns and rows between tiles.
+    pub gap: u16,
+}
+
+/// The shape of the grid of tiles, for terminals and streaming setups of different shapes.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Grid {
+    /// Whichever fits the largest tiles, preferring three columns.
+    #[default]
+    Auto,
+    /// Three rows, e.g. 3 by 4 for twelve cards.
+    Wide,
+    /// Three columns, e.g. 4 by 3 for twelve cards.
+    Tall,
+    /// Every card in a single row.
+    Row,
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Auto => "auto",
+            Self::Wide => "wide",
+            Self::Tall => "tall",
+            Self::Row => "row",
+        })
+    }
+}
+
+impl FromStr for Grid {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "wide" => Ok(Self::Wide),
+            "tall" => Ok(Self::Tall),
+            "row" => Ok(Self::Row),
+            _ => Err(format!(
+                "unknown grid '{s}', expected 'auto', 'wide', 'tall' or 'row'"
+            )),
+        }
+    }
+}
+
+/// The largest a tile is drawn, as tiles shrink to fit the terminal.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum TileSize {
+    /// Just the borders around the symbols.
+    Compact,
+    #[default]
+    Normal,
+    Large,
+}
+
+impl fmt::Display for TileSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Compact => "compact",
+            Self::Normal => "normal",
+            Self::Large => "large",
+        })
+    }
+}
+
+impl FromStr for TileSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "compact" => Ok(Self::Compact),
+            "normal" => Ok(Self::Normal),
+            "large" => Ok(Self::Large),
+            _ => Err(format!(
+                "unknown tile size '{s}', expected 'compact', 'normal' or 'large'"
+            )),
+        }
+    }
+}
+
+/// Ways of scoring a game, as different groups of players score differently.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Scoring {
+    /// A point for each set, as in the card game.
+    #[default]
+    Classic,
+    /// A bonus point for each set found within ten seconds of the last.
+    Speed,
+    /// A point off for each claim that is not a set.
+    Strict,
+    /// A bonus point for each set from the third in a row without a misclaim.
+    Streak,
+}
+
+impl Scoring {
+    pub fn policy(self) -> ScoringPolicy {
+        match self {
+            Self::Classic => ScoringPolicy::default(),
+            Self::Speed => ScoringPolicy {
+                time_bonus: Some(TimeBonus {
+                    within: Duration::from_secs(10),
+                    points: 1,
+                }),
+                ..ScoringPolicy::default()
+            },
+            Self::Strict => ScoringPolicy {
+                misclaim: 1,
+                ..ScoringPolicy::default()
+            },
+            Self::Streak => ScoringPolicy {
+                streak_bonus: Some(StreakBonus {
+                    after: 2,
+                    points: 1,
+                }),
+                ..ScoringPolicy::default()
+            },
+        }
+    }
+}
+
+impl fmt::Display for Scoring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Classic => "classic",
+            Self::Speed => "speed",
+            Self::Strict => "strict",
+            Self::Streak => "streak",
+        })
+    }
+}
+
+impl FromStr for Scoring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" => Ok(Self::Classic),
+            "speed" => Ok(Self::Speed),
+            "strict" => Ok(Self::Strict),
+            "streak" => Ok(Self::Streak),
+            _ => Err(format!(
+                "unknown scoring '{s}', expected 'classic', 'speed', 'strict' or 'streak'"
+            )),
+        }
+    }
+}
+
+/// Comma separated overrides of the defaults, e.g. `accepted=1,rejected=0,deal=3`.
+impl FromStr for Bells {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bells = Self::default();
+        for part in s.split(',').filter(|part| !part.is_empty()) {
+            let (event, count) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected '<event>=<count>', got '{part}'"))?;
+            let count = count
+                .parse()
+                .map_err(|_| format!("invalid bell count '{count}'"))?;
+            match event {
+                "accepted" => bells.accepted = count,
+                "rejected" => bells.rejected = count,
+                "deal" => bells.extra_deal = count,
+                _ => {
+                    return Err(format!(
+                        "unknown event '{event}', expected 'accepted', 'rejected' or 'deal'"
+                    ))
+                }
+            }
+        }
+        Ok(bells)
+    }
+}
+
+/// Settings that persist across restarts within a session.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub glyphs: Glyphs,
+    /// The player's replacements for some of the glyphs.
+    pub symbols: Symbols,
+    pub layout: BoardLayout,
+    pub bells: Bells,
+    /// Show each card's notation and board position on its tile.
+    pub show_notation: bool,
+    /// Draw without color, bracketing each card's symbols to show its color instead.
+    pub monochrome: bool,
+    /// List the sets found so far beside the board.
+    pub show_history: bool,
+    /// Show how quickly and accurately sets are being found beside the board.
+    pub show_metrics: bool,
+    /// Play the "find them all" puzzle on the opening board, rather than a game.
+    pub find_all: bool,
+    /// Make the "find them all" puzzle a chain, where each set found must follow on from the
+    /// one before, on the first board from the seed on that allows it.
+    pub chain: bool,
+    /// Play a quiz on counting the sets on boards, rather than a game.
+    pub count_sets: bool,
+    /// Edit the opening board rather than play a game.
+    pub edit: bool,
+    /// How to open other than with the seed's opening board.
+    pub opening: Option<Opening>,
+    /// How many hints each game allows, and their price.
+    pub hints: HintRules,
+    pub scoring: Scoring,
+    /// Whose profile finished games are kept in, if anyone's.
+    pub player: Option<Player>,
+    /// Where generated files such as snapshots are written.
+    pub data_dir: PathBuf,
+    pub language: Language,
+}
+
+/// An opening chosen by the player rather than dealt from the seed.
+#[derive(Debug, Clone)]
+pub enum Opening {
+    /// These cards on the board, then the rest of the seed's deck.
+    Board(Vec<Card>),
+    /// A position read from this file, which deals its own deck.
+    Position(PathBuf, Position),
+}
+
+impl Opening {
+    /// The flag and its value to open the same way again, e.g. `--board 1R#D,2G~S,3P=O`.
+    fn flag(&self) -> String {
+        match self {
+            Self::Board(cards) => format!("--board {}", format_board(cards)),
+            Self::Position(path, _) => format!("--position {}", path.display()),
+        }
+    }
+}
+
+impl Settings {
+    /// The flag to play the same kind of puzzle again, e.g. `chain`.
+    fn puzzle_flag(&self) -> &'static str {
+        if self.chain {
+            "chain"
+        } else {
+            "find-all"
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            glyphs: Glyphs::detect(),
+            symbols: Symbols::default(),
+            layout: BoardLayout::default(),
+            bells: Bells::default(),
+            show_notation: false,
+            monochrome: false,
+            show_history: false,
+            show_metrics: false,
+            find_all: false,
+            chain: false,
+            count_sets: false,
+            edit: false,
+            opening: None,
+            hints: HintRules::default(),
+            scoring: Scoring::default(),
+            player: None,
+            data_dir: paths::data_dir(),
+            language: Language::detect(),
+        }
+    }
+}
+
+/// Output format for a board snapshot.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SnapshotFormat {
+    /// Aligned plain text, suitable for pasting into bug reports.
+    Plain,
+    /// Plain text with ANSI color escapes, for viewing with `cat` or `less -R`.
+    Ansi,
+}
+
+impl SnapshotFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Plain => "txt",
+            Self::Ansi => "ans",
+        }
+    }
+}
+
+/// How the current session is being driven.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Mode {
+    Play,
+    Record,
+    Playback,
+    /// Following a recording as it is made, e.g. to cast a game played elsewhere.
+    Watch,
+}
+
+impl Mode {
+    fn label(&self, strings: &Strings) -> &'static str {
+        match self {
+            Self::Play => strings.mode_play,
+            Self::Record => strings.mode_record,
+            Self::Playback => strings.mode_playback,
+            Self::Watch => strings.mode_watch,
+        }
+    }
+}
+
+/// The one player at the terminal.
+const PLAYER: PlayerId = PlayerId(0);
+
+/// A set found during the game.
+#[derive(Debug, Clone, Copy)]
+pub struct FoundSet {
+    pub cards: [Card; 3],
+    /// How long into the game it was found, if known.
+    pub at: Option<Duration>,
+}
+
+/// The time into a session, which everything timed in the game reads so that a recording replays
+/// exactly: real time while playing, or held at the time each input was made while it is handled.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionClock {
+    /// When the session started.
+    origin: Instant,
+    /// A moment in real time, and the time into the session then, to run on from.
+    anchor: (Instant, Duration),
+    held: Option<Duration>,
+}
+
+impl SessionClock {
+    fn new() -> Self {
+        let origin = Instant::now();
+        Self {
+            origin,
+            anchor: (origin, Duration::ZERO),
+            held: None,
+        }
+    }
+
+    /// The time into the session, to the millisecond, as recordings keep it.
+    pub fn time(&self) -> Duration {
+        let time = self
+            .held
+            .unwrap_or_else(|| self.anchor.1 + self.anchor.0.elapsed());
+        Duration::from_millis(time.as_millis() as u64)
+    }
+
+    pub fn now(&self) -> Instant {
+        self.origin + self.time()
+    }
+
+    /// Stop the clock at a time into the session, e.g. while an input made then is handled.
+    pub fn hold(&mut self, at: Duration) {
+        self.held = Some(at);
+    }
+
+    /// Run in real time again, as if never held.
+    pub fn release(&mut self) {
+        self.held = None;
+    }
+
+    /// Run in real time again from the time held, e.g. once a replay catches up.
+    pub fn carry_on(&mut self) {
+        if let Some(at) = self.held.take() {
+            self.anchor = (Instant::now(), at);
+        }
+    }
+}
+
+/// A duration as `mm:ss`.
+fn mm_ss(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+pub struct App<'a> {
+    pub title: &'a str,
+    pub seed: u64,
+    pub mode: Mode,
+    pub settings: Settings,
+    pub clock: SessionClock,
+    /// When the game's clock started, by the session's clock.
+    pub started: Instant,
+    pub status_message: Option<String>,
+    pub game: GameState,
+    /// Everything that happens in the game, to log and announce.
+    events: Receiver<GameEvent>,
+    /// Every move so far and how long into the game it was made, to save the game with.
+    moves: Vec<(Move, Duration)>,
+    pub hints: Hints,
+    /// The puzzle being played instead of a game, if any, whose board never changes.
+    pub find_all: Option<FindAll>,
+    /// The quiz being played instead of a game, if any.
+    pub quiz: Option<CountQuiz>,
+    /// The count typed so far for the quiz's board.
+    pub guess: String,
+    /// When the quiz's board was first shown.
+    pub shown_at: Duration,
+    /// The board being edited instead of playing, if any.
+    pub editor: Option<Editor>,
+    /// Cards pointed out by hints that are still on the board.
+    pub hinted: Vec<Card>,
+    /// The cards on the board in the order they are shown, which may be shuffled. Positions
+    /// such as the selected card are places in this order.
+    arrangement: Vec<Card>,
+    /// How many times the board has been shuffled.
+    shuffles: u64,
+    /// Every set found so far, to list beside the board.
+    history: Vec<FoundSet>,
+    /// How many sets back from the newest the history has been scrolled.
+    pub history_scroll: usize,
+    pub metrics: Metrics,
+    pub selected_card: usize,
+    /// Number of tiles per row in the most recently drawn board.
+    pub columns: usize,
+    /// Where each card was drawn on screen, for hit testing the mouse.
+    pub tiles: Vec<Rect>,
+    /// The position of the first card drawn, when the board is split into pages.
+    pub first_tile: usize,
+    pub hovered_card: Option<usize>,
+    /// Cards marked by dragging the mouse across them, to be claimed as a set.
+    pub dragged_cards: Vec<usize>,
+    /// Events since the last frame, waiting to be announced.
+    pub notifications: Vec<Notification>,
+    pub keymap: Keymap,
+    pub should_quit: bool,
+    /// Set to the length of the game once it has ended.
+    pub game_over: Option<Duration>,
+    /// Set to the time on the clock while the game is paused.
+    pub paused: Option<Duration>,
+    /// Set when quitting from the pause screen, to carry on the game next time.
+    pub suspended: bool,
+    pub snapshot_requested: Option<SnapshotFormat>,
+    /// Set to play as the next player with a profile.
+    pub switch_player_requested: bool,
+    /// Whether the finished game has been kept in the player's profile.
+    pub result_saved: bool,
+    /// Text to be copied to the system clipboard by the terminal.
+    pub clipboard_requested: Option<String>,
+}
+
+impl<'a> App<'a> {
+    pub fn new(seed: u64, settings: Settings) -> App<'a> {
+        let seed = if settings.chain {
+            chain_seed(seed)
+        } else {
+            seed
+        };
+        let deck = Deck::new_from_seed(seed);
+        let game = match &settings.opening {
+            Some(Opening::Board(cards)) => {
+                let mut deck = TrackedDeck::new(deck);
+                for card in cards {
+                    deck.remove(*card);
+                }
+                GameState::from_board(cards.clone().into(), deck.into())
+            }
+            Some(Opening::Position(_, position)) => position.clone().into_game(),
+            None => GameState::new(deck),
+        };
+        let mut game = game.with_scoring(settings.scoring.policy());
+        let editor = settings
+            .edit
+            .then(|| Editor::new(game.board().cards().to_vec()));
+        let arrangement = match &editor {
+            Some(editor) => editor.cards.clone(),
+            None => game.board().cards().to_vec(),
+        };
+        let clock = SessionClock::new();
+        App {
+            seed,
+            mode: Mode::Play,
+            hints: Hints::new(settings.hints),
+            find_all: settings.find_all.then(|| {
+                let puzzle = FindAll::new(game.board().cards().to_vec());
+                if settings.chain {
+                    puzzle.with_constraint(Chained)
+                } else {
+                    puzzle
+                }
+            }),
+            quiz: settings.count_sets.then(|| quiz_boards(seed)),
+            guess: String::new(),
+            shown_at: Duration::ZERO,
+            editor,
+            hinted: Vec::new(),
+            settings,
+            started: clock.now(),
+            clock,
+            status_message: None,
+            events: game.subscribe(),
+            game,
+            moves: Vec::new(),
+            arrangement,
+            shuffles: 0,
+            history: Vec::new(),
+            history_scroll: 0,
+            metrics: Metrics::default(),
+            selected_card: 0,
+            columns: 1,
+            tiles: Vec::new(),
+            first_tile: 0,
+            hovered_card: None,
+            dragged_cards: Vec::new(),
+            notifications: Vec::new(),
+            keymap: Keymap::default(),
+            should_quit: false,
+            game_over: None,
+            paused: None,
+            suspended: false,
+            snapshot_requested: None,
+            switch_player_requested: false,
+            result_saved: false,
+            clipboard_requested: None,
+            title: "foo",
+        }
+    }
+
+    /// Pick up a game where it was left, by replaying its moves, paused if it was suspended.
+    pub fn resume(recovery: &Recovery, mut settings: Settings) -> Result<App<'a>, String> {
+        let Recovery {
+            record, elapsed, ..
+        } = recovery;
+        let Start::Seed(seed) = record.start else {
+            return Err("only games dealt from a seed can be resumed".to_owned());
+        };
+        // The game carries on under the rules it was started with, where they were saved
+        settings.scoring = recovery.scoring.unwrap_or(settings.scoring);
+        settings.hints = recovery.hints.unwrap_or(settings.hints);
+        let mut app = App::new(seed, settings);
+        // Older recovery files do not say when each move was made
+        let timed = recovery.times.len() == record.moves.len();
+        if !timed {
+            app.metrics = Metrics::since(*elapsed);
+        }
+        // Misclaims are not kept, so a resumed game's score loses their penalties
+        for (number, played) in record.moves.iter().enumerate() {
+            let at = timed.then(|| recovery.times[number]);
+            match played {
+                Move::Set(cards @ [a, b, c]) => {
+                    let triple = Triple::from((*a, *b, *c));
+                    match at {
+                        Some(at) => app.game.try_claim_at(PLAYER, triple, at),
+                        None => app.game.try_claim(PLAYER, triple),
+                    }
+                    .map_err(|error| format!("move {}: {error}", number + 1))?;
+                    app.history.push(FoundSet { cards: *cards, at });
+                    if let Some(at) = at {
+                        app.metrics.found(at);
+                    }
+                }
+                Move::Misclaim([a, b, c]) => {
+                    let triple = Triple::from((*a, *b, *c));
+                    match at {
+                        Some(at) => app.game.try_claim_at(PLAYER, triple, at),
+                        None => app.game.try_claim(PLAYER, triple),
+                    }
+                    .map_or_else(
+                        |error| match error {
+                            ClaimError::NotASet { .. } => Ok(()),
+                            error => Err(format!("move {}: {error}", number + 1)),
+                        },
+                        |_| Err(format!("move {}: {a} {b} {c} is a set", number + 1)),
+                    )?;
+                    app.metrics.rejected();
+                }
+                Move::DealExtra => {
+                    app.game.deal_extra();
+                }
+                // Charged at today's prices
+                Move::Hint(card) => {
+                    let at = at.unwrap_or(*elapsed);
+                    app.hints.restore(&mut app.game, PLAYER, *card, at);
+                    app.hinted.push(*card);
+                }
+            }
+            app.moves.push((*played, at.unwrap_or(*elapsed)));
+        }
+        // Moves already made are not news
+        app.events.try_iter().for_each(drop);
+        app.arrangement = app.game.board().cards().to_vec();
+        app.hinted.retain(|card| app.arrangement.contains(card));
+        app.started = app.clock.now().checked_sub(*elapsed).unwrap_or(app.started);
+        if recovery.suspended {
+            app.paused = Some(*elapsed);
+        }
+        Ok(app)
+    }
+
+    /// How long into the game each move was made, in the order of the record.
+    pub fn move_times(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.moves.iter().map(|(_, at)| *at)
+    }
+
+    /// The game so far in Set Game Notation.
+    pub fn record(&self) -> GameRecord {
+        GameRecord {
+            start: Start::Seed(self.seed),
+            moves: self.moves.iter().map(|(played, _)| *played).collect(),
+        }
+    }
+
+    fn move_selection(&mut self, direction: Direction, count: usize) {
+        let step = match direction {
+            Direction::Left | Direction::Right => count,
+            Direction::Up | Direction::Down => count.saturating_mul(self.columns),
+        };
+        let last = self.board_len().saturating_sub(1);
+        self.selected_card = match direction {
+            Direction::Left | Direction::Up => self.selected_card.saturating_sub(step),
+            Direction::Right | Direction::Down => self.selected_card.saturating_add(step).min(last),
+        };
+    }
+
+    fn card_at(&self, column: u16, row: u16) -> Option<usize> {
+        self.tiles
+            .iter()
+            .position(|tile| {
+                (tile.left()..tile.right()).contains(&column)
+                    && (tile.top()..tile.bottom()).contains(&row)
+            })
+            .map(|position| self.first_tile + position)
+    }
+
+    /// Point at the card at a position on screen, or away from the cards.
+    pub fn on_pointer(&mut self, pointer: Pointer, card: Option<usize>) {
+        if self.game_over.is_some() || self.paused.is_some() {
+            return;
+        }
+        self.hovered_card = card;
+        match pointer {
+            Pointer::Moved => {}
+            Pointer::Down => {
+                self.dragged_cards = card.into_iter().collect();
+            }
+            Pointer::Drag => {
+                if let Some(card) = card {
+                    if !self.dragged_cards.contains(&card) && self.dragged_cards.len() < 3 {
+                        self.dragged_cards.push(card);
+                    }
+                }
+            }
+            Pointer::Up => {
+                let dragged = std::mem::take(&mut self.dragged_cards);
+                if let [a, b, c] = dragged[..] {
+                    self.claim([a, b, c]);
+                }
+            }
+        }
+    }
+
+    /// Claim the cards at the given positions on screen as a set, replacing them if it is one.
+    pub fn claim(&mut self, positions: [usize; 3]) {
+        // Quizzes are answered by typing, and boards being edited are not played yet
+        if self.quiz.is_some() || self.editor.is_some() {
+            return;
+        }
+        let [a, b, c] = positions.map(|i| self.arrangement[i]);
+        if self.find_all.is_some() {
+            return self.claim_in_puzzle(Triple::from((a, b, c)));
+        }
+        // The outcome is shown from the game's events, like every other change to it
+        let now = self.elapsed();
+        let _ = self.game.try_claim_at(PLAYER, Triple::from((a, b, c)), now);
+        self.on_game_events();
+        self.selected_card = self.selected_card.min(self.board_len().saturating_sub(1));
+    }
+
+    /// Edit the board with a key, returning whether the input was taken.
+    fn on_editor_input(&mut self, input: Input) -> bool {
+        if self.paused.is_some() || self.game_over.is_some() {
+            return false;
+        }
+        let Some(editor) = self.editor.as_mut() else {
+            return false;
+        };
+        let strings = self.settings.language.strings();
+        let position = self.selected_card;
+        let attribute = match input {
+            Input::Char('c' | 'C') => Some(Attribute::Color),
+            Input::Char('n' | 'N') => Some(Attribute::Count),
+            Input::Char('f' | 'F') => Some(Attribute::Shade),
+            Input::Char('s' | 'S') => Some(Attribute::Shape),
+            _ => None,
+        };
+        match (input, attribute) {
+            (Input::Char(key), Some(attribute)) => {
+                editor.cycle(position, attribute, key.is_ascii_uppercase());
+            }
+            (Input::Char('a'), _) => {
+                if let Some(added) = editor.add() {
+                    self.selected_card = added;
+                }
+            }
+            (Input::Char('d') | Input::Backspace, _) => {
+                editor.remove(position);
+                self.selected_card = position.min(editor.cards.len().saturating_sub(1));
+            }
+            (Input::Char('v'), _) => {
+                let sets: Vec<_> = editor
+                    .sets()
+                    .iter()
+                    .map(|[a, b, c]| format!("{a} {b} {c}"))
+                    .collect();
+                self.status_message = Some(fill(
+                    strings.editor_sets,
+                    &[("count", &sets.len()), ("sets", &sets.join("; "))],
+                ));
+            }
+            (Input::Char('y'), _) => {
+                let command = editor.command();
+                self.status_message = Some(fill(strings.copied, &[("command", &command)]));
+                self.clipboard_requested = Some(command);
+            }
+            (Input::Enter, _) => {
+                self.play_edited();
+                return true;
+            }
+            _ => return false,
+        }
+        self.arrangement = editor.cards.clone();
+        true
+    }
+
+    /// Play the board being edited as a "find them all" puzzle.
+    fn play_edited(&mut self) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+        if editor.sets().is_empty() {
+            self.status_message = Some(self.strings().no_set_to_play.to_owned());
+            return;
+        }
+        tracing::info!(board = %editor.command(), "playing edited board");
+        // Restarting comes back to this board
+        self.settings.edit = false;
+        self.settings.opening = Some(Opening::Board(editor.cards.clone()));
+        self.settings.find_all = true;
+        self.find_all = Some(FindAll::new(editor.cards.clone()));
+        self.editor = None;
+        self.status_message = None;
+        self.started = self.clock.now();
+    }
+
+    /// A command line to play the puzzle again.
+    fn puzzle_command(&self) -> String {
+        match &self.settings.opening {
+            Some(opening) => format!("sgs --{} {}", self.settings.puzzle_flag(), opening.flag()),
+            None => format!("sgs --{} --seed {}", self.settings.puzzle_flag(), self.seed),
+        }
+    }
+
+    /// A command line to play this game again.
+    fn game_command(&self) -> String {
+        match &self.settings.opening {
+            Some(opening @ Opening::Board(_)) => {
+                format!("sgs --seed {} {}", self.seed, opening.flag())
+            }
+            Some(opening @ Opening::Position(..)) => format!("sgs {}", opening.flag()),
+            None => format!("sgs --seed {}", self.seed),
+        }
+    }
+
+    /// Type a digit of the count of sets on the quiz's board, or submit it with Enter, returning
+    /// whether the input was taken.
+    fn on_quiz_input(&mut self, input: Input) -> bool {
+        if self.quiz.is_none() || self.paused.is_some() || self.game_over.is_some() {
+            return false;
+        }
+        match input {
+            Input::Char(digit @ '0'..='9') => {
+                if self.guess.len() < 3 {
+                    self.guess.push(digit);
+                }
+            }
+            Input::Backspace => {
+                self.guess.pop();
+            }
+            Input::Enter => self.answer_quiz(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn answer_quiz(&mut self) {
+        let now = self.elapsed();
+        let (Some(quiz), Ok(guess)) = (self.quiz.as_mut(), self.guess.parse()) else {
+            return;
+        };
+        let Some(answer) = quiz.answer(guess, now.saturating_sub(self.shown_at)) else {
+            return;
+        };
+        tracing::info!(
+            sets = answer.sets,
+            guess,
+            points = answer.points,
+            "answered quiz"
+        );
+        self.notifications.push(if answer.guess == answer.sets {
+            Notification::Accepted
+        } else {
+            Notification::Rejected
+        });
+        self.status_message = Some(fill(
+            self.settings.language.strings().quiz_answer,
+            &[
+                ("sets", &answer.sets),
+                ("guess", &answer.guess),
+                ("points", &answer.points),
+            ],
+        ));
+        self.guess.clear();
+        self.shown_at = now;
+        match quiz.board() {
+            Some(board) => self.arrangement = board.to_vec(),
+            None => self.end_game(),
+        }
+    }
+
+    /// Claim a set in the "find them all" puzzle, where the board stays as it is.
+    fn claim_in_puzzle(&mut self, triple: Triple) {
+        let now = self.elapsed();
+        let Some(puzzle) = self.find_all.as_mut() else {
+            return;
+        };
+        let [a, b, c] = triple.cards();
+        let cards = format!("{a} {b} {c}");
+        let strings = self.settings.language.strings();
+        match puzzle.try_claim(triple) {
+            Ok(_) => {
+                tracing::info!(%cards, found = puzzle.found().len(), "found a set");
+                self.history.push(FoundSet {
+                    cards: [a, b, c],
+                    at: Some(now),
+                });
+                self.history_scroll = 0;
+                self.metrics.found(now);
+                self.notifications.push(Notification::Accepted);
+                let template = if puzzle.is_stuck() {
+                    strings.chain_stuck
+                } else {
+                    strings.found_one
+                };
+                self.status_message = Some(fill(
+                    template,
+                    &[
+                        ("cards", &cards),
+                        ("found", &puzzle.found().len()),
+                        ("sets", &puzzle.total()),
+                    ],
+                ));
+            }
+            Err(ClaimError::NotASet { broken, .. }) => {
+                tracing::info!(%cards, "rejected claim");
+                let attribute = broken
+                    .first()
+                    .map(|attribute| strings.attribute(*attribute))
+                    .unwrap_or_default();
+                self.status_message = Some(fill(
+                    strings.not_a_set,
+                    &[("cards", &cards), ("attribute", &attribute)],
+                ));
+                self.notifications.push(Notification::Rejected);
+                self.metrics.rejected();
+            }
+            Err(ClaimError::AlreadyFound) => {
+                self.status_message = Some(fill(strings.already_found, &[("cards", &cards)]));
+                self.notifications.push(Notification::Rejected);
+            }
+            Err(ClaimError::Disallowed) => {
+                let last = puzzle.found().last().map(|set| {
+                    let [a, b, c] = set.cards();
+                    format!("{a} {b} {c}")
+                });
+                self.status_message = Some(fill(
+                    strings.not_next,
+                    &[("cards", &cards), ("last", &last.unwrap_or_default())],
+                ));
+                self.notifications.push(Notification::Rejected);
+                self.metrics.rejected();
+            }
+            // Positions always name cards on the board
+            Err(error) => tracing::warn!(%error, "unexpected claim error"),
+        }
+    }
+
+    fn board_len(&self) -> usize {
+        self.arrangement.len()
+    }
+
+    /// Point out a card in a set, if the rules allow another hint yet.
+    fn hint(&mut self) {
+        if self.find_all.is_some() || self.quiz.is_some() {
+            self.status_message = Some(self.strings().board_stays.to_owned());
+            return;
+        }
+        let now = self.elapsed();
+        let error = match self.hints.try_hint(&mut self.game, PLAYER, now) {
+            // Shown from the game's events
+            Ok(_) => return self.on_game_events(),
+            Err(error) => error,
+        };
+        let strings = self.strings();
+        self.status_message = Some(match error {
+            HintError::NoneLeft => strings.no_hints_left.to_owned(),
+            HintError::CoolingDown { ready_at } => {
+                let seconds = (ready_at - now).as_secs_f64().ceil();
+                fill(strings.hint_cooling_down, &[("seconds", &seconds)])
+            }
+            HintError::NoSet => strings.no_set_on_board.to_owned(),
+            HintError::AllShown => strings.all_sets_shown.to_owned(),
+            HintError::GameOver => return,
+        });
+    }
+
+    /// Lay the board out in a new order on screen, leaving the game as it is, as rearranging
+    /// cards on the table helps to spot sets.
+    fn shuffle(&mut self) {
+        use rand::{seq::SliceRandom, SeedableRng};
+        use rand_pcg::Pcg64;
+
+        // Derived from the seed, so recorded sessions replay identically
+        self.shuffles += 1;
+        let mut rng = Pcg64::seed_from_u64(self.seed.wrapping_add(self.shuffles));
+        self.arrangement.shuffle(&mut rng);
+        self.dragged_cards.clear();
+    }
+
+    /// Deal three more cards onto the board, for when no set can be found.
+    fn deal_extra(&mut self) {
+        if self.find_all.is_some() || self.quiz.is_some() {
+            self.status_message = Some(self.strings().board_stays.to_owned());
+            return;
+        }
+        if self.game.deal_extra().is_empty() {
+            self.status_message = Some(self.strings().deck_empty.to_string());
+        }
+        self.on_game_events();
+    }
+
+    /// Log and announce what has happened in the game since last time.
+    fn on_game_events(&mut self) {
+        let events: Vec<_> = self.events.try_iter().collect();
+        if !events.is_empty() {
+            self.arrangement = self.game.board().arranged_like(&self.arrangement);
+            let arrangement = &self.arrangement;
+            self.hinted.retain(|card| arrangement.contains(card));
+        }
+        for event in events {
+            match event {
+                GameEvent::Claimed { claim, .. } => {
+                    self.moves.push((Move::Set(claim.cards), self.elapsed()));
+                    self.history.push(FoundSet {
+                        cards: claim.cards,
+                        at: Some(self.elapsed()),
+                    });
+                    self.history_scroll = 0;
+                    self.metrics.found(self.elapsed());
+                    let [a, b, c] = claim.cards;
+                    let cards = format!("{a} {b} {c}");
+                    tracing::info!(
+                        %cards,
+                        score = claim.score,
+                        dealt = claim.dealt.len(),
+                        "accepted claim"
+                    );
+                    self.notifications.push(Notification::Accepted);
+                    self.status_message = Some(fill(self.strings().is_a_set, &[("cards", &cards)]));
+                }
+                GameEvent::Refused {
+                    error: ClaimError::NotASet { cards, broken },
+                    ..
+                } => {
+                    self.moves.push((Move::Misclaim(cards), self.elapsed()));
+                    let [a, b, c] = cards;
+                    let cards = format!("{a} {b} {c}");
+                    tracing::info!(%cards, "rejected claim");
+                    let attribute = broken
+                        .first()
+                        .map(|attribute| self.strings().attribute(*attribute))
+                        .unwrap_or_default();
+                    self.status_message = Some(fill(
+                        self.strings().not_a_set,
+                        &[("cards", &cards), ("attribute", &attribute)],
+                    ));
+                    self.notifications.push(Notification::Rejected);
+                    self.metrics.rejected();
+                }
+                // Positions always name cards on the board, and claims stop once the game is over
+                GameEvent::Refused { error, .. } => {
+                    tracing::warn!(%error, "unexpected claim error")
+                }
+                GameEvent::DealtExtra(_) => {
+                    self.moves.push((Move::DealExtra, self.elapsed()));
+                    tracing::info!(
+                        board = self.board_len(),
+                        deck = self.game.deck().0.len(),
+                        "dealt extra cards"
+                    );
+                    self.notifications.push(Notification::ExtraDeal);
+                }
+                GameEvent::GameOver => self.end_game(),
+                // There is no clock in a game on your own
+                GameEvent::OutOfTime(_) => {}
+                GameEvent::Hinted { card, score, .. } => {
+                    self.moves.push((Move::Hint(card), self.elapsed()));
+                    tracing::info!(%card, score, "gave a hint");
+                    self.hinted.push(card);
+                    let card = card.to_string();
+                    self.status_message = Some(fill(self.strings().hint, &[("card", &card)]));
+                }
+            }
+        }
+    }
+
+    /// Start over with the given seed, keeping the session mode and settings.
+    pub fn restart(&mut self, seed: u64) {
+        tracing::info!(seed, "restarting");
+        let (mode, clock) = (self.mode, self.clock);
+        *self = App::new(seed, self.settings.clone());
+        self.mode = mode;
+        self.clock = clock;
+        self.started = clock.now();
+    }
+
+    /// Derive the seed for the next game from the current one, so recorded sessions replay
+    /// identically.
+    fn next_seed(&self) -> u64 {
+        use rand::{Rng, SeedableRng};
+        use rand_pcg::Pcg64;
+
+        Pcg64::seed_from_u64(self.seed).gen()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.game_over
+            .or(self.paused)
+            .unwrap_or_else(|| self.running())
+    }
+
+    /// The time on the game's clock while it runs.
+    fn running(&self) -> Duration {
+        self.clock.now().saturating_duration_since(self.started)
+    }
+
+    /// Whether quitting while paused can save the game to carry on later, which recorded
+    /// sessions cannot as they replay from the start.
+    pub fn can_suspend(&self) -> bool {
+        self.mode == Mode::Play
+    }
+
+    /// Stop or restart the clock, hiding the board while it is stopped.
+    fn toggle_pause(&mut self) {
+        match self.paused.take() {
+            Some(elapsed) => {
+                tracing::info!("carrying on");
+                self.started = self
+                    .clock
+                    .now()
+                    .checked_sub(elapsed)
+                    .unwrap_or(self.started);
+            }
+            None => {
+                tracing::info!("paused");
+                self.paused = Some(self.running());
+                self.dragged_cards.clear();
+            }
+        }
+    }
+
+    /// The elapsed time as `mm:ss`.
+    fn clock(&self) -> String {
+        mm_ss(self.elapsed())
+    }
+
+    pub fn strings(&self) -> &'static Strings {
+        self.settings.language.strings()
+    }
+
+    fn end_game(&mut self) {
+        if self.game_over.is_none() {
+            let elapsed = self.running();
+            tracing::info!(
+                seed = self.seed,
+                score = self.game.score(PLAYER),
+                elapsed_ms = elapsed.as_millis() as u64,
+                "game over"
+            );
+            self.game_over = Some(elapsed);
+        }
+    }
+
+    pub fn on_action(&mut self, action: Action) {
+        if self.game_over.is_some() {
+            match action {
+                Action::Quit => self.should_quit = true,
+                Action::Restart => self.restart(self.seed),
+                Action::NewGame => self.restart(self.next_seed()),
+                _ => {}
+            }
+            return;
+        }
+        if self.paused.is_some() {
+            match action {
+                Action::Pause => self.toggle_pause(),
+                Action::Quit if self.can_suspend() => {
+                    self.suspended = true;
+                    self.should_quit = true;
+                }
+                Action::Quit => {
+                    self.toggle_pause();
+                    self.end_game();
+                }
+                _ => {}
+            }
+            return;
+        }
+        match action {
+            // There is no game to end while editing
+            Action::Quit if self.editor.is_some() => self.should_quit = true,
+            Action::DealExtra | Action::Shuffle | Action::Hint if self.editor.is_some() => {}
+            Action::Pause => self.toggle_pause(),
+            Action::Quit => self.end_game(),
+            Action::Restart => self.restart(self.seed),
+            Action::NewGame => self.restart(self.next_seed()),
+            Action::Snapshot(format) => self.snapshot_requested = Some(format),
+            Action::DealExtra => self.deal_extra(),
+            Action::Shuffle => self.shuffle(),
+            Action::Hint => self.hint(),
+            Action::SwitchPlayer => self.switch_player_requested = true,
+            Action::CopySeed => {
+                let command = self.game_command();
+                self.status_message = Some(fill(self.strings().copied, &[("command", &command)]));
+                self.clipboard_requested = Some(command);
+            }
+            Action::ToggleNotation => {
+                self.settings.show_notation = !self.settings.show_notation;
+            }
+            Action::ToggleHistory => {
+                self.settings.show_history = !self.settings.show_history;
+            }
+            Action::ToggleMetrics => {
+                self.settings.show_metrics = !self.settings.show_metrics;
+            }
+            Action::HistoryOlder => self.history_scroll = self.history_scroll.saturating_add(1),
+            Action::HistoryNewer => self.history_scroll = self.history_scroll.saturating_sub(1),
+            Action::Move(direction, count) => self.move_selection(direction, count),
+            Action::First => self.selected_card = 0,
+            Action::Last => self.selected_card = self.board_len().saturating_sub(1),
+        }
+    }
+
+    pub fn on_tick(&mut self) {
+        let solved = self.find_all.as_ref().is_some_and(FindAll::is_complete);
+        if self.game.is_over() || solved {
+            self.end_game();
+        }
+    }
+}
+
+/// Sets on a chain puzzle's board, so that finding them in order is a puzzle at all.
+const CHAIN_MIN_SETS: usize = 4;
+
+/// The first seed from `seed` on whose opening board is a chain puzzle.
+fn chain_seed(seed: u64) -> u64 {
+    let boards = (0..).map(|i| {
+        let mut deck = Deck::new_from_seed(seed.wrapping_add(i));
+        std::iter::from_fn(|| deck.deal())
+            .take(BOARD_SIZE)
+            .collect()
+    });
+    let (i, _) =
+        ChainPuzzle::generate(boards, CHAIN_MIN_SETS).expect("there are always more seeds");
+    seed.wrapping_add(i as u64)
+}
+
+/// Boards in a quiz on counting sets.
+const QUIZ_BOARDS: u64 = 10;
+/// How long each board in the quiz is shown for.
+const QUIZ_SHOWN_FOR: Duration = Duration::from_secs(5);
+
+/// A quiz on the opening boards of the seeds from `seed` on.
+fn quiz_boards(seed: u64) -> CountQuiz {
+    let boards = (0..QUIZ_BOARDS)
+        .map(|i| {
+            let mut deck = Deck::new_from_seed(seed.wrapping_add(i));
+            std::iter::from_fn(|| deck.deal())
+                .take(BOARD_SIZE)
+                .collect()
+        })
+        .collect();
+    CountQuiz::new(boards, CountRules::default())
+}
+
+/// Render the app into an in-memory buffer, without touching the terminal.
+pub fn render_headless(app: &mut App, area: Rect) -> io::Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(area.width, area.height))?;
+    terminal.draw(|f| draw(f, app))?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+/// Convert a rendered buffer to text, one line per row with trailing whitespace removed.
+pub fn buffer_to_text(buffer: &Buffer, format: SnapshotFormat) -> String {
+    let width = buffer.area().width as usize;
+    let mut text = String::new();
+    for row in buffer.content().chunks(width) {
+        let mut line = String::new();
+        let mut fg = Color::Reset;
+        for cell in row {
+            if format == SnapshotFormat::Ansi && cell.fg != fg {
+                fg = cell.fg;
+                line.push_str(ansi_fg(fg));
+            }
+            line.push_str(&cell.symbol);
+        }
+        if format == SnapshotFormat::Ansi && fg != Color::Reset {
+            line.push_str(ansi_fg(Color::Reset));
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+fn ansi_fg(color: Color) -> &'static str {
+    match color {
+        Color::Red => "\x1b[31m",
+        Color::Green => "\x1b[32m",
+        Color::Magenta => "\x1b[35m",
+        _ => "\x1b[39m",
+    }
+}
+
+/// Write a snapshot of the current board to the data directory, returning the path written.
+fn write_snapshot(app: &mut App, area: Rect, format: SnapshotFormat) -> io::Result<PathBuf> {
+    let buffer = render_headless(app, area)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let dir = app.settings.data_dir.join("snapshots");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "sgs-{}-{}.{}",
+        app.seed,
+        timestamp,
+        format.extension()
+    ));
+    fs::write(&path, buffer_to_text(&buffer, format))?;
+    Ok(path)
+}
+
+/// A single user input, independent of the terminal backend that produced it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Input {
+    Char(char),
+    Left,
+    Up,
+    Right,
+    Down,
+    Escape,
+    Enter,
+    Backspace,
+    /// The left mouse button or pointer, at a column and row, as recorded before version 4.
+    Mouse(Pointer, u16, u16),
+    /// The left mouse button or pointer, over the card at a position on screen if any.
+    Pointer(Pointer, Option<usize>),
+    /// The terminal changed to a number of columns and rows.
+    Resize(u16, u16),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Pointer {
+    Moved,
+    Down,
+    Drag,
+    Up,
+}
+
+impl Pointer {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "moved" => Some(Self::Moved),
+            "down" => Some(Self::Down),
+            "drag" => Some(Self::Drag),
+            "up" => Some(Self::Up),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Moved => "moved",
+            Self::Down => "down",
+            Self::Drag => "drag",
+            Self::Up => "up",
+        }
+    }
+}
+
+impl Input {
+    /// The mouse event, over the card it points at in the app as last drawn.
+    fn from_mouse_event(event: MouseEvent, app: &App) -> Option<Self> {
+        let pointer = match event.kind {
+            MouseEventKind::Moved => Pointer::Moved,
+            MouseEventKind::Down(MouseButton::Left) => Pointer::Down,
+            MouseEventKind::Drag(MouseButton::Left) => Pointer::Drag,
+            MouseEventKind::Up(MouseButton::Left) => Pointer::Up,
+            _ => return None,
+        };
+        Some(Self::Pointer(pointer, app.card_at(event.column, event.row)))
+    }
+
+    fn from_key_code(code: KeyCode) -> Option<Self> {
+        match code {
+            KeyCode::Char(c) => Some(Self::Char(c)),
+            KeyCode::Left => Some(Self::Left),
+            KeyCode::Up => Some(Self::Up),
+            KeyCode::Right => Some(Self::Right),
+            KeyCode::Down => Some(Self::Down),
+            KeyCode::Esc => Some(Self::Escape),
+            KeyCode::Enter => Some(Self::Enter),
+            KeyCode::Backspace => Some(Self::Backspace),
+            _ => None,
+        }
+    }
+
+    fn dispatch(self, app: &mut App) {
+        if let Self::Mouse(pointer, column, row) = self {
+            app.on_pointer(pointer, app.card_at(column, row));
+        } else if let Self::Pointer(pointer, card) = self {
+            app.on_pointer(pointer, card);
+        } else if let Self::Resize(..) = self {
+            // The board is laid out again when it is next drawn
+        } else if app.on_quiz_input(self) {
+            // Typed into the quiz's answer
+        } else if app.on_editor_input(self) {
+            // Edited the board
+        } else if let Some(action) = app.keymap.handle(self) {
+            app.on_action(action);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Left,
+    Up,
+    Right,
+    Down,
+}
+
+/// Something the player asked for, independent of the keys used to ask.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Action {
+    Quit,
+    /// Stop the clock and hide the board, or carry on.
+    Pause,
+    Restart,
+    NewGame,
+    Snapshot(SnapshotFormat),
+    /// Deal three more cards onto the board.
+    DealExtra,
+    /// Rearrange the cards on screen, without changing the game.
+    Shuffle,
+    /// Point out a card in a set, at a cost.
+    Hint,
+    /// Play as the next player with a profile.
+    SwitchPlayer,
+    /// Show or hide card notation on each tile.
+    ToggleNotation,
+    /// Show or hide the list of sets found.
+    ToggleHistory,
+    /// Show or hide how quickly and accurately sets are being found.
+    ToggleMetrics,
+    /// Scroll the list of sets found back one set.
+    HistoryOlder,
+    /// Scroll the list of sets found forward one set.
+    HistoryNewer,
+    /// Copy a command line reproducing this game to the clipboard.
+    CopySeed,
+    /// Move the selection a number of cards in a direction.
+    Move(Direction, usize),
+    /// Select the first card on the board.
+    First,
+    /// Select the last card on the board.
+    Last,
+}
+
+/// Translates inputs into actions, tracking multi-key sequences such as `3l` and `gg`.
+#[derive(Debug, Default)]
+pub struct Keymap {
+    count: Option<usize>,
+    pending_g: bool,
+}
+
+impl Keymap {
+    pub fn handle(&mut self, input: Input) -> Option<Action> {
+        let count = self.count.take();
+        let pending_g = std::mem::take(&mut self.pending_g);
+        let action = match input {
+            Input::Char(c @ '0'..='9') if c != '0' || count.is_some() => {
+                let digit = c.to_digit(10).unwrap_or_default() as usize;
+                self.count = Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+                return None;
+            }
+            Input::Char('g') if !pending_g => {
+                self.pending_g = true;
+                return None;
+            }
+            Input::Char('g') => Action::First,
+            Input::Char('G') => Action::Last,
+            Input::Char('h') | Input::Left => Action::Move(Direction::Left, count.unwrap_or(1)),
+            Input::Char('k') | Input::Up => Action::Move(Direction::Up, count.unwrap_or(1)),
+            Input::Char('l') | Input::Right => Action::Move(Direction::Right, count.unwrap_or(1)),
+            Input::Char('j') | Input::Down => Action::Move(Direction::Down, count.unwrap_or(1)),
+            Input::Char('q') => Action::Quit,
+            Input::Escape => Action::Pause,
+            Input::Char('r') => Action::Restart,
+            Input::Char('n') => Action::NewGame,
+            Input::Char('p') => Action::Snapshot(SnapshotFormat::Plain),
+            Input::Char('P') => Action::Snapshot(SnapshotFormat::Ansi),
+            Input::Char('+') => Action::DealExtra,
+            Input::Char('x') => Action::Shuffle,
+            Input::Char('?') => Action::Hint,
+            Input::Char('u') => Action::SwitchPlayer,
+            Input::Char('t') => Action::ToggleNotation,
+            Input::Char('s') => Action::ToggleHistory,
+            Input::Char('m') => Action::ToggleMetrics,
+            Input::Char('[') => Action::HistoryOlder,
+            Input::Char(']') => Action::HistoryNewer,
+            Input::Char('y') => Action::CopySeed,
+            Input::Char(_) | Input::Enter | Input::Backspace => return None,
+            Input::Mouse(..) | Input::Pointer(..) | Input::Resize(..) => return None,
+        };
+        Some(action)
+    }
+}
+
+/// One input per line, e.g. `left`, `char:q` or `pointer:down:4`, or `pointer:up:none` away from
+/// the cards.
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Char(c) => write!(f, "char:{c}"),
+            Self::Left => f.write_str("left"),
+            Self::Up => f.write_str("up"),
+            Self::Right => f.write_str("right"),
+            Self::Down => f.write_str("down"),
+            Self::Escape => f.write_str("escape"),
+            Self::Enter => f.write_str("enter"),
+            Self::Backspace => f.write_str("backspace"),
+            Self::Mouse(pointer, column, row) => {
+                write!(f, "mouse:{}:{column},{row}", pointer.as_str())
+            }
+            Self::Pointer(pointer, Some(card)) => write!(f, "pointer:{}:{card}", pointer.as_str()),
+            Self::Pointer(pointer, None) => write!(f, "pointer:{}:none", pointer.as_str()),
+            Self::Resize(columns, rows) => write!(f, "resize:{columns}x{rows}"),
+        }
+    }
+}
+
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left" => Ok(Self::Left),
+            "up" => Ok(Self::Up),
+            "right" => Ok(Self::Right),
+            "down" => Ok(Self::Down),
+            "escape" => Ok(Self::Escape),
+            "enter" => Ok(Self::Enter),
+            "backspace" => Ok(Self::Backspace),
+            _ if s.starts_with("mouse:") => {
+                let invalid = || format!("expected 'mouse:<kind>:<column>,<row>' in '{s}'");
+                let mut parts = s.splitn(3, ':').skip(1);
+                let pointer = parts.next().and_then(Pointer::parse).ok_or_else(invalid)?;
+                let (column, row) = parts
+                    .next()
+                    .and_then(|p| p.split_once(','))
+                    .ok_or_else(invalid)?;
+                Ok(Self::Mouse(
+                    pointer,
+                    column.parse().map_err(|_| invalid())?,
+                    row.parse().map_err(|_| invalid())?,
+                ))
+            }
+            _ if s.starts_with("resize:") => {
+                let invalid = || format!("expected 'resize:<columns>x<rows>' in '{s}'");
+                let (columns, rows) = parse_size(&s["resize:".len()..]).ok_or_else(invalid)?;
+                Ok(Self::Resize(columns, rows))
+            }
+            _ if s.starts_with("pointer:") => {
+                let invalid = || format!("expected 'pointer:<kind>:<position>' in '{s}'");
+                let mut parts = s.splitn(3, ':').skip(1);
+                let pointer = parts.next().and_then(Pointer::parse).ok_or_else(invalid)?;
+                let card = match parts.next().ok_or_else(invalid)? {
+                    "none" => None,
+                    card => Some(card.parse().map_err(|_| invalid())?),
+                };
+                Ok(Self::Pointer(pointer, card))
+            }
+            _ => {
+                let mut chars = s
+                    .strip_prefix("char:")
+                    .ok_or_else(|| format!("unknown input '{s}'"))?
+                    .chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Self::Char(c)),
+                    _ => Err(format!("expected a single character in '{s}'")),
+                }
+            }
+        }
+    }
+}
+
+/// The version of recording written, and the newest that can be played back.
+///
+/// Version 1 started with the seed, and version 2 gives its version first. Version 3 adds the
+/// version of the shuffle, and ends with a digest of everything before it. Version 4 gives the
+/// time each input was made, the card pointed at rather than where on screen, and the size of the
+/// terminal, which moving up and down the board depends on.
+const RECORDING_VERSION: u32 = 4;
+
+/// A terminal size as `<columns>x<rows>`.
+fn parse_size(s: &str) -> Option<(u16, u16)> {
+    let (columns, rows) = s.split_once('x')?;
+    Some((columns.parse().ok()?, rows.parse().ok()?))
+}
+
+/// Writes a session's seed and every input to a file, so it can be replayed later.
+///
+/// The first lines are `version <n>`, `seed <n>`, `shuffle <n>` and `size <columns>x<rows>`,
+/// followed by one input per line after the milliseconds into the session it was made, e.g.
+/// `1500 char:q`, and finally `digest <hex>` once the session ends.
+pub struct Recorder {
+    writer: BufWriter<fs::File>,
+    chain: Chain,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, seed: u64, (columns, rows): (u16, u16)) -> Result<Self, Error> {
+        let error = || Error::io(format!("failed to write recording {}", path.display()));
+        let mut writer = BufWriter::new(fs::File::create(path).map_err(error())?);
+        let mut chain = Chain::default();
+        for line in [
+            format!("version {RECORDING_VERSION}"),
+            format!("seed {seed}"),
+            format!("shuffle {SHUFFLE_VERSION}"),
+            format!("size {columns}x{rows}"),
+        ] {
+            writeln!(writer, "{line}").map_err(error())?;
+            chain.link(&line);
+        }
+        // Anyone watching can start as soon as the game does
+        writer.flush().map_err(error())?;
+        Ok(Self { writer, chain })
+    }
+
+    pub fn record(&mut self, at: Duration, input: Input) -> Result<(), Error> {
+        let line = format!("{} {input}", at.as_millis());
+        self.chain.link(&line);
+        writeln!(self.writer, "{line}")
+            .and_then(|()| self.writer.flush())
+            .map_err(Error::io("failed to write recording"))
+    }
+}
+
+/// Seal the recording with its digest, however the session ended.
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = writeln!(self.writer, "digest {}", self.chain.digest())
+            .and_then(|()| self.writer.flush());
+    }
+}
+
+/// A hash chain over the lines of a recording, where each link is the SHA-256 of the one before
+/// and the next line, so that changing, adding or taking away any line changes the digest.
+#[derive(Debug, Default, Clone)]
+struct Chain([u8; 32]);
+
+impl Chain {
+    fn link(&mut self, line: &str) {
+        let mut hasher = Sha256::new();
+        hasher.update(self.0);
+        hasher.update(line.as_bytes());
+        self.0 = hasher.finalize().into();
+    }
+
+    fn digest(&self) -> String {
+        self.0.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+/// A previously recorded session, or one still being recorded when watched.
+pub struct Playback {
+    pub seed: u64,
+    /// Each input, and the time into the session it was made if recorded.
+    pub inputs: VecDeque<(Option<Duration>, Input)>,
+    /// The version it was recorded in.
+    pub version: u32,
+    /// The size of the terminal when the next input to replay was made, if recorded.
+    pub size: Option<(u16, u16)>,
+    /// The digest it was sealed with, which matched its contents, or none if it was recorded
+    /// before digests or cut off before the end.
+    pub digest: Option<String>,
+    /// The recording's path, to name it in errors.
+    name: String,
+    /// The hash chain over the lines read so far.
+    chain: Chain,
+    /// The number of the last line read.
+    line: usize,
+    /// When the last input was made, if recorded.
+    last: Option<Duration>,
+    /// The rest of a recording still being written.
+    tail: Option<Tail>,
+}
+
+impl Playback {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path).map_err(Error::io(format!(
+            "failed to read recording {}",
+            path.display()
+        )))?;
+        Self::parse(path, &text)
+    }
+
+    /// Start following a recording while it is written, from the inputs written so far.
+    pub fn watch(path: &Path) -> Result<Self, Error> {
+        let error = || Error::io(format!("failed to read recording {}", path.display()));
+        let mut tail = Tail {
+            file: fs::File::open(path).map_err(error())?,
+            partial: Vec::new(),
+        };
+        let text = tail.read().map_err(error())?;
+        let mut playback = Self::parse(path, &text)?;
+        // Sealed already, so there is nothing more to come
+        if playback.digest.is_none() {
+            playback.tail = Some(tail);
+        }
+        Ok(playback)
+    }
+
+    /// Whether the recording is still being written, and followed.
+    pub fn following(&self) -> bool {
+        self.tail.is_some()
+    }
+
+    /// Read the inputs written since last time, if following a recording.
+    pub fn follow(&mut self) -> Result<(), Error> {
+        let Some(tail) = self.tail.as_mut() else {
+            return Ok(());
+        };
+        let text = tail
+            .read()
+            .map_err(Error::io(format!("failed to read recording {}", self.name)))?;
+        for line in text.lines() {
+            self.read(line)?;
+        }
+        if self.digest.is_some() {
+            self.tail = None;
+        }
+        Ok(())
+    }
+
+    /// Read the header, then every input that follows it.
+    fn parse(path: &Path, text: &str) -> Result<Self, Error> {
+        let name = path.display().to_string();
+        let mut lines = text.lines();
+        let mut chain = Chain::default();
+        let mut number = 0;
+        let mut header = || match lines.next() {
+            Some(line) => {
+                number += 1;
+                chain.link(line);
+                Ok((number, line))
+            }
+            None => Err(Error::from(format!("recording {name} is empty"))),
+        };
+        let (mut number, mut line) = header()?;
+        // Version 1 recordings start with the seed
+        let mut version = 1;
+        if let Some(found) = line.strip_prefix("version ") {
+            version = found
+                .parse()
+                .map_err(|_| format!("{name}:{number}: invalid version '{found}'"))?;
+            if version > RECORDING_VERSION {
+                Err(format!(
+                    "{name}:{number}: recorded by a newer version of sgs (version {version}, \
+                     expected up to {RECORDING_VERSION})"
+                ))?;
+            }
+            (number, line) = header()?;
+        }
+        let seed = line
+            .strip_prefix("seed ")
+            .ok_or_else(|| format!("{name}:{number}: expected 'seed <n>' header, got '{line}'"))?;
+        let seed = seed
+            .parse()
+            .map_err(|_| format!("{name}:{number}: invalid seed '{seed}'"))?;
+        if version >= 3 {
+            (number, line) = header()?;
+            let shuffle = line
+                .strip_prefix("shuffle ")
+                .and_then(|shuffle| shuffle.parse::<u32>().ok())
+                .ok_or_else(|| {
+                    format!("{name}:{number}: expected 'shuffle <n>' header, got '{line}'")
+                })?;
+            if shuffle != SHUFFLE_VERSION {
+                Err(format!(
+                    "{name}:{number}: dealt with shuffle version {shuffle}, but this version of \
+                     sgs shuffles with version {SHUFFLE_VERSION}, so the seed deals other cards"
+                ))?;
+            }
+        }
+        let mut size = None;
+        if version >= 4 {
+            (number, line) = header()?;
+            let found = line.strip_prefix("size ").and_then(parse_size);
+            size = Some(found.ok_or_else(|| {
+                format!("{name}:{number}: expected 'size <columns>x<rows>' header, got '{line}'")
+            })?);
+        }
+        let mut playback = Self {
+            seed,
+            inputs: VecDeque::new(),
+            version,
+            size,
+            digest: None,
+            name: name.clone(),
+            chain,
+            line: number,
+            last: None,
+            tail: None,
+        };
+        for line in lines {
+            playback.read(line)?;
+        }
+        Ok(playback)
+    }
+
+    /// Check that the terminal is the size it was when the inputs to come were made, as the board
+    /// is laid out to fit it.
+    pub fn check_size(&self) -> Result<(), Error> {
+        let Some(recorded) = self.size else {
+            return Ok(());
+        };
+        let size = term::size().map_err(Error::io("failed to read the terminal size"))?;
+        if size != recorded {
+            Err(format!(
+                "{}: recorded in a {}x{} terminal, but this one is {}x{}, and the board is laid \
+                 out to fit; resize it to replay",
+                self.name, recorded.0, recorded.1, size.0, size.1
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Read a line after the header: an input, or the digest that ends the recording.
+    fn read(&mut self, line: &str) -> Result<(), Error> {
+        self.line += 1;
+        let (name, number) = (&self.name, self.line);
+        if line.is_empty() {
+            return Ok(());
+        }
+        if self.digest.is_some() {
+            Err(format!(
+                "{name}:{number}: unexpected '{line}' after the digest"
+            ))?;
+        }
+        match line.strip_prefix("digest ") {
+            Some(sealed) if self.version >= 3 => {
+                if sealed != self.chain.digest() {
+                    Err(format!(
+                        "{name}:{number}: the digest does not match, so the recording was \
+                         changed after it was made"
+                    ))?;
+                }
+                self.digest = Some(sealed.to_owned());
+            }
+            _ => {
+                let (at, input) = match line.split_once(' ') {
+                    Some((at, input)) if self.version >= 4 => {
+                        let at = at
+                            .parse()
+                            .map(Duration::from_millis)
+                            .map_err(|_| format!("{name}:{number}: invalid time '{at}'"))?;
+                        if self.last.is_some_and(|last| at < last) {
+                            Err(format!("{name}:{number}: made before the input before it"))?;
+                        }
+                        self.last = Some(at);
+                        (Some(at), input)
+                    }
+                    _ if self.version >= 4 => Err(format!(
+                        "{name}:{number}: expected '<ms> <input>', got '{line}'"
+                    ))?,
+                    _ => (None, line),
+                };
+                let input = input
+                    .parse()
+                    .map_err(|error| format!("{name}:{number}: {error}"))?;
+                self.chain.link(line);
+                self.inputs.push_back((at, input));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The end of a file that is still being written.
+struct Tail {
+    file: fs::File,
+    /// What has been read of the line being written.
+    partial: Vec<u8>,
+}
+
+impl Tail {
+    /// Every whole line written since last time, keeping back the start of a line still being
+    /// written until the rest of it is.
+    fn read(&mut self) -> io::Result<String> {
+        if self.file.metadata()?.len() < self.file.stream_position()? {
+            return Err(io::Error::other("it was started again from the beginning"));
+        }
+        self.file.read_to_end(&mut self.partial)?;
+        let end = self
+            .partial
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |end| end + 1);
+        let rest = self.partial.split_off(end);
+        let lines = std::mem::replace(&mut self.partial, rest);
+        String::from_utf8(lines).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+pub fn run(
+    seed: u64,
+    tick_rate: Duration,
+    settings: Settings,
+    playback: Option<Playback>,
+    recorder: Option<Recorder>,
+    recovery: Option<Recovery>,
+) -> Result<(), Error> {
+    // Recordings replay from the start, so games being recorded cannot be resumed either, and
+    // records keep neither the sets found in puzzles nor a board given instead of the seed's
+    let puzzle = settings.find_all || settings.count_sets || settings.edit;
+    let autosave = match (&playback, &recorder) {
+        (None, None) if !puzzle && settings.opening.is_none() => {
+            Some(Autosave::install(&settings.data_dir)?)
+        }
+        _ => None,
+    };
+
+    // create app before taking over the terminal, so that errors are printed to it as usual
+    let mut app = match recovery {
+        Some(recovery) => App::resume(&recovery, settings)
+            .map_err(|error| format!("{}: {error}", recovery.path.display()))?,
+        None => App::new(seed, settings),
+    };
+    app.mode = match (&playback, &recorder) {
+        (Some(playback), _) if playback.following() => Mode::Watch,
+        (Some(_), _) => Mode::Playback,
+        (None, Some(_)) => Mode::Record,
+        (None, None) => Mode::Play,
+    };
+    let profiles = Profiles::new(&app.settings.data_dir);
+    let settings_language = app.settings.language;
+
+    // setup terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    stdout
+        .execute(EnterAlternateScreen)?
+        .execute(EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // run it
+    let res = run_app(
+        &mut terminal,
+        app,
+        tick_rate,
+        playback,
+        recorder,
+        autosave,
+        profiles,
+    );
+
+    // restore terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    if let Some(path) = res? {
+        let strings = settings_language.strings();
+        eprintln!("{}", fill(strings.suspended, &[("path", &path.display())]));
+    }
+    Ok(())
+}
+
+fn run_app<B: Backend + Write>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    tick_rate: Duration,
+    mut playback: Option<Playback>,
+    mut recorder: Option<Recorder>,
+    mut autosave: Option<Autosave>,
+    profiles: Profiles,
+) -> Result<Option<PathBuf>, Error> {
+    let mut last_tick = Instant::now();
+    loop {
+        terminal.draw(|f| draw(f, &mut app))?;
+
+        let timeout = tick_rate
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(0));
+        if crossterm::event::poll(timeout)? {
+            let input = match event::read()? {
+                Event::Key(key) => Input::from_key_code(key.code),
+                Event::Mouse(mouse) => Input::from_mouse_event(mouse, &app),
+                Event::Resize(columns, rows) => Some(Input::Resize(columns, rows)),
+            };
+            if let Some(input) = input.filter(|_| app.mode == Mode::Watch) {
+                // Watching is not playing, so the only thing to do is stop
+                if app.keymap.handle(input) == Some(Action::Quit) {
+                    app.should_quit = true;
+                }
+            } else if let Some(input) = input {
+                let at = app.clock.time();
+                handle(&mut app, recorder.as_mut(), at, input)?;
+                app.clock.release();
+            }
+        }
+        for notification in app.notifications.drain(..) {
+            let bells = app.settings.bells.count(notification);
+            let backend = terminal.backend_mut();
+            backend.write_all(&b"\x07".repeat(bells.into()))?;
+            Write::flush(backend)?;
+        }
+        if let Some(text) = app.clipboard_requested.take() {
+            // OSC 52 asks the terminal to set the clipboard, which also works over SSH
+            use base64::Engine;
+            let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+            let backend = terminal.backend_mut();
+            write!(backend, "\x1b]52;c;{encoded}\x07")?;
+            Write::flush(backend)?;
+        }
+        if let Some(format) = app.snapshot_requested.take() {
+            let path = write_snapshot(&mut app, terminal.size()?, format)
+                .map_err(Error::io("failed to save snapshot"))?;
+            app.status_message = Some(fill(app.strings().saved, &[("path", &path.display())]));
+        }
+        if std::mem::take(&mut app.switch_player_requested) {
+            switch_player(&mut app, &profiles)?;
+        }
+        // Replays and games watched are not new results, even when they end in play
+        if app.game_over.is_some() && !app.result_saved {
+            app.result_saved = true;
+            if app.mode != Mode::Playback && app.mode != Mode::Watch {
+                save_result(&mut app, &profiles)?;
+            }
+        }
+        if last_tick.elapsed() >= tick_rate {
+            if let Some(playback) = playback.as_mut() {
+                playback.follow()?;
+                // Replay one recorded input per tick, so playback is watchable, but keep up
+                // with a game being watched however far behind it was joined
+                let due = match app.mode {
+                    Mode::Watch => playback.inputs.len(),
+                    _ => 1,
+                };
+                let due = due.min(playback.inputs.len());
+                let inputs: Vec<_> = playback.inputs.drain(..due).collect();
+                for (at, input) in inputs {
+                    match input {
+                        Input::Resize(columns, rows) => playback.size = Some((columns, rows)),
+                        _ => playback.check_size()?,
+                    }
+                    // Recordings without times replay on the clock as it runs
+                    let at = match at {
+                        Some(at) => at,
+                        None => {
+                            app.clock.release();
+                            app.clock.time()
+                        }
+                    };
+                    handle(&mut app, recorder.as_mut(), at, input)?;
+                }
+                // Games being watched run on between inputs, and recordings played back wait
+                if app.mode == Mode::Watch {
+                    app.clock.carry_on();
+                }
+                let replaying = app.mode == Mode::Playback || app.mode == Mode::Watch;
+                if due == 0 && replaying && !playback.following() {
+                    app.clock.carry_on();
+                    app.mode = if recorder.is_some() {
+                        Mode::Record
+                    } else {
+                        Mode::Play
+                    };
+                }
+            }
+            app.on_tick();
+            if let Some(autosave) = autosave.as_mut() {
+                autosave.update(&app)?;
+            }
+            last_tick = Instant::now();
+        }
+        if app.should_quit {
+            return match autosave.as_mut() {
+                Some(autosave) if app.suspended => autosave.suspend(&app).map(Some),
+                _ => Ok(None),
+            };
+        }
+    }
+}
+
+/// Handle an input with the clock held at the time it was made, recording it if recording.
+fn handle(
+    app: &mut App,
+    recorder: Option<&mut Recorder>,
+    at: Duration,
+    input: Input,
+) -> Result<(), Error> {
+    app.clock.hold(at);
+    if let Some(recorder) = recorder {
+        recorder.record(at, input)?;
+    }
+    input.dispatch(app);
+    // A game ends at the input that finished it, not whenever the next tick comes
+    app.on_tick();
+    Ok(())
+}
+
+/// Play as the player after the current one, in order of name.
+fn switch_player(app: &mut App, profiles: &Profiles) -> Result<(), Error> {
+    let mut names = profiles.names()?;
+    names.sort();
+    let current = app.settings.player.as_ref().map(|player| &player.name);
+    let next = match current.and_then(|current| names.iter().position(|name| name == current)) {
+        Some(position) => names.get(position + 1).or(names.first()),
+        None => names.first(),
+    }
+    .filter(|next| Some(*next) != current);
+    app.status_message = Some(match next {
+        Some(name) => {
+            let player = profiles.open(name, app.settings.scoring)?;
+            tracing::info!(player = %player.name, "switched player");
+            let message = fill(app.strings().playing_as, &[("player", &player.name)]);
+            app.settings.player = Some(player);
+            message
+        }
+        None => app.strings().no_other_players.to_owned(),
+    });
+    Ok(())
+}
+
+/// Keep the finished game in the player's profile, if there is a player.
+fn save_result(app: &mut App, profiles: &Profiles) -> Result<(), Error> {
+    let elapsed = app.elapsed();
+    let strings = app.strings();
+    // Puzzles are not scored like games, nor are games from a board of the player's choosing
+    let puzzle = app.find_all.is_some() || app.quiz.is_some() || app.editor.is_some();
+    let puzzle = puzzle || app.settings.opening.is_some();
+    let Some(player) = app.settings.player.as_mut().filter(|_| !puzzle) else {
+        return Ok(());
+    };
+    let tally = app.game.tally(PLAYER);
+    let score = app.game.score(PLAYER);
+    let result = GameResult {
+        seed: app.seed,
+        scoring: app.settings.scoring.to_string(),
+        score,
+        sets: tally.sets,
+        misclaims: tally.misclaims,
+        hints: app.hints.used(PLAYER),
+        elapsed_ms: elapsed.as_millis() as u64,
+        finished_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+    profiles.record(&player.name, &result)?;
+    if player.best.map_or(score > 0, |best| score > best) {
+        player.best = Some(score);
+        app.status_message = Some(fill(strings.new_best, &[("player", &player.name)]));
+    }
+    Ok(())
+}
+
+/// Play and solve the Set card game
+#[derive(Debug, FromArgs, ArgsInfo)]
+struct Cli {
+    #[argh(subcommand)]
+    command: Option<Command>,
+
+    /// path to a config file (default: $SGS_CONFIG, then config.toml in the platform config
+    /// directory)
+    #[argh(option)]
+    config: Option<PathBuf>,
+
+    /// directory to keep snapshots and other generated files in (default: the platform data
+    /// directory)
+    #[argh(option)]
+    data_dir: Option<PathBuf>,
+
+    /// seed for the game (default: random)
+    #[argh(option)]
+    seed: Option<u64>,
+
+    /// record the seed and all inputs to this file
+    #[argh(option)]
+    record: Option<PathBuf>,
+
+    /// replay the seed and inputs from a recording made with --record
+    #[argh(option)]
+    play: Option<PathBuf>,
+
+    /// follow a recording while another sgs makes it with --record, showing the game live
+    #[argh(option)]
+    watch: Option<PathBuf>,
+
+    /// symbols to draw cards with: 'unicode', 'ascii' or 'braille' (default: detected from the
+    /// terminal)
+    #[argh(option)]
+    glyphs: Option<Glyphs>,
+
+    /// how many times to ring the bell for each event, e.g. 'accepted=1,rejected=2,deal=1'
+    #[argh(option)]
+    bell: Option<Bells>,
+
+    /// never ring the bell
+    #[argh(switch)]
+    quiet: bool,
+
+    /// show each card's notation and position on its tile
+    #[argh(switch)]
+    notation: bool,
+
+    /// draw without color, bracketing each card's symbols to show its color: '()' red, '[]'
+    /// green and '{}' purple
+    #[argh(switch)]
+    monochrome: bool,
+
+    /// language to show the game in, either 'en' or 'de' (default: detected from the locale)
+    #[argh(option)]
+    language: Option<Language>,
+
+    /// find every set on the opening board, which never changes, instead of playing a game
+    #[argh(switch)]
+    find_all: bool,
+
+    /// find every set on a board, each following on from the last by sharing a card or which
+    /// attributes are alike, instead of playing a game
+    #[argh(switch)]
+    chain: bool,
+
+    /// count the sets on boards shown briefly, scored on closeness and speed, instead of
+    /// playing a game
+    #[argh(switch)]
+    count_sets: bool,
+
+    /// assemble a board by hand, then solve, share or play it, instead of playing a game
+    #[argh(switch)]
+    edit: bool,
+
+    /// the board to open with, e.g. one from a real table, as cards in notation or Set with
+    /// Friends codes separated by commas, e.g. '1R#D,2G~S,3P=O'; the rest of the seed's deck is
+    /// dealt after it (default: the seed's opening board)
+    #[argh(option, from_str_fn(parse_board))]
+    board: Option<Vec<Card>>,
+
+    /// open with the board and deck of a position file, e.g. from a real game or another tool,
+    /// instead of dealing from a seed
+    #[argh(option)]
+    position: Option<PathBuf>,
+
+    /// carry on the game suspended or cut short last time
+    #[argh(switch)]
+    resume: bool,
+
+    /// name of the player, whose profile keeps their results (default: whoever played last)
+    #[argh(option)]
+    player: Option<String>,
+
+    /// append logs to this file, filtered by $SGS_LOG (default: 'info')
+    #[argh(option)]
+    log_file: Option<PathBuf>,
+}
+
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand)]
+enum Command {
+    Completions(CompletionsCommand),
+    Config(ConfigCommand),
+    Deal(DealCommand),
+    Puzzle(PuzzleCommand),
+    Schema(SchemaCommand),
+    Solve(SolveCommand),
+    Stats(StatsCommand),
+    Swap(SwapCommand),
+    Verify(VerifyCommand),
+}
+
+/// A board for `--board`, as cards separated by commas, each in notation such as `2R~O` or a
+/// Set with Friends code such as `0121`. The cards must all differ.
+fn parse_board(s: &str) -> Result<Vec<Card>, String> {
+    let cards = s
+        .split(',')
+        .map(str::trim)
+        .filter(|card| !card.is_empty())
+        .map(|card| match card.parse::<Card>() {
+            Ok(card) => Ok(card),
+            Err(error) => {
+                set_game_solver::setwithfriends::decode(card).map_err(|_| error.to_string())
+            }
+        })
+        .collect::<Result<Vec<Card>, _>>()?;
+    if set_game_solver::CardSet::from(&cards[..]).len() != cards.len() {
+        return Err("the board has the same card more than once".to_owned());
+    }
+    Ok(cards)
+}
+
+/// Read and check a position file for `--position`.
+fn load_position(path: &Path) -> Result<Position, Error> {
+    let name = path.display();
+    let text = fs::read_to_string(path).map_err(Error::io(format!("failed to read {name}")))?;
+    Ok(text
+        .parse()
+        .map_err(|error| format!("{name}: invalid position, {error}"))?)
+}
+
+/// A board in the form `--board` takes, e.g. `1R#D,2G~S,3P=O`.
+pub fn format_board(cards: &[Card]) -> String {
+    let cards: Vec<_> = cards.iter().map(Card::to_string).collect();
+    cards.join(",")
+}
+
+/// Send logs to a file, as stdout belongs to the terminal UI.
+fn init_logging(path: &Path) -> Result<(), Error> {
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(Error::io(format!(
+            "failed to open log file {}",
+            path.display()
+        )))?;
+    let filter = tracing_subscriber::EnvFilter::try_from_env("SGS_LOG")
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_ansi(false)
+        .with_writer(std::sync::Mutex::new(file))
+        .init();
+    Ok(())
+}
+
+/// Parse the command line like `argh::from_env`, but exit with the same code as other bad input.
+fn parse_cli() -> Result<Cli, ExitCode> {
+    let args: Vec<String> = std::env::args().collect();
+    let command = Path::new(&args[0])
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("sgs");
+    let strs: Vec<&str> = args[1..].iter().map(String::as_str).collect();
+    Cli::from_args(&[command], &strs).map_err(|early_exit| match early_exit.status {
+        Ok(()) => {
+            println!("{}", early_exit.output);
+            ExitCode::SUCCESS
+        }
+        Err(()) => {
+            eprintln!(
+                "{}\nRun {command} --help for more information.",
+                early_exit.output
+            );
+            Error::Input(String::new()).exit_code()
+        }
+    })
+}
+
+fn main() -> ExitCode {
+    let cli = match parse_cli() {
+        Ok(cli) => cli,
+        Err(code) => return code,
+    };
+    match try_main(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            error.exit_code()
+        }
+    }
+}
+
+fn try_main(cli: Cli) -> Result<(), Error> {
+    if let Some(path) = &cli.log_file {
+        init_logging(path)?;
+    }
+    let config = Config::load(Flags {
+        config: cli.config,
+        seed: cli.seed,
+        data_dir: cli.data_dir,
+        glyphs: cli.glyphs,
+        bell: cli.bell,
+        quiet: cli.quiet,
+        notation: cli.notation,
+        monochrome: cli.monochrome,
+        language: cli.language,
+        player: cli.player,
+    })?;
+    if let Some(command) = cli.command {
+        match command {
+            Command::Completions(command) => command.run(),
+            Command::Config(command) => command.run(&config),
+            Command::Deal(command) => command.run(&config),
+            Command::Puzzle(command) => command.run(&config)?,
+            Command::Schema(command) => command.run(),
+            Command::Solve(command) => command.run()?,
+            Command::Stats(command) => command.run()?,
+            Command::Swap(command) => command.run()?,
+            Command::Verify(command) => command.run()?,
+        }
+        return Ok(());
+    }
+
+    let playback = match (&cli.play, &cli.watch) {
+        (Some(_), Some(_)) => Err("--play cannot be combined with --watch")?,
+        (Some(path), None) => Some(Playback::load(path)?),
+        (None, Some(path)) => Some(Playback::watch(path)?),
+        (None, None) => None,
+    };
+    if let Some(playback) = &playback {
+        playback.check_size()?;
+    }
+    if cli.watch.is_some() && cli.record.is_some() {
+        Err("--watch cannot be combined with --record")?;
+    }
+    let mut settings = config.settings();
+    // A chain is a "find them all" puzzle with an order to find the sets in
+    settings.find_all = cli.find_all || cli.chain;
+    settings.chain = cli.chain;
+    settings.count_sets = cli.count_sets;
+    if settings.find_all && cli.count_sets {
+        Err("--find-all and --chain cannot be combined with --count-sets")?;
+    }
+    if cli.edit && (settings.find_all || cli.count_sets) {
+        Err("--edit cannot be combined with --find-all, --chain or --count-sets")?;
+    }
+    settings.edit = cli.edit;
+    settings.opening = match (cli.board, cli.position) {
+        (Some(_), Some(_)) => Err("--board cannot be combined with --position")?,
+        (Some(cards), None) => Some(Opening::Board(cards)),
+        (None, Some(path)) => {
+            let position = load_position(&path)?;
+            Some(Opening::Position(path, position))
+        }
+        (None, None) => None,
+    };
+    // A chain looks for its own board, and a quiz has many
+    if settings.opening.is_some() && (cli.chain || cli.count_sets) {
+        Err("--board and --position cannot be combined with --chain or --count-sets")?;
+    }
+    let puzzle = settings.find_all || cli.count_sets || cli.edit;
+    if puzzle && (playback.is_some() || cli.record.is_some() || cli.resume) {
+        Err("puzzles cannot be combined with --play, --record or --resume")?;
+    }
+    let recorded = playback.is_some() || cli.record.is_some() || cli.resume;
+    if settings.opening.is_some() && recorded {
+        Err("--board and --position cannot be combined with --play, --record or --resume")?;
+    }
+    let interactive = playback.is_none() && io::stdin().is_terminal();
+    settings.player = choose_player(&config.player.value, &settings, interactive)?;
+    // Asking for a particular game, or a puzzle, means not wanting the interrupted one
+    let recovery = match (&playback, &cli.record, cli.seed, cli.resume) {
+        (None, None, None, true) => Some(
+            Recovery::load(&settings.data_dir)?.ok_or("there is no suspended game to resume")?,
+        ),
+        (_, _, _, true) => Err("--resume cannot be combined with --seed, --play or --record")?,
+        (None, None, None, false)
+            if io::stdin().is_terminal() && !puzzle && settings.opening.is_none() =>
+        {
+            Recovery::load(&settings.data_dir)?
+                .filter(|recovery| offer_resume(recovery, settings.language.strings()))
+        }
+        _ => None,
+    };
+    let seed = match (&playback, cli.seed, &recovery) {
+        (Some(_), Some(_), _) => Err("--seed cannot be combined with --play")?,
+        (Some(playback), None, _) => playback.seed,
+        (None, None, Some(Recovery { record, .. })) => match record.start {
+            Start::Seed(seed) => seed,
+            Start::Deck(_) => config.seed.value.seed(),
+        },
+        (None, _, _) => config.seed.value.seed(),
+    };
+    let recorder = cli
+        .record
+        .as_deref()
+        .map(|path| {
+            let size = term::size().map_err(Error::io("failed to read the terminal size"))?;
+            Recorder::create(path, seed, size)
+        })
+        .transpose()?;
+    let mode = match (&playback, &recovery) {
+        (Some(playback), _) if playback.following() => "watch",
+        (Some(_), _) => "playback",
+        (None, Some(_)) => "resume",
+        (None, None) => "play",
+    };
+    tracing::info!(seed, mode, "starting game");
+    run(
+        seed,
+        config.tick_rate(),
+        settings,
+        playback,
+        recorder,
+        recovery,
+    )?;
+    Ok(())
+}
+
+/// The player asked for, otherwise whoever played last, asking on the terminal if several
+/// people have profiles.
+fn choose_player(
+    name: &str,
+    settings: &Settings,
+    interactive: bool,
+) -> Result<Option<Player>, Error> {
+    let profiles = Profiles::new(&settings.data_dir);
+    if !name.is_empty() {
+        return profiles.open(name, settings.scoring).map(Some);
+    }
+    let names = profiles.names()?;
+    let Some(last) = names.first() else {
+        return Ok(None);
+    };
+    if !interactive || names.len() == 1 {
+        return profiles.open(last, settings.scoring).map(Some);
+    }
+    let strings = settings.language.strings();
+    loop {
+        eprint!(
+            "{}",
+            fill(
+                strings.who_is_playing,
+                &[("players", &names.join(", ")), ("last", last)]
+            )
+        );
+        let mut answer = String::new();
+        io::stdin()
+            .lock()
+            .read_line(&mut answer)
+            .map_err(Error::io("failed to read the player's name"))?;
+        let answer = answer.trim();
+        let name = if answer.is_empty() { last } else { answer };
+        match Profiles::validate(name) {
+            Ok(()) => return profiles.open(name, settings.scoring).map(Some),
+            Err(error) => eprintln!("{error}"),
+        }
+    }
+}
+
+/// Ask on the terminal whether to resume an interrupted game, defaulting to yes.
+fn offer_resume(recovery: &Recovery, strings: &Strings) -> bool {
+    eprint!(
+        "{}",
+        fill(strings.resume, &[("path", &recovery.path.display())])
+    );
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    let answer = answer.trim().to_lowercase();
+    answer.is_empty() || strings.yes.contains(&answer.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// A recording of the lines, sealed with their digest.
+    fn sealed(lines: &[&str]) -> String {
+        let mut chain = Chain::default();
+        let mut text = String::new();
+        for line in lines {
+            chain.link(line);
+            text += &format!("{line}\n");
+        }
+        text + &format!("digest {}\n", chain.digest())
+    }
+
+    fn parse(text: &str) -> Result<Playback, String> {
+        Playback::parse(Path::new("test.inputs"), text).map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn inputs_round_trip() {
+        for input in [
+            Input::Char('q'),
+            Input::Char(' '),
+            Input::Left,
+            Input::Backspace,
+            Input::Mouse(Pointer::Drag, 12, 4),
+            Input::Pointer(Pointer::Down, Some(3)),
+            Input::Pointer(Pointer::Up, None),
+            Input::Resize(100, 30),
+        ] {
+            assert_eq!(input.to_string().parse(), Ok(input));
+        }
+        assert!("pointer:down:left".parse::<Input>().is_err());
+        assert!("resize:100".parse::<Input>().is_err());
+    }
+
+    #[test]
+    fn recordings_keep_times_and_size() {
+        let shuffle = format!("shuffle {SHUFFLE_VERSION}");
+        let header = ["version 4", "seed 7", &shuffle, "size 80x24"];
+        let text = sealed(
+            &[
+                &header[..],
+                &["0 char:?", "1500 pointer:down:3", "2000 resize:100x30"],
+            ]
+            .concat(),
+        );
+        let playback = parse(&text).unwrap();
+        assert_eq!(playback.size, Some((80, 24)));
+        assert_eq!(
+            Vec::from(playback.inputs),
+            vec![
+                (Some(Duration::ZERO), Input::Char('?')),
+                (
+                    Some(Duration::from_millis(1500)),
+                    Input::Pointer(Pointer::Down, Some(3))
+                ),
+                (Some(Duration::from_millis(2000)), Input::Resize(100, 30)),
+            ]
+        );
+
+        let text = sealed(&[&header[..], &["1500 char:q", "1000 char:q"]].concat());
+        assert_eq!(
+            parse(&text).err(),
+            Some("test.inputs:6: made before the input before it".to_owned())
+        );
+        let text = sealed(&[&header[..], &["char:q"]].concat());
+        assert!(parse(&text).is_err());
+        let text = sealed(&["version 4", "seed 7", &shuffle, "char:q"]);
+        assert!(parse(&text).is_err());
+    }
+
+    #[test]
+    fn older_recordings_are_played() {
+        let load = |version| {
+            let path = format!(
+                "{}/fixtures/recording/v{version}.inputs",
+                env!("CARGO_MANIFEST_DIR")
+            );
+            Playback::load(Path::new(&path)).unwrap()
+        };
+        let inputs = vec![
+            (None, Input::Char('?')),
+            (None, Input::Right),
+            (None, Input::Mouse(Pointer::Down, 12, 4)),
+            (None, Input::Mouse(Pointer::Up, 12, 4)),
+            (None, Input::Char('q')),
+        ];
+        for version in 1..=3 {
+            let playback = load(version);
+            assert_eq!(playback.version, version);
+            assert_eq!(playback.seed, 42);
+            assert_eq!(playback.size, None);
+            assert_eq!(playback.digest.is_some(), version >= 3);
+            assert_eq!(Vec::from(playback.inputs), inputs);
+        }
+
+        let playback = load(RECORDING_VERSION);
+        assert_eq!(playback.size, Some((100, 40)));
+        assert!(playback.digest.is_some());
+        let at = |millis| Some(Duration::from_millis(millis));
+        assert_eq!(
+            Vec::from(playback.inputs),
+            vec![
+                (at(0), Input::Char('?')),
+                (at(1200), Input::Right),
+                (at(2500), Input::Pointer(Pointer::Down, Some(4))),
+                (at(2650), Input::Pointer(Pointer::Up, Some(4))),
+                (at(3100), Input::Resize(120, 40)),
+                (at(4000), Input::Char('q')),
+            ]
+        );
+    }
+
+    #[test]
+    fn the_clock_holds_for_inputs() {
+        let mut clock = SessionClock::new();
+        clock.hold(Duration::from_secs(90));
+        assert_eq!(clock.time(), Duration::from_secs(90));
+        assert_eq!(clock.now() - clock.origin, Duration::from_secs(90));
+        clock.carry_on();
+        assert!(clock.time() >= Duration::from_secs(90));
+        assert!(clock.time() < Duration::from_secs(91));
+        clock.hold(Duration::from_secs(1));
+        clock.release();
+        assert!(clock.time() >= Duration::from_secs(90));
+    }
+}
@@ -0,0 +1,61 @@
+//! Rolling measures of how well the player is doing, for those training to play faster.
+//!
+//! Metrics are worked out from the game's events as they happen. A resumed game counts the sets
+//! already found from when its recovery file says they were, but not the claims that were
+//! refused, which are not saved.
+
+use std::time::Duration;
+
+/// How far back the pace of finding sets is measured.
+const WINDOW: Duration = Duration::from_secs(180);
+
+#[derive(Debug, Default, Clone)]
+pub struct Metrics {
+    /// How long into the game each set was found.
+    found: Vec<Duration>,
+    /// Claims that were not sets.
+    rejected: usize,
+    /// When these metrics started counting, e.g. the game was resumed.
+    since: Duration,
+}
+
+impl Metrics {
+    /// Start counting `since` into the game.
+    pub fn since(since: Duration) -> Self {
+        Self {
+            since,
+            ..Self::default()
+        }
+    }
+
+    pub fn found(&mut self, at: Duration) {
+        self.found.push(at);
+    }
+
+    pub fn rejected(&mut self) {
+        self.rejected += 1;
+    }
+
+    /// Sets found per minute over the last few minutes, or none before any time has passed.
+    pub fn sets_per_minute(&self, now: Duration) -> Option<f64> {
+        let start = now.saturating_sub(WINDOW).max(self.since);
+        let window = now.saturating_sub(start);
+        if window.is_zero() {
+            return None;
+        }
+        let sets = self.found.iter().filter(|at| **at > start).count();
+        Some(sets as f64 * 60.0 / window.as_secs_f64())
+    }
+
+    /// The share of claims that were sets, or none before the first claim.
+    pub fn accuracy(&self) -> Option<f64> {
+        let claims = self.found.len() + self.rejected;
+        (claims > 0).then(|| self.found.len() as f64 / claims as f64)
+    }
+
+    /// The average time taken to find each set, or none before the first.
+    pub fn time_per_set(&self) -> Option<Duration> {
+        let last = self.found.last()?;
+        Some(last.saturating_sub(self.since) / self.found.len() as u32)
+    }
+}
@@ -0,0 +1,20 @@
+//! Platform conventions for where files live, e.g. XDG directories on Linux, `Library` on macOS
+//! and `AppData` on Windows.
+
+use std::path::PathBuf;
+
+const APP_NAME: &str = "sgs";
+
+/// The config file read when no other is given, if the platform has a config directory.
+pub fn config_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_NAME).join("config.toml"))
+}
+
+/// Where saved games, scores and other generated files are kept by default.
+///
+/// Falls back to the working directory on platforms without a data directory.
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_NAME))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
@@ -0,0 +1,151 @@
+//! Named player profiles, so that people sharing a machine each keep their own results.
+//!
+//! Each profile is a directory under `<data_dir>/profiles`, holding a line of JSON for every game
+//! finished as that player. Best scores are worked out from them, per way of scoring, so that
+//! scores from different rules are never compared.
+
+use crate::{error::Error, Scoring};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+const RESULTS: &str = "results.jsonl";
+
+/// The player a game is played as, and their best score so far.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Player {
+    pub name: String,
+    /// Their best score with the scoring being played.
+    pub best: Option<u32>,
+}
+
+/// A finished game, as kept in a profile.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct GameResult {
+    pub seed: u64,
+    pub scoring: String,
+    pub score: u32,
+    pub sets: u32,
+    pub misclaims: u32,
+    pub hints: u32,
+    pub elapsed_ms: u64,
+    /// Seconds since the Unix epoch.
+    pub finished_at: u64,
+}
+
+/// Every profile in a data directory.
+#[derive(Debug, Clone)]
+pub struct Profiles {
+    dir: PathBuf,
+}
+
+impl Profiles {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            dir: data_dir.join("profiles"),
+        }
+    }
+
+    /// Names may only use letters, digits, `-` and `_`, as each is a directory name.
+    pub fn validate(name: &str) -> Result<(), String> {
+        let valid = (1..=32).contains(&name.len())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if valid {
+            Ok(())
+        } else {
+            Err(format!(
+                "invalid player name '{name}', expected up to 32 letters, digits, '-' or '_'"
+            ))
+        }
+    }
+
+    /// Every player with a profile, most recently played first.
+    pub fn names(&self) -> Result<Vec<String>, Error> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => {
+                return Err(Error::io(format!("failed to list {}", self.dir.display()))(
+                    error,
+                ))
+            }
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry =
+                entry.map_err(Error::io(format!("failed to list {}", self.dir.display())))?;
+            let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+                continue;
+            };
+            if entry.path().is_dir() && Self::validate(&name).is_ok() {
+                let played = fs::metadata(entry.path().join(RESULTS))
+                    .and_then(|metadata| metadata.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                names.push((played, name));
+            }
+        }
+        names.sort_by(|(a, a_name), (b, b_name)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+        Ok(names.into_iter().map(|(_, name)| name).collect())
+    }
+
+    /// Start playing as the named player, creating their profile if it is new.
+    pub fn open(&self, name: &str, scoring: Scoring) -> Result<Player, Error> {
+        Self::validate(name)?;
+        let dir = self.dir.join(name);
+        fs::create_dir_all(&dir)
+            .map_err(Error::io(format!("failed to create {}", dir.display())))?;
+        Ok(Player {
+            name: name.to_owned(),
+            best: self.best(name, scoring)?,
+        })
+    }
+
+    pub fn results(&self, name: &str) -> Result<Vec<GameResult>, Error> {
+        let path = self.dir.join(name).join(RESULTS);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => {
+                return Err(Error::io(format!("failed to read {}", path.display()))(
+                    error,
+                ))
+            }
+        };
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|error| format!("{} line {}: {error}", path.display(), i + 1).into())
+            })
+            .collect()
+    }
+
+    /// The player's best score with the given scoring, if they have finished a game with it.
+    pub fn best(&self, name: &str, scoring: Scoring) -> Result<Option<u32>, Error> {
+        let scoring = scoring.to_string();
+        Ok(self
+            .results(name)?
+            .iter()
+            .filter(|result| result.scoring == scoring)
+            .map(|result| result.score)
+            .max())
+    }
+
+    pub fn record(&self, name: &str, result: &GameResult) -> Result<(), Error> {
+        let path = self.dir.join(name).join(RESULTS);
+        let line = serde_json::to_string(result).expect("results always serialize");
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| writeln!(file, "{line}"))
+            .map_err(Error::io(format!("failed to save {}", path.display())))
+    }
+}
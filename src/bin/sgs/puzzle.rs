@@ -0,0 +1,543 @@
+//! Generate boards with a chosen number of sets, for solving away from the game.
+//!
+//! Boards are dealt from shuffled decks until one has the sets asked for, except boards with
+//! exactly one set, which are built card by card from a shuffled deck by [`OneSetBoard`] so that
+//! large packs and boards of up to 17 cards come quickly. Chain puzzles are dealt boards whose
+//! sets can all be found in one [`ChainPuzzle`] chain, and list their answers in that order.
+//! `--alike`, `--different` and `--exclude` only keep boards whose every set is of the kind asked
+//! for, as in `sgs solve`.
+//!
+//! `--cache` keeps the puzzles generated and hands them out again when the same ones are asked
+//! for, whatever the output format, rather than searching for them again.
+
+use crate::{cache::Cache, config::Config, error::Error};
+use argh::{ArgsInfo, FromArgs};
+use set_game_solver::{
+    find_sets, Attribute, Card, CardProperties, CardRenderer, ChainPuzzle, Color, Count, Deck,
+    Line, OneSetBoard, SetPattern, Shade, Shape, Triple, SHUFFLE_VERSION,
+};
+use std::{fmt::Write as _, fs, ops::RangeInclusive, path::PathBuf, str::FromStr};
+
+/// Boards to try per puzzle before giving up on the constraints.
+const MAX_ATTEMPTS: usize = 100_000;
+
+/// Generate puzzles: boards with a given number of sets to find
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "puzzle")]
+pub struct PuzzleCommand {
+    /// seed for the first puzzle (default: the configured seed policy)
+    #[argh(option)]
+    seed: Option<u64>,
+
+    /// cards on each board (default: 12)
+    #[argh(option, default = "crate::BOARD_SIZE")]
+    cards: usize,
+
+    /// exact number of sets on each board (default: decided by --difficulty)
+    #[argh(option)]
+    sets: Option<usize>,
+
+    /// how hard to make each board: 'easy' (4 or more sets), 'medium' (2 or 3 sets) or 'hard'
+    /// (1 set) (default: medium)
+    #[argh(option, default = "Difficulty::Medium")]
+    difficulty: Difficulty,
+
+    /// only boards whose sets can all be found in a chain, each sharing a card or which
+    /// attributes are alike with the one before, as played with 'sgs --chain'
+    #[argh(switch)]
+    chain: bool,
+
+    /// only boards whose every set is alike in this attribute: 'color', 'count', 'shade' or
+    /// 'shape', and repeat for more
+    #[argh(option, from_str_fn(crate::solve::parse_attribute))]
+    alike: Vec<Attribute>,
+
+    /// only boards whose every set is different in this attribute, and repeat for more
+    #[argh(option, from_str_fn(crate::solve::parse_attribute))]
+    different: Vec<Attribute>,
+
+    /// only boards without sets of this pattern, e.g. 'all-different', and repeat for more
+    #[argh(option)]
+    exclude: Vec<SetPattern>,
+
+    /// include the sets on each board, e.g. for an answer key
+    #[argh(switch)]
+    answers: bool,
+
+    /// how many puzzles to generate (default: 1)
+    #[argh(option, default = "1")]
+    count: usize,
+
+    /// output format: 'notation', 'json' (one puzzle per line) or 'svg' (default: notation)
+    #[argh(option, default = "Format::Notation")]
+    format: Format,
+
+    /// write the puzzles to this file, e.g. a puzzle pack, instead of stdout
+    #[argh(option)]
+    output: Option<PathBuf>,
+
+    /// keep the puzzles in the data directory, and reuse them when the same seed and options
+    /// ask for them again instead of generating them
+    #[argh(switch)]
+    cache: bool,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// Fewer sets on the board are harder to spot.
+    fn sets(&self) -> RangeInclusive<usize> {
+        match self {
+            Self::Easy => 4..=usize::MAX,
+            Self::Medium => 2..=3,
+            Self::Hard => 1..=1,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Self::Easy),
+            "medium" => Ok(Self::Medium),
+            "hard" => Ok(Self::Hard),
+            _ => Err(format!("expected 'easy', 'medium' or 'hard', got '{s}'")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Format {
+    Notation,
+    Json,
+    Svg,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "notation" => Ok(Self::Notation),
+            "json" => Ok(Self::Json),
+            "svg" => Ok(Self::Svg),
+            _ => Err(format!("expected 'notation', 'json' or 'svg', got '{s}'")),
+        }
+    }
+}
+
+/// A generated board, and the seed of the deck it was dealt or built from.
+struct Puzzle {
+    seed: u64,
+    cards: Vec<Card>,
+    sets: usize,
+    /// Every set on the board, if asked for.
+    answers: Option<Vec<[Card; 3]>>,
+}
+
+impl PuzzleCommand {
+    pub fn run(&self, config: &Config) -> Result<(), Error> {
+        if !(3..=81).contains(&self.cards) {
+            Err(format!(
+                "--cards must be between 3 and 81, got {}",
+                self.cards
+            ))?;
+        }
+        let sets = match self.sets {
+            Some(sets) => sets..=sets,
+            None => self.difficulty.sets(),
+        };
+        let seed = self.seed.unwrap_or_else(|| config.seed.value.seed());
+        let puzzles = if self.cache {
+            let cache = Cache::new(&config.data_dir.value, "puzzle");
+            let key = self.cache_key(seed, &sets);
+            match cache.get(&key).and_then(|cached| parse_json(&cached)) {
+                Some(puzzles) => puzzles,
+                None => {
+                    let puzzles = self.generate(seed, sets)?;
+                    cache.put(&key, &json(&puzzles))?;
+                    puzzles
+                }
+            }
+        } else {
+            self.generate(seed, sets)?
+        };
+        let out = match self.format {
+            Format::Notation => notation(&puzzles),
+            Format::Json => json(&puzzles),
+            Format::Svg => svg(&puzzles),
+        };
+        match &self.output {
+            Some(path) => fs::write(path, out)
+                .map_err(Error::io(format!("failed to write {}", path.display())))?,
+            None => print!("{out}"),
+        }
+        Ok(())
+    }
+
+    /// Every option that decides which puzzles are generated, in a canonical form, e.g. with
+    /// repeated flags sorted, and the shuffle that deals each board from its seed.
+    fn cache_key(&self, seed: u64, sets: &RangeInclusive<usize>) -> String {
+        fn sorted(values: impl Iterator<Item = String>) -> String {
+            let mut values: Vec<_> = values.collect();
+            values.sort();
+            values.dedup();
+            values.join(",")
+        }
+
+        let names = |attributes: &[Attribute]| {
+            sorted(
+                attributes
+                    .iter()
+                    .map(|attribute| attribute.name().to_owned()),
+            )
+        };
+        format!(
+            "seed {seed}\nshuffle {SHUFFLE_VERSION}\ncards {}\nsets {}\nchain {}\nalike {}\ndifferent {}\nexclude {}\n\
+             answers {}\ncount {}",
+            self.cards,
+            describe(sets),
+            self.chain,
+            names(&self.alike),
+            names(&self.different),
+            sorted(self.exclude.iter().map(SetPattern::to_string)),
+            self.answers,
+            self.count
+        )
+    }
+
+    fn generate(&self, seed: u64, sets: RangeInclusive<usize>) -> Result<Vec<Puzzle>, String> {
+        use rand::{Rng, SeedableRng};
+        use rand_pcg::Pcg64;
+
+        // Each board is dealt from its own seed, so `sgs deal` shows the same cards
+        let filter = crate::solve::pattern_filter(&self.alike, &self.different, &self.exclude);
+        let mut rng = Pcg64::seed_from_u64(seed);
+        (0..self.count)
+            .map(|_| {
+                for _ in 0..MAX_ATTEMPTS {
+                    let seed = rng.gen();
+                    let mut deck = Deck::new_from_seed(seed);
+                    let cards: Vec<Card> = if sets == (1..=1) && !self.chain {
+                        // In the order the deck would be dealt
+                        match OneSetBoard::construct(deck.0.into_iter().rev(), self.cards) {
+                            Some(board) => board.cards().to_vec(),
+                            None => continue,
+                        }
+                    } else {
+                        std::iter::from_fn(|| deck.deal())
+                            .take(self.cards)
+                            .collect()
+                    };
+                    let found: Vec<_> = find_sets(&cards).iter().map(Triple::cards).collect();
+                    let wanted = found.iter().all(|[a, b, _]| {
+                        Line::through(*a, *b).is_some_and(|set| filter.allows(set))
+                    });
+                    if sets.contains(&found.len()) && wanted {
+                        let mut answers: Vec<_> = found.clone();
+                        if self.chain {
+                            match ChainPuzzle::new(cards.clone(), found.len()) {
+                                Some(puzzle) => {
+                                    answers = puzzle.chain().iter().map(|set| set.cards()).collect()
+                                }
+                                None => continue,
+                            }
+                        }
+                        let answers = self.answers.then_some(answers);
+                        return Ok(Puzzle {
+                            seed,
+                            cards,
+                            sets: found.len(),
+                            answers,
+                        });
+                    }
+                }
+                Err(format!(
+                    "no board of {} cards with {} sets found in {MAX_ATTEMPTS} attempts",
+                    self.cards,
+                    describe(&sets)
+                ))
+            })
+            .collect()
+    }
+}
+
+fn describe(sets: &RangeInclusive<usize>) -> String {
+    match (*sets.start(), *sets.end()) {
+        (start, end) if start == end => start.to_string(),
+        (start, usize::MAX) => format!("{start} or more"),
+        (start, end) => format!("{start} to {end}"),
+    }
+}
+
+fn notation(puzzles: &[Puzzle]) -> String {
+    let mut out = String::new();
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        if i > 0 {
+            writeln!(out).unwrap();
+        }
+        writeln!(out, "seed {} | {} sets", puzzle.seed, puzzle.sets).unwrap();
+        for row in puzzle.cards.chunks(3) {
+            let row: Vec<_> = row.iter().map(Card::to_string).collect();
+            writeln!(out, "{}", row.join(" ")).unwrap();
+        }
+        if let Some(answers) = &puzzle.answers {
+            writeln!(out, "answers: {}", answers_notation(answers)).unwrap();
+        }
+    }
+    out
+}
+
+/// Sets separated by `;`, as `sgs solve` prints them.
+fn answers_notation(answers: &[[Card; 3]]) -> String {
+    answers
+        .iter()
+        .map(|[a, b, c]| format!("{a} {b} {c}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Puzzles written by [`json`], or none if any line is not one.
+fn parse_json(text: &str) -> Option<Vec<Puzzle>> {
+    fn cards(value: &serde_json::Value) -> Option<Vec<Card>> {
+        value
+            .as_array()?
+            .iter()
+            .map(|card| card.as_str()?.parse().ok())
+            .collect()
+    }
+
+    text.lines()
+        .map(|line| {
+            let puzzle: serde_json::Value = serde_json::from_str(line).ok()?;
+            let answers = match puzzle.get("answers") {
+                Some(answers) => Some(
+                    answers
+                        .as_array()?
+                        .iter()
+                        .map(|set| cards(set)?.try_into().ok())
+                        .collect::<Option<_>>()?,
+                ),
+                None => None,
+            };
+            Some(Puzzle {
+                seed: puzzle.get("seed")?.as_u64()?,
+                cards: cards(puzzle.get("cards")?)?,
+                sets: puzzle.get("sets")?.as_u64()? as usize,
+                answers,
+            })
+        })
+        .collect()
+}
+
+fn json(puzzles: &[Puzzle]) -> String {
+    let mut out = String::new();
+    for puzzle in puzzles {
+        let cards: Vec<_> = puzzle
+            .cards
+            .iter()
+            .map(|card| format!("\"{card}\""))
+            .collect();
+        let answers = match &puzzle.answers {
+            Some(answers) => {
+                let answers: Vec<_> = answers
+                    .iter()
+                    .map(|[a, b, c]| format!("[\"{a}\",\"{b}\",\"{c}\"]"))
+                    .collect();
+                format!(",\"answers\":[{}]", answers.join(","))
+            }
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "{{\"seed\":{},\"sets\":{},\"cards\":[{}]{answers}}}",
+            puzzle.seed,
+            puzzle.sets,
+            cards.join(",")
+        )
+        .unwrap();
+    }
+    out
+}
+
+pub const CARD_WIDTH: u32 = 120;
+pub const CARD_HEIGHT: u32 = 80;
+pub const GAP: u32 = 10;
+const CAPTION_HEIGHT: u32 = 24;
+const SYMBOL_WIDTH: u32 = 24;
+const SYMBOL_HEIGHT: u32 = 56;
+
+fn svg_color(color: Color) -> &'static str {
+    match color {
+        Color::Red => "#d62728",
+        Color::Green => "#2ca02c",
+        Color::Purple => "#7b3294",
+    }
+}
+
+/// Stack every puzzle into one drawing, three cards to a row like the game board.
+fn svg(puzzles: &[Puzzle]) -> String {
+    let width = 3 * CARD_WIDTH + 4 * GAP;
+    let heights: Vec<u32> = puzzles
+        .iter()
+        .map(|puzzle| {
+            let rows = puzzle.cards.len().div_ceil(3) as u32;
+            CAPTION_HEIGHT + rows * (CARD_HEIGHT + GAP) + GAP
+        })
+        .collect();
+    let height: u32 = heights.iter().sum();
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    )
+    .unwrap();
+    svg_defs(&mut out);
+
+    let mut top = 0;
+    for (puzzle, height) in puzzles.iter().zip(heights) {
+        let answers = match &puzzle.answers {
+            Some(answers) => format!(" | answers: {}", answers_notation(answers)),
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            r#"<text x="{GAP}" y="{}" font-family="monospace" font-size="14">seed {} | {} sets{answers}</text>"#,
+            top + CAPTION_HEIGHT - 6,
+            puzzle.seed,
+            puzzle.sets
+        )
+        .unwrap();
+        svg_board(&mut out, &puzzle.cards, top + CAPTION_HEIGHT);
+        top += height;
+    }
+    writeln!(out, "</svg>").unwrap();
+    out
+}
+
+/// The `stripes-<color>` patterns that striped cards fill with.
+pub fn svg_defs(out: &mut String) {
+    writeln!(out, "<defs>").unwrap();
+    for color in Color::iter() {
+        writeln!(
+            out,
+            r#"<pattern id="stripes-{color}" width="4" height="4" patternUnits="userSpaceOnUse"><line x1="0" y1="1" x2="4" y2="1" stroke="{}" stroke-width="1.5"/></pattern>"#,
+            svg_color(color)
+        )
+        .unwrap();
+    }
+    writeln!(out, "</defs>").unwrap();
+}
+
+/// The cards three to a row like the game board, from `top` down.
+pub fn svg_board(out: &mut String, cards: &[Card], top: u32) {
+    for (i, card) in cards.iter().enumerate() {
+        let x = GAP + (i as u32 % 3) * (CARD_WIDTH + GAP);
+        let y = top + (i as u32 / 3) * (CARD_HEIGHT + GAP);
+        writeln!(out, r#"<g transform="translate({x} {y})">"#).unwrap();
+        out.push_str(&SvgCard.render(*card));
+        writeln!(out, "</g>").unwrap();
+    }
+}
+
+/// A card drawn in SVG at the origin, `CARD_WIDTH` by `CARD_HEIGHT`.
+///
+/// Striped cards fill with the `stripes-<color>` patterns, which the drawing must define.
+struct SvgCard;
+
+impl CardRenderer for SvgCard {
+    type Output = String;
+
+    fn render(&self, card: Card) -> String {
+        let mut out = String::new();
+        svg_card(&mut out, card);
+        out
+    }
+}
+
+fn svg_card(out: &mut String, card: Card) {
+    let CardProperties {
+        color,
+        count,
+        shade,
+        shape,
+    } = card.into();
+    writeln!(
+        out,
+        r##"<rect width="{CARD_WIDTH}" height="{CARD_HEIGHT}" rx="8" fill="white" stroke="#888"><title>{card}</title></rect>"##
+    )
+    .unwrap();
+
+    let stroke = svg_color(color);
+    let fill = match shade {
+        Shade::Solid => stroke.to_string(),
+        Shade::Striped => format!("url(#stripes-{color})"),
+        Shade::Open => "none".to_string(),
+    };
+    let count = match count {
+        Count::One => 1,
+        Count::Two => 2,
+        Count::Three => 3,
+    };
+    let spacing = 8;
+    let symbols_width = count * SYMBOL_WIDTH + (count - 1) * spacing;
+    let top = (CARD_HEIGHT - SYMBOL_HEIGHT) / 2;
+    for i in 0..count {
+        let left = (CARD_WIDTH - symbols_width) / 2 + i * (SYMBOL_WIDTH + spacing);
+        let (w, h) = (SYMBOL_WIDTH, SYMBOL_HEIGHT);
+        let style = format!(r#"fill="{fill}" stroke="{stroke}" stroke-width="2""#);
+        match shape {
+            Shape::Diamond => writeln!(
+                out,
+                r#"<polygon points="{},{top} {},{} {},{} {left},{}" {style}/>"#,
+                left + w / 2,
+                left + w,
+                top + h / 2,
+                left + w / 2,
+                top + h,
+                top + h / 2
+            ),
+            Shape::Oval => writeln!(
+                out,
+                r#"<rect x="{left}" y="{top}" width="{w}" height="{h}" rx="{}" {style}/>"#,
+                w / 2
+            ),
+            Shape::Squiggle => writeln!(
+                out,
+                r#"<path d="M{},{} C{},{top} {},{} {},{} C{},{} {},{} {},{} C{left},{} {left},{} {},{} C{},{} {},{} {},{} Z" {style}/>"#,
+                left + 8,
+                top + 2,
+                left + w,
+                left + w,
+                top + 18,
+                left + 17,
+                top + 28,
+                left + 11,
+                top + 36,
+                left + 20,
+                top + 46,
+                left + 16,
+                top + 54,
+                top + h,
+                top + 40,
+                left + 6,
+                top + 28,
+                left + 12,
+                top + 20,
+                left + 4,
+                top + 10,
+                left + 8,
+                top + 2
+            ),
+        }
+        .unwrap();
+    }
+}
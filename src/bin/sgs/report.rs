@@ -0,0 +1,199 @@
+//! Documents for `--report`, laying out results as Markdown or HTML to hand out or publish as
+//! they are, without post-processing JSON.
+//!
+//! Boards are drawn three cards to a row like the game board: as notation in a code block in
+//! Markdown, and as SVG embedded in the page in HTML.
+
+use crate::puzzle::{svg_board, svg_defs, CARD_HEIGHT, CARD_WIDTH, GAP};
+use set_game_solver::Card;
+use std::{fmt::Write, str::FromStr};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            _ => Err(format!("expected 'md' or 'html', got '{s}'")),
+        }
+    }
+}
+
+/// Keeps HTML reports readable without any other styling.
+const STYLE: &str = "body { font-family: sans-serif; max-width: 50em; margin: auto; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #888; padding: 0.2em 0.6em; }
+td { text-align: right; }";
+
+/// A document built up a block at a time.
+#[derive(Debug)]
+pub struct Report {
+    format: ReportFormat,
+    out: String,
+}
+
+impl Report {
+    /// Start a document with its title.
+    pub fn new(format: ReportFormat, title: &str) -> Self {
+        let mut out = String::new();
+        match format {
+            ReportFormat::Markdown => writeln!(out, "# {}\n", markdown(title)),
+            ReportFormat::Html => {
+                let title = html(title);
+                writeln!(
+                    out,
+                    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                     <title>{title}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n\
+                     <h1>{title}</h1>"
+                )
+                .unwrap();
+                // Every board's striped cards share these patterns
+                out.push_str("<svg width=\"0\" height=\"0\">\n");
+                svg_defs(&mut out);
+                writeln!(out, "</svg>")
+            }
+        }
+        .unwrap();
+        Self { format, out }
+    }
+
+    pub fn heading(&mut self, text: &str) {
+        match self.format {
+            ReportFormat::Markdown => writeln!(self.out, "## {}\n", markdown(text)),
+            ReportFormat::Html => writeln!(self.out, "<h2>{}</h2>", html(text)),
+        }
+        .unwrap();
+    }
+
+    pub fn paragraph(&mut self, text: &str) {
+        match self.format {
+            ReportFormat::Markdown => writeln!(self.out, "{}\n", markdown(text)),
+            ReportFormat::Html => writeln!(self.out, "<p>{}</p>", html(text)),
+        }
+        .unwrap();
+    }
+
+    pub fn board(&mut self, cards: &[Card]) {
+        match self.format {
+            ReportFormat::Markdown => {
+                writeln!(self.out, "```text").unwrap();
+                for row in cards.chunks(3) {
+                    let row: Vec<_> = row.iter().map(Card::to_string).collect();
+                    writeln!(self.out, "{}", row.join("  ")).unwrap();
+                }
+                writeln!(self.out, "```\n").unwrap();
+            }
+            ReportFormat::Html => {
+                let width = 3 * CARD_WIDTH + 4 * GAP;
+                let height = cards.len().div_ceil(3) as u32 * (CARD_HEIGHT + GAP) + GAP;
+                writeln!(
+                    self.out,
+                    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+                )
+                .unwrap();
+                svg_board(&mut self.out, cards, GAP);
+                writeln!(self.out, "</svg>").unwrap();
+            }
+        }
+    }
+
+    /// A numbered list of sets, each followed by a note such as its kind.
+    pub fn sets(&mut self, sets: &[([Card; 3], String)]) {
+        if self.format == ReportFormat::Html {
+            writeln!(self.out, "<ol>").unwrap();
+        }
+        for ([a, b, c], note) in sets {
+            match self.format {
+                ReportFormat::Markdown => {
+                    writeln!(self.out, "1. `{a}` `{b}` `{c}` {}", markdown(note))
+                }
+                ReportFormat::Html => {
+                    writeln!(self.out, "<li><code>{a} {b} {c}</code> {}</li>", html(note))
+                }
+            }
+            .unwrap();
+        }
+        match self.format {
+            ReportFormat::Markdown if !sets.is_empty() => writeln!(self.out),
+            ReportFormat::Markdown => Ok(()),
+            ReportFormat::Html => writeln!(self.out, "</ol>"),
+        }
+        .unwrap();
+    }
+
+    /// A table with a header row, where every column after the first holds numbers.
+    pub fn table(&mut self, header: &[&str], rows: &[Vec<String>]) {
+        match self.format {
+            ReportFormat::Markdown => {
+                let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+                let rule = (0..header.len())
+                    .map(|i| if i == 0 { "---" } else { "---:" }.to_owned())
+                    .collect();
+                let header = header.iter().map(|cell| markdown(cell)).collect();
+                writeln!(self.out, "{}", row(header)).unwrap();
+                writeln!(self.out, "{}", row(rule)).unwrap();
+                for cells in rows {
+                    let cells = cells.iter().map(|cell| markdown(cell)).collect();
+                    writeln!(self.out, "{}", row(cells)).unwrap();
+                }
+                writeln!(self.out).unwrap();
+            }
+            ReportFormat::Html => {
+                writeln!(self.out, "<table>").unwrap();
+                let header: Vec<_> = header
+                    .iter()
+                    .map(|cell| format!("<th>{}</th>", html(cell)))
+                    .collect();
+                writeln!(self.out, "<tr>{}</tr>", header.concat()).unwrap();
+                for cells in rows {
+                    let cells: Vec<_> = cells
+                        .iter()
+                        .enumerate()
+                        .map(|(i, cell)| match i {
+                            0 => format!("<th>{}</th>", html(cell)),
+                            _ => format!("<td>{}</td>", html(cell)),
+                        })
+                        .collect();
+                    writeln!(self.out, "<tr>{}</tr>", cells.concat()).unwrap();
+                }
+                writeln!(self.out, "</table>").unwrap();
+            }
+        }
+    }
+
+    /// The finished document.
+    pub fn finish(mut self) -> String {
+        if self.format == ReportFormat::Html {
+            writeln!(self.out, "</body>\n</html>").unwrap();
+        }
+        self.out
+    }
+}
+
+/// Text with the characters HTML gives meaning to escaped.
+fn html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Text with the characters Markdown gives meaning to escaped, such as the `#` and `~` of card
+/// notation.
+fn markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\`*_[]<>#~|".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,36 @@
+//! JSON Schema for the types the library serializes, for writing clients in other languages.
+
+use argh::{ArgsInfo, FromArgs};
+use schemars::{gen::SchemaSettings, schema::Schema};
+use set_game_solver::{Board, BoardDiff, Card, Deck, GameEvent, GameState};
+use std::collections::BTreeMap;
+
+/// Print a JSON Schema document defining cards, boards, game state and game events
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "schema")]
+pub struct SchemaCommand {}
+
+impl SchemaCommand {
+    pub fn run(&self) {
+        let mut generator = SchemaSettings::draft07().into_generator();
+        // Newtypes such as boards are inlined where they are used, so define them by name too.
+        // Named types come back as references to themselves, and are replaced below.
+        let mut definitions: BTreeMap<String, Schema> = BTreeMap::new();
+        definitions.insert("Card".into(), generator.subschema_for::<Card>());
+        definitions.insert("Board".into(), generator.subschema_for::<Board>());
+        definitions.insert("BoardDiff".into(), generator.subschema_for::<BoardDiff>());
+        definitions.insert("Deck".into(), generator.subschema_for::<Deck>());
+        definitions.insert("GameState".into(), generator.subschema_for::<GameState>());
+        definitions.insert("GameEvent".into(), generator.subschema_for::<GameEvent>());
+        definitions.extend(generator.take_definitions());
+        let document = serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "set-game-solver",
+            "definitions": definitions,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document).expect("schemas always serialize")
+        );
+    }
+}
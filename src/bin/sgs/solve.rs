@@ -0,0 +1,344 @@
+//! Find the sets on boards read from files or stdin, one board per line.
+//!
+//! `--trace` also reports to stderr how the solver went about each board: every pair of cards it
+//! examined, the card completing each pair to a set and whether that card was on the board, and
+//! how long each step took. The sets themselves are still printed to stdout as usual.
+//!
+//! `--alike`, `--different` and `--exclude` narrow the sets printed by their [`SetPattern`], e.g.
+//! for drills on one kind of set. `--filter` solves each board among only the cards matching a
+//! [`CardQuery`], e.g. `color=red OR count=1`.
+//!
+//! `--report` writes one document instead, with every board drawn, its sets and their kinds, and
+//! tables of totals over all the boards.
+
+use crate::{
+    error::Error,
+    report::{Report, ReportFormat},
+};
+use argh::{ArgsInfo, FromArgs};
+use set_game_solver::{
+    find_sets_ordered, find_sets_traced, Attribute, Card, CardQuery, Line, PairExamined,
+    ParseCardError, PatternCounts, PatternFilter, SetOrder, SetPattern, Triple,
+};
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// Print the sets on each board, one line of output per line of input
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "solve")]
+pub struct SolveCommand {
+    /// output format: 'notation' (sets separated by ';') or 'json' (default: notation)
+    #[argh(option, default = "Format::Notation")]
+    format: Format,
+
+    /// order of sets on each line: 'board' (by position), 'easiest' or 'hardest' first, where
+    /// sets differing in more attributes are harder to spot (default: board)
+    #[argh(option, default = "SetOrder::Board")]
+    order: SetOrder,
+
+    /// only solve among the cards matching this query, e.g. "color=red AND count!=2", where
+    /// comparisons of color, count, shade or shape combine with AND, OR, NOT and parentheses
+    #[argh(option)]
+    filter: Option<CardQuery>,
+
+    /// only print sets alike in this attribute: 'color', 'count', 'shade' or 'shape', and
+    /// repeat for more
+    #[argh(option, from_str_fn(parse_attribute))]
+    alike: Vec<Attribute>,
+
+    /// only print sets different in this attribute, and repeat for more
+    #[argh(option, from_str_fn(parse_attribute))]
+    different: Vec<Attribute>,
+
+    /// never print sets of this pattern, e.g. 'all-different' or 'color+shade', and repeat for
+    /// more
+    #[argh(option)]
+    exclude: Vec<SetPattern>,
+
+    /// write one document of every board, its sets and totals instead of a line per board:
+    /// 'md' (Markdown) or 'html', which draws the boards
+    #[argh(option)]
+    report: Option<ReportFormat>,
+
+    /// report the pairs the solver examined, which completions were on the board and how long
+    /// each step took, to stderr
+    #[argh(switch)]
+    trace: bool,
+
+    /// files of boards, one per line as cards separated by spaces or commas (default: stdin). To
+    /// mix in stdin, name it '-' after '--', e.g. 'sgs solve -- a.txt -'
+    #[argh(positional)]
+    inputs: Vec<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Format {
+    Notation,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "notation" => Ok(Self::Notation),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("expected 'notation' or 'json', got '{s}'")),
+        }
+    }
+}
+
+impl SolveCommand {
+    pub fn run(&self) -> Result<(), Error> {
+        let stdin = PathBuf::from("-");
+        let inputs = match &self.inputs[..] {
+            [] => std::slice::from_ref(&stdin),
+            inputs => inputs,
+        };
+        let mut out = io::stdout().lock();
+        let mut solved = Vec::new();
+        for input in inputs {
+            let name = if *input == stdin {
+                "stdin".to_string()
+            } else {
+                input.display().to_string()
+            };
+            let reader: Box<dyn BufRead> = if *input == stdin {
+                Box::new(io::stdin().lock())
+            } else {
+                Box::new(BufReader::new(
+                    fs::File::open(input).map_err(Error::io(format!("failed to open {name}")))?,
+                ))
+            };
+            for (number, line) in reader.lines().enumerate() {
+                let line = line.map_err(Error::io(format!("failed to read {name}")))?;
+                let start = Instant::now();
+                let mut cards = parse_board(&line)
+                    .map_err(|error| format!("{name}:{}: {error}", number + 1))?;
+                if let Some(query) = &self.filter {
+                    cards = query.filter(&cards);
+                }
+                let mut trace = self.trace.then(|| Trace {
+                    parse: start.elapsed(),
+                    ..Trace::default()
+                });
+                let source = format!("{name}:{}", number + 1);
+                let sets = self.solve(&cards, trace.as_mut());
+                let start = Instant::now();
+                let rendered = self.report.is_none().then(|| self.render(&sets));
+                if let Some(trace) = &mut trace {
+                    trace.render = start.elapsed();
+                    let mut err = io::stderr().lock();
+                    trace
+                        .write(&mut err, &source, &cards)
+                        .map_err(Error::io("failed to write trace"))?;
+                }
+                match rendered {
+                    // Flush per board so results stream through pipelines as they are found
+                    Some(rendered) => writeln!(out, "{rendered}")
+                        .and_then(|()| out.flush())
+                        .map_err(Error::io("failed to write output"))?,
+                    None => solved.push(Solved {
+                        source,
+                        cards,
+                        sets,
+                    }),
+                }
+            }
+        }
+        if let Some(format) = self.report {
+            write!(out, "{}", report(format, &solved))
+                .map_err(Error::io("failed to write output"))?;
+        }
+        Ok(())
+    }
+
+    /// The sets on the board that the filters allow, in order.
+    fn solve(&self, cards: &[Card], mut trace: Option<&mut Trace>) -> Vec<[Card; 3]> {
+        let start = Instant::now();
+        let filter = pattern_filter(&self.alike, &self.different, &self.exclude);
+        let sets = match trace.as_deref_mut() {
+            Some(trace) => find_sets_traced(cards, self.order, |pair| trace.examined.push(pair)),
+            None => find_sets_ordered(cards, self.order),
+        };
+        let solved = Instant::now();
+        let sets = sets
+            .iter()
+            .map(Triple::cards)
+            .filter(|[a, b, _]| Line::through(*a, *b).is_some_and(|set| filter.allows(set)))
+            .collect();
+        if let Some(trace) = trace {
+            trace.solve = solved - start;
+            trace.filter = solved.elapsed();
+        }
+        sets
+    }
+
+    fn render(&self, sets: &[[Card; 3]]) -> String {
+        let sets = sets.iter();
+        match self.format {
+            Format::Notation => sets
+                .map(|[a, b, c]| format!("{a} {b} {c}"))
+                .collect::<Vec<_>>()
+                .join("; "),
+            Format::Json => {
+                let sets: Vec<_> = sets
+                    .map(|[a, b, c]| format!("[\"{a}\",\"{b}\",\"{c}\"]"))
+                    .collect();
+                format!("[{}]", sets.join(","))
+            }
+        }
+    }
+}
+
+/// A board solved for a report.
+struct Solved {
+    /// Where the board came from, e.g. `stdin:1`.
+    source: String,
+    cards: Vec<Card>,
+    sets: Vec<[Card; 3]>,
+}
+
+/// A document of every board solved, with totals first.
+fn report(format: ReportFormat, solved: &[Solved]) -> String {
+    let mut report = Report::new(format, "Sets found");
+    let mut patterns = PatternCounts::default();
+    for Solved { sets, .. } in solved {
+        for [a, b, _] in sets {
+            patterns.add(line(*a, *b).pattern());
+        }
+    }
+    let boards = solved.len();
+    let no_set = solved.iter().filter(|board| board.sets.is_empty()).count();
+    report.table(
+        &["", "Total"],
+        &[
+            vec!["Boards".to_owned(), boards.to_string()],
+            vec!["Sets".to_owned(), patterns.total().to_string()],
+            vec![
+                "Mean sets per board".to_owned(),
+                format!("{:.2}", patterns.total() as f64 / boards.max(1) as f64),
+            ],
+            vec!["Boards without a set".to_owned(), no_set.to_string()],
+        ],
+    );
+    let rows: Vec<_> = patterns
+        .most_common()
+        .into_iter()
+        .map(|(pattern, sets)| vec![pattern.to_string(), sets.to_string()])
+        .collect();
+    if !rows.is_empty() {
+        report.heading("Kinds of set");
+        report.table(&["Kind", "Sets"], &rows);
+    }
+    for Solved {
+        source,
+        cards,
+        sets,
+    } in solved
+    {
+        report.heading(source);
+        report.board(cards);
+        report.paragraph(&format!("{} cards, {} sets", cards.len(), sets.len()));
+        let sets: Vec<_> = sets
+            .iter()
+            .map(|set @ [a, b, _]| (*set, line(*a, *b).describe()))
+            .collect();
+        report.sets(&sets);
+    }
+    report.finish()
+}
+
+/// The set two cards make with their third.
+fn line(a: Card, b: Card) -> Line {
+    Line::through(a, b).expect("the cards of a set differ")
+}
+
+/// An attribute by its name, for flags.
+pub fn parse_attribute(name: &str) -> Result<Attribute, String> {
+    Attribute::iter()
+        .find(|attribute| attribute.name() == name)
+        .ok_or_else(|| format!("expected 'color', 'count', 'shade' or 'shape', got '{name}'"))
+}
+
+/// The filter asked for by `--alike`, `--different` and `--exclude`.
+pub fn pattern_filter(
+    alike: &[Attribute],
+    different: &[Attribute],
+    exclude: &[SetPattern],
+) -> PatternFilter {
+    let filter = alike
+        .iter()
+        .fold(PatternFilter::default(), |filter, attribute| {
+            filter.alike(*attribute)
+        });
+    let filter = different
+        .iter()
+        .fold(filter, |filter, attribute| filter.different(*attribute));
+    exclude
+        .iter()
+        .fold(filter, |filter, pattern| filter.excluding(*pattern))
+}
+
+/// What the solver did for one board.
+#[derive(Debug, Default)]
+pub struct Trace {
+    /// Every pair of cards examined, in order.
+    pub examined: Vec<PairExamined>,
+    pub parse: Duration,
+    pub solve: Duration,
+    pub filter: Duration,
+    pub render: Duration,
+}
+
+impl Trace {
+    /// One line per pair, then a summary, e.g.
+    /// `stdin:1: 1R#D 2G~S -> 3P=O at 7`.
+    fn write(&self, out: &mut impl Write, board: &str, cards: &[Card]) -> io::Result<()> {
+        for PairExamined {
+            pair: [a, b],
+            completion,
+            hit,
+        } in &self.examined
+        {
+            let (a, b) = (cards[*a], cards[*b]);
+            match hit {
+                Some(c) => writeln!(out, "{board}: {a} {b} -> {completion} at {}", c + 1)?,
+                None => writeln!(
+                    out,
+                    "{board}: {a} {b} -> {completion} not later on the board"
+                )?,
+            }
+        }
+        let hits = self
+            .examined
+            .iter()
+            .filter(|pair| pair.hit.is_some())
+            .count();
+        writeln!(
+            out,
+            "{board}: {} cards, {} pairs examined, {hits} completions on the board",
+            cards.len(),
+            self.examined.len(),
+        )?;
+        writeln!(
+            out,
+            "{board}: parse {:?}, solve {:?}, filter {:?}, render {:?}",
+            self.parse, self.solve, self.filter, self.render,
+        )
+    }
+}
+
+/// Parse a board of cards in notation, separated by whitespace and/or commas.
+fn parse_board(line: &str) -> Result<Vec<Card>, ParseCardError> {
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|card| !card.is_empty())
+        .map(str::parse)
+        .collect()
+}
@@ -0,0 +1,362 @@
+//! Statistics on opening boards across many seeds, for the curious and as a check on the shuffle.
+//!
+//! Each seed's board is dealt through the same path as the game. Over enough seeds the counts
+//! should settle near the known figures for 12 random cards, e.g. an extra deal needed at once
+//! about 3% of the time, so a drift suggests a change to dealing or set finding.
+//!
+//! `--report` lays the same tables out as a Markdown or HTML document.
+//!
+//! Seeds are dealt on `--jobs` threads, each taking a run of consecutive seeds, and their counts
+//! are added together, so the figures are the same however many threads there are. A progress bar
+//! with the time left is drawn on stderr while they work, if it is a terminal.
+
+use crate::{
+    error::Error,
+    report::{Report, ReportFormat},
+};
+use argh::{ArgsInfo, FromArgs};
+use set_game_solver::{Card, Deck, PatternCounts, SetPattern};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{self, IsTerminal, Write as _},
+    num::NonZeroUsize,
+    ops::Range,
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Width of the longest bar in a histogram.
+const BAR_WIDTH: usize = 40;
+/// Kinds of set listed in the report.
+const TOP_PATTERNS: usize = 5;
+/// Width of the progress bar.
+const PROGRESS_WIDTH: usize = 30;
+/// How often the progress bar is redrawn.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Report on the opening boards of many seeds: how many sets they hold and of which kinds
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "stats")]
+pub struct StatsCommand {
+    /// how many seeds to deal (default: 10000)
+    #[argh(option, default = "10_000")]
+    seeds: u64,
+
+    /// the first seed, followed by the next ones in order (default: 0)
+    #[argh(option, default = "0")]
+    from: u64,
+
+    /// output format: 'text' or 'json' (default: text)
+    #[argh(option, default = "Format::Text")]
+    format: Format,
+
+    /// write a document of the same tables instead: 'md' (Markdown) or 'html'
+    #[argh(option)]
+    report: Option<ReportFormat>,
+
+    /// threads to deal on (default: one per core)
+    #[argh(option)]
+    jobs: Option<NonZeroUsize>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("expected 'text' or 'json', got '{s}'")),
+        }
+    }
+}
+
+/// Counts over every board dealt.
+#[derive(Debug, Default)]
+struct Stats {
+    boards: u64,
+    /// Boards by how many sets they hold.
+    sets: BTreeMap<usize, u64>,
+    patterns: PatternCounts,
+}
+
+impl Stats {
+    fn add(&mut self, cards: &[Card]) {
+        let patterns = PatternCounts::on_board(cards);
+        self.boards += 1;
+        *self.sets.entry(patterns.total()).or_default() += 1;
+        self.patterns.merge(&patterns);
+    }
+
+    /// Add the counts from another run of seeds.
+    fn merge(&mut self, other: Self) {
+        self.boards += other.boards;
+        for (sets, boards) in other.sets {
+            *self.sets.entry(sets).or_default() += boards;
+        }
+        self.patterns.merge(&other.patterns);
+    }
+
+    fn share(&self, count: u64) -> f64 {
+        100.0 * count as f64 / self.boards.max(1) as f64
+    }
+
+    fn mean_sets(&self) -> f64 {
+        let total: u64 = self
+            .sets
+            .iter()
+            .map(|(sets, boards)| *sets as u64 * boards)
+            .sum();
+        total as f64 / self.boards.max(1) as f64
+    }
+
+    /// Boards that need an extra deal before anything can be claimed.
+    fn no_set(&self) -> u64 {
+        self.sets.get(&0).copied().unwrap_or_default()
+    }
+
+    /// The most common kinds of set first.
+    fn top_patterns(&self) -> Vec<(SetPattern, usize)> {
+        let mut patterns = self.patterns.most_common();
+        patterns.truncate(TOP_PATTERNS);
+        patterns
+    }
+}
+
+/// The attributes alike on every card, e.g. `color, shade`.
+fn alike(pattern: SetPattern) -> Vec<&'static str> {
+    pattern.alike().map(|attribute| attribute.name()).collect()
+}
+
+/// The seeds one of `jobs` workers deals, as a run of consecutive seeds as even as they divide.
+fn run_for(job: u64, jobs: u64, seeds: u64) -> Range<u64> {
+    // Never more than `seeds`, so this cannot overflow however many seeds are asked for
+    let start = |job: u64| seeds / jobs * job + job.min(seeds % jobs);
+    start(job)..start(job + 1)
+}
+
+fn bar(count: u64, max: u64) -> String {
+    let width = (count as f64 / max.max(1) as f64 * BAR_WIDTH as f64).round() as usize;
+    // Never hide a count that is there at all
+    let width = if count > 0 { width.max(1) } else { 0 };
+    "\u{2588}".repeat(width)
+}
+
+impl StatsCommand {
+    pub fn run(&self) -> Result<(), Error> {
+        if self.seeds == 0 {
+            Err("--seeds must be at least 1")?;
+        }
+        let jobs = self
+            .jobs
+            .or_else(|| thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get) as u64;
+        let done = AtomicU64::new(0);
+        let stats = thread::scope(|scope| {
+            let workers: Vec<_> = (0..jobs)
+                .map(|job| {
+                    let done = &done;
+                    scope.spawn(move || self.deal(run_for(job, jobs, self.seeds), done))
+                })
+                .collect();
+            if io::stderr().is_terminal() {
+                let started = Instant::now();
+                while !workers.iter().all(|worker| worker.is_finished()) {
+                    progress(done.load(Ordering::Relaxed), self.seeds, started.elapsed());
+                    thread::sleep(PROGRESS_INTERVAL);
+                }
+                // Clear the bar
+                eprint!("\r\x1b[K");
+            }
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("dealing does not panic"))
+                .fold(Stats::default(), |mut stats, run| {
+                    stats.merge(run);
+                    stats
+                })
+        });
+        print!(
+            "{}",
+            match (self.report, self.format) {
+                (Some(format), _) => self.report(&stats, format),
+                (None, Format::Text) => self.text(&stats),
+                (None, Format::Json) => self.json(&stats),
+            }
+        );
+        Ok(())
+    }
+
+    fn text(&self, stats: &Stats) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "Opening boards of {} seeds from {}\n",
+            stats.boards, self.from
+        )
+        .unwrap();
+
+        writeln!(out, "Sets on the board").unwrap();
+        let max = stats.sets.values().copied().max().unwrap_or_default();
+        for (sets, boards) in &stats.sets {
+            writeln!(
+                out,
+                "{sets:>3} | {:<BAR_WIDTH$} {boards:>7} {:>5.1}%",
+                bar(*boards, max),
+                stats.share(*boards)
+            )
+            .unwrap();
+        }
+        writeln!(out, "mean {:.2} sets per board\n", stats.mean_sets()).unwrap();
+
+        writeln!(
+            out,
+            "Extra deal needed at once: {:.1}% ({} of {} boards)\n",
+            stats.share(stats.no_set()),
+            stats.no_set(),
+            stats.boards
+        )
+        .unwrap();
+
+        writeln!(out, "Most common kinds of set").unwrap();
+        let total = stats.patterns.total();
+        for (pattern, sets) in stats.top_patterns() {
+            let alike = alike(pattern);
+            let label = if alike.is_empty() {
+                "all different".to_owned()
+            } else {
+                format!("{} alike", alike.join(", "))
+            };
+            writeln!(
+                out,
+                "{label:<24} {sets:>7} {:>5.1}%",
+                100.0 * sets as f64 / total.max(1) as f64
+            )
+            .unwrap();
+        }
+        out
+    }
+
+    /// Deal the opening boards of a run of seeds, counting each board dealt in `done`.
+    fn deal(&self, seeds: std::ops::Range<u64>, done: &AtomicU64) -> Stats {
+        let mut stats = Stats::default();
+        for i in seeds {
+            let mut deck = Deck::new_from_seed(self.from.wrapping_add(i));
+            let cards: Vec<Card> = std::iter::from_fn(|| deck.deal())
+                .take(crate::BOARD_SIZE)
+                .collect();
+            stats.add(&cards);
+            done.fetch_add(1, Ordering::Relaxed);
+        }
+        stats
+    }
+
+    fn report(&self, stats: &Stats, format: ReportFormat) -> String {
+        let title = format!(
+            "Opening boards of {} seeds from {}",
+            stats.boards, self.from
+        );
+        let mut report = Report::new(format, &title);
+
+        report.heading("Sets on the board");
+        let rows: Vec<_> = stats
+            .sets
+            .iter()
+            .map(|(sets, boards)| {
+                let share = format!("{:.1}%", stats.share(*boards));
+                vec![sets.to_string(), boards.to_string(), share]
+            })
+            .collect();
+        report.table(&["Sets", "Boards", "Share"], &rows);
+        report.paragraph(&format!(
+            "Mean {:.2} sets per board. Extra deal needed at once: {:.1}% ({} of {} boards).",
+            stats.mean_sets(),
+            stats.share(stats.no_set()),
+            stats.no_set(),
+            stats.boards
+        ));
+
+        report.heading("Most common kinds of set");
+        let total = stats.patterns.total();
+        let rows: Vec<_> = stats
+            .top_patterns()
+            .into_iter()
+            .map(|(pattern, sets)| {
+                let share = format!("{:.1}%", 100.0 * sets as f64 / total.max(1) as f64);
+                vec![pattern.to_string(), sets.to_string(), share]
+            })
+            .collect();
+        report.table(&["Kind", "Sets", "Share"], &rows);
+        report.finish()
+    }
+
+    fn json(&self, stats: &Stats) -> String {
+        let sets: serde_json::Map<_, _> = stats
+            .sets
+            .iter()
+            .map(|(sets, boards)| (sets.to_string(), (*boards).into()))
+            .collect();
+        let patterns: Vec<_> = stats
+            .top_patterns()
+            .into_iter()
+            .map(|(pattern, sets)| serde_json::json!({ "alike": alike(pattern), "sets": sets }))
+            .collect();
+        let report = serde_json::json!({
+            "from": self.from,
+            "boards": stats.boards,
+            "sets": sets,
+            "mean_sets": stats.mean_sets(),
+            "extra_deal": stats.no_set() as f64 / stats.boards.max(1) as f64,
+            "patterns": patterns,
+        });
+        format!("{report}\n")
+    }
+}
+
+/// Draw how many boards are dealt on stderr, with the time left at the rate so far, e.g.
+/// `[#########                     ]  30% 3000/10000, 7s left`.
+fn progress(done: u64, total: u64, elapsed: Duration) {
+    let filled = (done as f64 / total as f64 * PROGRESS_WIDTH as f64) as usize;
+    let left = match done {
+        0 => "?".to_owned(),
+        _ => format!(
+            "{}s",
+            (elapsed.as_secs_f64() * (total - done) as f64 / done as f64).ceil()
+        ),
+    };
+    let mut err = io::stderr().lock();
+    let _ = write!(
+        err,
+        "\r[{:<PROGRESS_WIDTH$}] {:>3}% {done}/{total}, {left} left",
+        "#".repeat(filled),
+        100 * done / total
+    );
+    let _ = err.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn runs_cover_every_seed_once() {
+        let runs =
+            |jobs, seeds| -> Vec<_> { (0..jobs).map(|job| run_for(job, jobs, seeds)).collect() };
+        assert_eq!(runs(3, 10), vec![0..4, 4..7, 7..10]);
+        assert_eq!(runs(4, 2), vec![0..1, 1..2, 2..2, 2..2]);
+        assert_eq!(
+            runs(2, u64::MAX),
+            vec![0..u64::MAX / 2 + 1, u64::MAX / 2 + 1..u64::MAX]
+        );
+    }
+}
@@ -0,0 +1,35 @@
+//! Suggest ways to edit a board one card at a time, for writing puzzles by hand.
+
+use crate::error::Error;
+use argh::{ArgsInfo, FromArgs};
+use set_game_solver::{suggest_swaps, Card, CardSet, SwapTarget};
+
+/// Suggest single-card swaps that give a board a property, e.g. exactly one set
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "swap")]
+pub struct SwapCommand {
+    /// what the board should be like after the swap: 'no-set', 'one-set', or 'through=<card>'
+    /// for more sets through that card
+    #[argh(option)]
+    target: SwapTarget,
+
+    /// the board, as cards in notation, e.g. '1R#D 2G~S 3P=O'
+    #[argh(positional)]
+    cards: Vec<Card>,
+}
+
+impl SwapCommand {
+    pub fn run(&self) -> Result<(), Error> {
+        if CardSet::from(&self.cards[..]).len() != self.cards.len() {
+            Err("the board has the same card more than once")?;
+        }
+        let swaps = suggest_swaps(&self.cards, self.target);
+        if swaps.is_empty() {
+            Err(format!("no single swap makes the board {}", self.target))?;
+        }
+        for swap in swaps {
+            println!("{}: {} -> {}", swap.position + 1, swap.removed, swap.added);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,328 @@
+//! Check a game before accepting it onto a leaderboard, or when debugging another client.
+//!
+//! A recording is checked to be complete and unchanged since it was made. A game in Set Game
+//! Notation is played again by the rules: every claim must be a set on the board as it was then,
+//! every misclaim must not be a set, every hint must be allowed and point at a card in a set, and
+//! extra cards must be left to deal. Records saved with move times, such as recovery files, are
+//! also checked to be in order of time, with hints no closer together than the cooldown, and
+//! claims earn their speed bonuses at the times they were made.
+//!
+//! A game is played by the scoring and hint rules it was saved with. Records that do not keep
+//! them are played by the rules given as flags, or else the defaults, never by the config, so
+//! that a game checks out the same way for everyone.
+
+use crate::{autosave::Recovery, error::Error, Playback, Scoring, PLAYER};
+use argh::{ArgsInfo, FromArgs};
+use set_game_solver::{
+    find_sets, CardSet, ClaimError, Deck, GameState, HintError, HintRules, Hints, Move,
+    ScoringPolicy, Start, Triple,
+};
+use std::{fmt::Display, path::PathBuf, time::Duration};
+
+/// Check that a recording made with --record has not been changed, or that a game in Set Game
+/// Notation (a .sgn file) was played by the rules
+#[derive(Debug, FromArgs, ArgsInfo)]
+#[argh(subcommand, name = "verify")]
+pub struct VerifyCommand {
+    /// the score the game claims, which its moves must earn exactly (only for .sgn files)
+    #[argh(option)]
+    score: Option<u32>,
+
+    /// the scoring the game was played with, if the .sgn file does not say: classic, speed,
+    /// strict or streak (default: classic)
+    #[argh(option)]
+    scoring: Option<Scoring>,
+
+    /// how many hints the game allowed, if the .sgn file does not say (default: 3)
+    #[argh(option)]
+    hints: Option<u32>,
+
+    /// seconds the game made players wait between hints, if the .sgn file does not say
+    /// (default: 30)
+    #[argh(option)]
+    hint_cooldown_secs: Option<u64>,
+
+    /// points each hint cost, if the .sgn file does not say (default: 1)
+    #[argh(option)]
+    hint_cost: Option<u32>,
+
+    /// the recording, or the game in Set Game Notation
+    #[argh(positional)]
+    file: PathBuf,
+}
+
+impl VerifyCommand {
+    pub fn run(&self) -> Result<(), Error> {
+        if self.file.extension() == Some("sgn".as_ref()) {
+            return self.game();
+        }
+        if self.score.is_some() {
+            Err("--score can only be checked for games in Set Game Notation")?;
+        }
+        if self.scoring.is_some()
+            || self.hints.is_some()
+            || self.hint_cooldown_secs.is_some()
+            || self.hint_cost.is_some()
+        {
+            Err("rules can only be given for games in Set Game Notation")?;
+        }
+        self.recording()
+    }
+
+    fn recording(&self) -> Result<(), Error> {
+        let name = self.file.display();
+        // Loading checks the digest against everything before it
+        let playback = Playback::load(&self.file)?;
+        let Some(digest) = playback.digest else {
+            if playback.version < 3 {
+                Err(format!(
+                    "{name}: recorded before recordings had digests, so it cannot be verified"
+                ))?;
+            }
+            Err(format!(
+                "{name}: has no digest, so the session was cut off or the end was removed"
+            ))?
+        };
+        println!(
+            "{name}: ok, seed {}, {} inputs, digest {digest}",
+            playback.seed,
+            playback.inputs.len()
+        );
+        Ok(())
+    }
+
+    fn game(&self) -> Result<(), Error> {
+        let name = self.file.display();
+        let recovery = Recovery::read(self.file.clone())?;
+        let (scoring, hints) = self
+            .rules(&recovery)
+            .map_err(|error| format!("{name}: {error}"))?;
+        let game =
+            play(&recovery, scoring.policy(), hints).map_err(|error| format!("{name}: {error}"))?;
+        let score = game.score(PLAYER);
+        if let Some(claimed) = self.score.filter(|claimed| *claimed != score) {
+            Err(format!(
+                "{name}: claims a score of {claimed}, but its moves earn {score}"
+            ))?;
+        }
+        println!(
+            "{name}: ok, {} moves{}, {}, score {score}",
+            recovery.record.moves.len(),
+            if recovery.times.is_empty() {
+                ""
+            } else {
+                " on time"
+            },
+            if game.is_over() {
+                "finished"
+            } else {
+                "unfinished"
+            },
+        );
+        Ok(())
+    }
+
+    /// The rules the game was played by: those it was saved with, else those given, else the
+    /// defaults. Rules given that differ from those saved are an error rather than ignored.
+    fn rules(&self, recovery: &Recovery) -> Result<(Scoring, HintRules), String> {
+        let saved = recovery.hints;
+        let default = HintRules::default();
+        // Compared in seconds, as given, but kept as saved, which may be part of a second
+        let cooldown = rule(
+            saved.map(|hints| hints.cooldown.as_secs_f64()),
+            self.hint_cooldown_secs.map(|secs| secs as f64),
+            default.cooldown.as_secs_f64(),
+            "seconds between hints",
+        )?;
+        let hints = HintRules {
+            allowed: rule(
+                saved.map(|hints| hints.allowed),
+                self.hints,
+                default.allowed,
+                "hints",
+            )?,
+            cooldown: saved.map_or(Duration::from_secs_f64(cooldown), |hints| hints.cooldown),
+            cost: rule(
+                saved.map(|hints| hints.cost),
+                self.hint_cost,
+                default.cost,
+                "points per hint",
+            )?,
+        };
+        let scoring = rule(
+            recovery.scoring,
+            self.scoring,
+            Scoring::default(),
+            "scoring",
+        )?;
+        Ok((scoring, hints))
+    }
+}
+
+/// One rule the game was played by, checking that any given agrees with any saved.
+fn rule<T: PartialEq + Display>(
+    saved: Option<T>,
+    given: Option<T>,
+    default: T,
+    what: &str,
+) -> Result<T, String> {
+    match (saved, given) {
+        (Some(saved), Some(given)) if saved != given => Err(format!(
+            "{what}: the game was played with {saved}, not {given}"
+        )),
+        (Some(rule), _) | (None, Some(rule)) => Ok(rule),
+        (None, None) => Ok(default),
+    }
+}
+
+/// Play the game's moves by the rules, at the times they were made if the record keeps them,
+/// returning the game as it was left or what was wrong with the first move that broke a rule.
+fn play(
+    recovery: &Recovery,
+    scoring: ScoringPolicy,
+    rules: HintRules,
+) -> Result<GameState, String> {
+    let Recovery {
+        record,
+        elapsed,
+        times,
+        ..
+    } = recovery;
+    let deck = match &record.start {
+        Start::Seed(seed) => Deck::new_from_seed(*seed),
+        Start::Deck(cards) => {
+            let in_deck = CardSet::from(&cards[..]);
+            if in_deck.len() != cards.len() || !in_deck.complement().is_empty() {
+                return Err("the deck does not have every card exactly once".to_owned());
+            }
+            // Records list the first card first, decks deal from the end
+            Deck(cards.iter().rev().copied().collect())
+        }
+    };
+    if !times.is_empty() && times.len() != record.moves.len() {
+        return Err(format!(
+            "has times for {} moves, but {} moves",
+            times.len(),
+            record.moves.len()
+        ));
+    }
+    let mut game = GameState::new(deck).with_scoring(scoring);
+    let mut hints = Hints::new(rules);
+    let mut last = Duration::ZERO;
+    for (number, played) in record.moves.iter().enumerate() {
+        let broken = |rule: String| format!("move {}: {rule}", number + 1);
+        let at = times.get(number).copied();
+        if let Some(at) = at {
+            if at < last {
+                return Err(broken("made before the move before it".to_owned()));
+            }
+            if at > *elapsed {
+                return Err(broken("made after the game was saved".to_owned()));
+            }
+            last = at;
+        }
+        if game.is_over() {
+            return Err(broken(ClaimError::GameOver.to_string()));
+        }
+        match played {
+            Move::Set([a, b, c]) => {
+                let triple = Triple::from((*a, *b, *c));
+                match at {
+                    Some(at) => game.try_claim_at(PLAYER, triple, at),
+                    None => game.try_claim(PLAYER, triple),
+                }
+                .map_err(|error| broken(error.to_string()))?;
+            }
+            Move::Misclaim([a, b, c]) => {
+                let triple = Triple::from((*a, *b, *c));
+                match at {
+                    Some(at) => game.try_claim_at(PLAYER, triple, at),
+                    None => game.try_claim(PLAYER, triple),
+                }
+                .map_or_else(
+                    |error| match error {
+                        ClaimError::NotASet { .. } => Ok(()),
+                        error => Err(broken(error.to_string())),
+                    },
+                    |_| Err(broken(format!("{a} {b} {c} is a set, not a misclaim"))),
+                )?;
+            }
+            Move::DealExtra => {
+                if game.deal_extra().is_empty() {
+                    return Err(broken("there were no cards left to deal".to_owned()));
+                }
+            }
+            Move::Hint(card) => {
+                let ready_at = hints
+                    .ready_at(PLAYER)
+                    .ok_or_else(|| broken(HintError::NoneLeft.to_string()))?;
+                if at.is_some_and(|at| at < ready_at) {
+                    return Err(broken(HintError::CoolingDown { ready_at }.to_string()));
+                }
+                if !find_sets(game.board().cards())
+                    .iter()
+                    .any(|set| set.cards().contains(card))
+                {
+                    return Err(broken(format!("{card} is not in a set on the board")));
+                }
+                hints.restore(&mut game, PLAYER, *card, at.unwrap_or(last));
+            }
+        }
+    }
+    Ok(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn rules_saved_with_the_game_win() {
+        assert_eq!(
+            rule(Some(Scoring::Speed), None, Scoring::Classic, "scoring"),
+            Ok(Scoring::Speed)
+        );
+        assert_eq!(rule(None, Some(0), 3, "hints"), Ok(0));
+        assert_eq!(rule(None, None, 3, "hints"), Ok(3));
+        assert_eq!(rule(Some(2), Some(2), 3, "hints"), Ok(2));
+        assert_eq!(
+            rule(
+                Some(Scoring::Speed),
+                Some(Scoring::Classic),
+                Scoring::Classic,
+                "scoring"
+            ),
+            Err("scoring: the game was played with speed, not classic".to_owned())
+        );
+    }
+
+    #[test]
+    fn misclaims_are_charged() {
+        let read = |version| {
+            let path = format!("{}/fixtures/sgn/v{version}.sgn", env!("CARGO_MANIFEST_DIR"));
+            Recovery::read(PathBuf::from(path)).unwrap()
+        };
+        let score = |version| {
+            let game = play(
+                &read(version),
+                Scoring::Strict.policy(),
+                HintRules::default(),
+            );
+            game.unwrap().score(PLAYER)
+        };
+        // Two sets, less a hint, and less the misclaim in version 3
+        assert_eq!((score(2), score(3)), (1, 0));
+
+        // Only a record built by hand can hold a set as a misclaim
+        let mut recovery = read(3);
+        let Move::Set(set) = recovery.record.moves[4] else {
+            panic!("the last move is a set");
+        };
+        recovery.record.moves[2] = Move::Misclaim(set);
+        assert_eq!(
+            play(&recovery, ScoringPolicy::default(), HintRules::default()).err(),
+            Some("move 3: 3P~O 1R~S 2G~D is a set, not a misclaim".to_owned())
+        );
+    }
+}
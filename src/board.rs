@@ -0,0 +1,137 @@
+use crate::{
+    core::{Card, Triple},
+    solver::find_sets,
+};
+
+/// The cards face up on the table, in the order they are laid out.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Board(pub(crate) Vec<Card>);
+
+impl From<Vec<Card>> for Board {
+    fn from(cards: Vec<Card>) -> Self {
+        Self(cards)
+    }
+}
+
+impl FromIterator<Card> for Board {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        Self(cards.into_iter().collect())
+    }
+}
+
+impl Board {
+    pub fn cards(&self) -> &[Card] {
+        &self.0
+    }
+
+    /// Every set on the board, in board order, as for [`find_sets`](crate::find_sets).
+    pub fn sets(&self) -> Vec<Triple> {
+        find_sets(&self.0)
+    }
+
+    /// The cards to take away from this board and add to it to reach `other`.
+    ///
+    /// Positions are ignored, so cards that only moved are not reported.
+    pub fn diff(&self, other: &Self) -> BoardDiff {
+        let missing_from = |board: &Self, cards: &[Card]| {
+            cards
+                .iter()
+                .filter(|card| !board.0.contains(card))
+                .copied()
+                .collect()
+        };
+        BoardDiff {
+            added: missing_from(self, &other.0),
+            removed: missing_from(other, &self.0),
+        }
+    }
+
+    /// This board's cards in the places they had in `shown`, an earlier board laid out in
+    /// another order, e.g. shuffled on screen.
+    ///
+    /// Cards still on the board keep their places, cards new to it fill the places of those
+    /// that left in board order, and any left over follow at the end.
+    pub fn arranged_like(&self, shown: &[Card]) -> Vec<Card> {
+        let mut added = self.0.iter().filter(|card| !shown.contains(card)).copied();
+        let mut arranged: Vec<_> = shown
+            .iter()
+            .filter_map(|card| {
+                if self.0.contains(card) {
+                    Some(*card)
+                } else {
+                    added.next()
+                }
+            })
+            .collect();
+        arranged.extend(added);
+        arranged
+    }
+}
+
+/// The difference between two boards, from [`Board::diff`]. Cards are listed in board order.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct BoardDiff {
+    pub added: Vec<Card>,
+    pub removed: Vec<Card>,
+}
+
+impl BoardDiff {
+    /// Whether the boards hold the same cards.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::cards;
+    use pretty_assertions::assert_eq;
+
+    fn board(notation: &str) -> Board {
+        cards(notation).into()
+    }
+
+    #[test]
+    fn board_diff() {
+        let before = board("1R#D 2G~S 3P=O 1G#D");
+        let after = board("1R#D 3R#D 3P=O 2P~O 1G#D");
+        assert_eq!(
+            before.diff(&after),
+            BoardDiff {
+                added: board("3R#D 2P~O").0,
+                removed: board("2G~S").0,
+            }
+        );
+        assert_eq!(after.diff(&before).added, before.diff(&after).removed);
+
+        // Reordering is not a change
+        let shuffled = board("1G#D 3P=O 1R#D 2G~S");
+        assert!(before.diff(&shuffled).is_empty());
+        assert!(Board::default().diff(&Board::default()).is_empty());
+    }
+
+    #[test]
+    fn arranged_like() {
+        let shown = board("1G#D 3P=O 1R#D 2G~S").0;
+        // Replacements take the places of claimed cards
+        let replaced = board("1R#D 3R#D 2P~O 1G#D");
+        assert_eq!(
+            replaced.arranged_like(&shown),
+            board("1G#D 3R#D 1R#D 2P~O").0
+        );
+        // Extra cards follow the rest, and places empty when nothing replaces a card
+        let extra = board("1R#D 2G~S 3P=O 1G#D 3R#D");
+        assert_eq!(
+            extra.arranged_like(&shown),
+            board("1G#D 3P=O 1R#D 2G~S 3R#D").0
+        );
+        let removed = board("1R#D 1G#D");
+        assert_eq!(removed.arranged_like(&shown), board("1G#D 1R#D").0);
+    }
+}
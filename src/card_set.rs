@@ -0,0 +1,354 @@
+//! Sets of cards in the sense of collections, not of the game: which of the 81 cards are in it.
+//!
+//! A bit per card packs any collection of distinct cards into one `u128`, so membership, adding
+//! and removing are a single instruction, and union, intersection, difference and complement are
+//! one each for the whole collection. Cards iterate in ascending order.
+//!
+//! [`TrackedDeck`] pairs a [`Deck`] with the set of cards still in it, for code that asks about
+//! particular cards rather than the next one, such as card counting trainers and scenario
+//! builders.
+
+use crate::core::{Card, Deck, DECK, DECK_SIZE};
+use std::{fmt, ops};
+
+/// Every card's bit.
+const FULL: u128 = (1 << DECK_SIZE) - 1;
+
+/// Distinct cards, in no particular order, iterated in ascending order.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "Vec<Card>", from = "Vec<Card>"))]
+pub struct CardSet(u128);
+
+fn bit(card: Card) -> u128 {
+    1 << u8::from(card)
+}
+
+impl CardSet {
+    /// No cards.
+    pub const EMPTY: Self = Self(0);
+    /// The whole deck.
+    pub const FULL: Self = Self(FULL);
+
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// Add the card, returning whether it was new.
+    pub fn insert(&mut self, card: Card) -> bool {
+        let new = !self.contains(card);
+        self.0 |= bit(card);
+        new
+    }
+
+    /// Take the card out, returning whether it was there.
+    pub fn remove(&mut self, card: Card) -> bool {
+        let present = self.contains(card);
+        self.0 &= !bit(card);
+        present
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.0 & bit(card) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The cards in either.
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// The cards in both.
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The cards in this but not the other.
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// The rest of the deck.
+    pub fn complement(self) -> Self {
+        Self(!self.0 & FULL)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// The cards in ascending order.
+    pub fn iter(&self) -> Iter {
+        Iter(self.0)
+    }
+
+    /// The cards as a list in ascending order.
+    pub fn to_vec(&self) -> Vec<Card> {
+        self.iter().collect()
+    }
+}
+
+/// The cards of a [`CardSet`] in ascending order.
+#[derive(Debug, Clone)]
+pub struct Iter(u128);
+
+impl Iterator for Iter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(DECK[index])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &CardSet {
+    type Item = Card;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        let mut set = Self::EMPTY;
+        set.extend(cards);
+        set
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, cards: I) {
+        for card in cards {
+            self.insert(card);
+        }
+    }
+}
+
+impl From<&[Card]> for CardSet {
+    fn from(cards: &[Card]) -> Self {
+        cards.iter().copied().collect()
+    }
+}
+
+impl From<Vec<Card>> for CardSet {
+    fn from(cards: Vec<Card>) -> Self {
+        cards.into_iter().collect()
+    }
+}
+
+impl From<CardSet> for Vec<Card> {
+    fn from(set: CardSet) -> Self {
+        set.to_vec()
+    }
+}
+
+impl ops::BitOr for CardSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl ops::BitAnd for CardSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+impl ops::Sub for CardSet {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+impl ops::Not for CardSet {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+/// A deck that knows which cards are in it, so checking for a card, taking out a particular card
+/// and listing the cards not yet dealt take constant time.
+///
+/// Taking out a card only clears its bit, and dealing passes over cards that were taken out, so
+/// the order of the rest is kept without shifting the deck along.
+#[derive(Debug, Clone)]
+pub struct TrackedDeck {
+    order: Vec<Card>,
+    remaining: CardSet,
+}
+
+impl TrackedDeck {
+    pub fn new(deck: Deck) -> Self {
+        let remaining = CardSet::from(&deck.0[..]);
+        Self {
+            order: deck.0,
+            remaining,
+        }
+    }
+
+    /// Whether the card is still to be dealt.
+    pub fn contains(&self, card: Card) -> bool {
+        self.remaining.contains(card)
+    }
+
+    /// Take out a particular card, wherever it is, returning whether it was there.
+    pub fn remove(&mut self, card: Card) -> bool {
+        self.remaining.remove(card)
+    }
+
+    /// Deal the next card still in the deck.
+    pub fn deal(&mut self) -> Option<Card> {
+        while let Some(card) = self.order.pop() {
+            if self.remaining.remove(card) {
+                return Some(card);
+            }
+        }
+        None
+    }
+
+    /// The cards still to be dealt, i.e. not yet seen.
+    pub fn remaining(&self) -> CardSet {
+        self.remaining
+    }
+
+    /// The cards dealt or taken out, or never in the deck.
+    pub fn seen(&self) -> CardSet {
+        !self.remaining
+    }
+
+    pub fn len(&self) -> usize {
+        self.remaining.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining.is_empty()
+    }
+}
+
+impl From<Deck> for TrackedDeck {
+    fn from(deck: Deck) -> Self {
+        Self::new(deck)
+    }
+}
+
+/// The cards still to be dealt, in the order they will be.
+impl From<TrackedDeck> for Deck {
+    fn from(deck: TrackedDeck) -> Self {
+        let TrackedDeck { order, remaining } = deck;
+        Deck(
+            order
+                .into_iter()
+                .filter(|card| remaining.contains(*card))
+                .collect(),
+        )
+    }
+}
+
+/// The cards in notation, e.g. `{1R#D 2G~S}`.
+impl fmt::Debug for CardSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cards: Vec<_> = self.iter().map(|card| card.to_string()).collect();
+        write!(f, "{{{}}}", cards.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::cards;
+    use pretty_assertions::assert_eq;
+
+    fn set(notation: &str) -> CardSet {
+        CardSet::from(&cards(notation)[..])
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = set("1R#D 2G~S 3P=O");
+        let b = set("3P=O 1R#S");
+        assert_eq!(a | b, set("1R#D 2G~S 3P=O 1R#S"));
+        assert_eq!(a & b, set("3P=O"));
+        assert_eq!(a - b, set("1R#D 2G~S"));
+        assert_eq!((!a).len(), 78);
+        assert_eq!(!!a, a);
+        assert_eq!(a | !a, CardSet::FULL);
+        assert!((a & b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+        assert_eq!(CardSet::FULL.len(), DECK.len());
+        assert_eq!(CardSet::FULL.to_vec(), *DECK);
+        assert_eq!(format!("{:?}", b), "{1R#S 3P=O}");
+    }
+
+    #[test]
+    fn membership() {
+        let mut cards = CardSet::new();
+        let card: Card = "2G~S".parse().unwrap();
+        assert!(cards.insert(card));
+        assert!(!cards.insert(card));
+        assert!(cards.contains(card));
+        assert_eq!(cards.len(), 1);
+        assert!(cards.remove(card));
+        assert!(!cards.remove(card));
+        assert!(cards.is_empty());
+        // In ascending order, whatever order they were added in
+        let cards = set("3P=O 1R#D 2G~S");
+        assert_eq!(cards.iter().len(), 3);
+        assert_eq!(Vec::from(cards), set("1R#D 2G~S 3P=O").to_vec());
+    }
+
+    #[test]
+    fn tracked_deck() {
+        let order = set("1R#D 2G~S 3P=O 1R#S").to_vec();
+        let mut deck = TrackedDeck::new(Deck(order.clone()));
+        assert_eq!(deck.len(), 4);
+        assert!(deck.remove(order[2]));
+        assert!(!deck.remove(order[2]));
+        assert!(!deck.contains(order[2]));
+        assert_eq!(deck.deal(), Some(order[3]));
+        // The card taken out is passed over
+        assert_eq!(deck.deal(), Some(order[1]));
+        assert_eq!(deck.remaining().to_vec(), vec![order[0]]);
+        assert_eq!(deck.seen().len(), 80);
+        assert_eq!(Deck::from(deck.clone()).0, vec![order[0]]);
+        assert_eq!(deck.deal(), Some(order[0]));
+        assert_eq!(deck.deal(), None);
+        assert!(deck.is_empty());
+    }
+}
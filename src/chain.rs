@@ -0,0 +1,186 @@
+//! Set chains: puzzles where each set found must follow on from the one found before it.
+//!
+//! Two sets follow on from each other when they have a card in common, or when the same
+//! attributes are all the same on both, e.g. two sets of one color and one count. A board is a
+//! chain puzzle when every set on it can be found in one such chain, which [`full_chain`]
+//! searches for.
+
+use crate::{
+    core::Card,
+    find_all::{ClaimConstraint, FindAll},
+    line::Line,
+    solver::find_sets,
+};
+use std::collections::HashSet;
+
+/// Each set after the first must share a card or its [`SetPattern`](crate::SetPattern) with the
+/// set found before it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Chained;
+
+impl ClaimConstraint for Chained {
+    fn allows(&self, previous: Option<Line>, next: Line) -> bool {
+        previous.is_none_or(|previous| follows(previous, next))
+    }
+}
+
+/// Whether the sets share a card, or the attributes that are the same on every card.
+pub fn follows(a: Line, b: Line) -> bool {
+    a.cards().iter().any(|card| b.contains(*card)) || a.pattern() == b.pattern()
+}
+
+/// An order to find every one of the sets in, where the constraint allows each in turn, or none
+/// if there is no such order.
+///
+/// The search tries each set to start from, and backs out of orders that get stuck, remembering
+/// which sets were left from which last set so that no dead end is explored twice.
+pub fn full_chain(sets: &[Line], constraint: &impl ClaimConstraint) -> Option<Vec<Line>> {
+    fn extend(
+        sets: &[Line],
+        constraint: &impl ClaimConstraint,
+        chain: &mut Vec<usize>,
+        dead_ends: &mut HashSet<(u64, usize)>,
+    ) -> bool {
+        if chain.len() == sets.len() {
+            return true;
+        }
+        let used = chain.iter().fold(0u64, |used, i| used | 1 << i);
+        let last = chain.last().copied();
+        if let Some(last) = last {
+            if dead_ends.contains(&(used, last)) {
+                return false;
+            }
+        }
+        for next in 0..sets.len() {
+            if used & 1 << next != 0 || !constraint.allows(last.map(|i| sets[i]), sets[next]) {
+                continue;
+            }
+            chain.push(next);
+            if extend(sets, constraint, chain, dead_ends) {
+                return true;
+            }
+            chain.pop();
+        }
+        if let Some(last) = last {
+            dead_ends.insert((used, last));
+        }
+        false
+    }
+
+    // Boards hold far fewer than 64 sets, even with every card dealt out
+    if sets.len() > 64 {
+        return None;
+    }
+    let mut chain = Vec::with_capacity(sets.len());
+    extend(sets, constraint, &mut chain, &mut HashSet::new())
+        .then(|| chain.into_iter().map(|i| sets[i]).collect())
+}
+
+/// A board on which every set can be found in one chain, and such a chain.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChainPuzzle {
+    cards: Vec<Card>,
+    chain: Vec<Line>,
+}
+
+impl ChainPuzzle {
+    /// The board as a chain puzzle, if it holds at least `min_sets` sets and they all chain.
+    pub fn new(cards: Vec<Card>, min_sets: usize) -> Option<Self> {
+        let sets: Vec<_> = find_sets(&cards)
+            .iter()
+            .filter_map(|set| Line::through(set.cards()[0], set.cards()[1]))
+            .collect();
+        if sets.len() < min_sets.max(1) {
+            return None;
+        }
+        let chain = full_chain(&sets, &Chained)?;
+        Some(Self { cards, chain })
+    }
+
+    /// The first of the boards that is a chain puzzle, with its position among them, e.g. to
+    /// generate one from the decks of successive seeds.
+    pub fn generate(
+        boards: impl IntoIterator<Item = Vec<Card>>,
+        min_sets: usize,
+    ) -> Option<(usize, Self)> {
+        boards
+            .into_iter()
+            .enumerate()
+            .find_map(|(i, cards)| Some((i, Self::new(cards, min_sets)?)))
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Every set on the board, in an order that solves the puzzle.
+    pub fn chain(&self) -> &[Line] {
+        &self.chain
+    }
+
+    /// The puzzle to play, where each set found must follow on from the one before.
+    pub fn play(&self) -> FindAll {
+        FindAll::new(self.cards.clone()).with_constraint(Chained)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::Triple, game::ClaimError, test_util::cards};
+    use pretty_assertions::assert_eq;
+
+    fn set(notation: &str) -> Line {
+        let cards = cards(notation);
+        Line::through(cards[0], cards[1]).unwrap()
+    }
+
+    fn claim(notation: &str) -> Triple {
+        let cards = cards(notation);
+        Triple::from((cards[0], cards[1], cards[2]))
+    }
+
+    #[test]
+    fn every_set_is_chained() {
+        let red = set("1R#D 1R~D 1R=D");
+        let green = set("2G#S 2G~S 2G=S");
+        let bridge = set("2G#S 1R~O 3P=D");
+        // Apart, but with the same pattern, and sharing a card
+        assert!(follows(red, green));
+        assert!(follows(green, bridge));
+        assert!(!follows(red, bridge));
+        let chain = full_chain(&[bridge, red, green], &Chained).unwrap();
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain[1], green);
+        assert_eq!(full_chain(&[red, bridge], &Chained), None);
+
+        let board = cards("1R#D 1R~D 1R=D 2G#S 2G~S 2G=S 1R~O 3P=D");
+        assert_eq!(ChainPuzzle::new(board.clone(), 4), None);
+        let puzzle = ChainPuzzle::new(board, 3).unwrap();
+        let mut play = puzzle.play();
+        for set in puzzle.chain() {
+            let [a, b, c] = set.cards();
+            play.try_claim(Triple::from((c, a, b))).unwrap();
+        }
+        assert!(play.is_complete());
+    }
+
+    #[test]
+    fn broken_chains_are_refused() {
+        let board = cards("2G#S 2G~S 2G=S 1P#D 2R~O 3G=S");
+        let mut play = FindAll::new(board.clone()).with_constraint(Chained);
+        play.try_claim(claim("1P#D 2R~O 3G=S")).unwrap();
+        assert_eq!(
+            play.try_claim(claim("2G#S 2G~S 2G=S")),
+            Err(ClaimError::Disallowed)
+        );
+        assert_eq!(play.found().len(), 1);
+        assert!(play.is_stuck());
+        assert_eq!(ChainPuzzle::new(board.clone(), 1), None);
+        let chained = cards("1R#D 1R~D 1R=D 2G#S 2G~S 2G=S");
+        assert_eq!(
+            ChainPuzzle::generate([board, chained.clone()], 2),
+            ChainPuzzle::new(chained, 2).map(|puzzle| (1, puzzle))
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Chess clock style time controls, for competitive and blitz play.
+//!
+//! Everyone looks for sets at the same time, so every player's clock runs at once. Each player
+//! starts with the same total time and may earn an increment for every set they find. A player
+//! whose time runs out can no longer claim, and the game tells its subscribers so.
+
+use crate::{
+    core::Triple,
+    game::{ClaimAccepted, ClaimError, GameEvent, GameState, PlayerId},
+};
+use std::{collections::HashMap, time::Duration};
+
+/// How much time each player has.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TimeControl {
+    /// Time each player starts with.
+    pub total: Duration,
+    /// Time added to a player's clock for each set they find.
+    pub increment: Duration,
+}
+
+impl TimeControl {
+    /// A fixed amount of time for the whole game, with nothing added for finding sets.
+    pub fn sudden_death(total: Duration) -> Self {
+        Self {
+            total,
+            increment: Duration::ZERO,
+        }
+    }
+
+    pub fn with_increment(total: Duration, increment: Duration) -> Self {
+        Self { total, increment }
+    }
+}
+
+/// Every player's clock in a game.
+///
+/// Times are on a clock shared by every call, as for [`Arbiter`](crate::Arbiter). The clocks
+/// only notice time passing when called, so call [`Clocks::tick`] regularly to announce players
+/// running out of time promptly.
+#[derive(Debug, Clone)]
+pub struct Clocks {
+    control: TimeControl,
+    /// When each player's time runs out.
+    flags: HashMap<PlayerId, Duration>,
+    out_of_time: Vec<PlayerId>,
+}
+
+impl Clocks {
+    /// Start a clock for each player at `now`.
+    pub fn new(
+        control: TimeControl,
+        players: impl IntoIterator<Item = PlayerId>,
+        now: Duration,
+    ) -> Self {
+        Self {
+            control,
+            flags: players
+                .into_iter()
+                .map(|player| (player, now + control.total))
+                .collect(),
+            out_of_time: Vec::new(),
+        }
+    }
+
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Time the player has left at `now`, or none for players without a clock.
+    pub fn remaining(&self, player: PlayerId, now: Duration) -> Option<Duration> {
+        self.flags.get(&player).map(|flag| flag.saturating_sub(now))
+    }
+
+    /// When the next player still playing will run out of time.
+    pub fn next_flag(&self) -> Option<Duration> {
+        self.flags
+            .iter()
+            .filter(|(player, _)| !self.out_of_time.contains(player))
+            .map(|(_, flag)| *flag)
+            .min()
+    }
+
+    /// Announce every player whose time ran out by `now`, earliest first.
+    ///
+    /// Returns an [`GameEvent::OutOfTime`] for each, which the game's subscribers also see.
+    pub fn tick(&mut self, game: &mut GameState, now: Duration) -> Vec<GameEvent> {
+        let mut flagged: Vec<_> = self
+            .flags
+            .iter()
+            .filter(|(player, flag)| **flag <= now && !self.out_of_time.contains(player))
+            .map(|(player, flag)| (*flag, *player))
+            .collect();
+        flagged.sort_by_key(|(flag, player)| (*flag, player.0));
+        flagged
+            .into_iter()
+            .map(|(_, player)| {
+                self.out_of_time.push(player);
+                let event = GameEvent::OutOfTime(player);
+                game.emit(event.clone());
+                event
+            })
+            .collect()
+    }
+
+    /// Claim a set for the player at `now`, if they have time left, adding the increment to their
+    /// clock if it is accepted.
+    ///
+    /// Players without a clock are refused as if out of time.
+    pub fn try_claim(
+        &mut self,
+        game: &mut GameState,
+        player: PlayerId,
+        triple: Triple,
+        now: Duration,
+    ) -> Result<ClaimAccepted, ClaimError> {
+        self.tick(game, now);
+        if self.out_of_time.contains(&player) || !self.flags.contains_key(&player) {
+            // The game never saw this claim, so tell its subscribers here
+            let error = ClaimError::OutOfTime;
+            game.emit(GameEvent::Refused {
+                player,
+                error: error.clone(),
+            });
+            return Err(error);
+        }
+        let claim = game.try_claim_at(player, triple, now)?;
+        if let Some(flag) = self.flags.get_mut(&player) {
+            *flag += self.control.increment;
+        }
+        Ok(claim)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{game, triple, BOARD};
+    use pretty_assertions::assert_eq;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn sets_earn_the_increment() {
+        let mut game = game(BOARD);
+        let control = TimeControl::with_increment(secs(60), secs(5));
+        let mut clocks = Clocks::new(control, [PlayerId(1), PlayerId(2)], secs(10));
+        assert_eq!(clocks.remaining(PlayerId(1), secs(40)), Some(secs(30)));
+        assert_eq!(clocks.next_flag(), Some(secs(70)));
+
+        assert!(clocks
+            .try_claim(&mut game, PlayerId(1), triple("1R#D 2G~S 3P=O"), secs(40))
+            .is_ok());
+        assert_eq!(clocks.remaining(PlayerId(1), secs(40)), Some(secs(35)));
+        assert_eq!(clocks.remaining(PlayerId(2), secs(40)), Some(secs(30)));
+        // Refused claims earn nothing
+        assert!(clocks
+            .try_claim(&mut game, PlayerId(2), triple("1R#S 2R#D 3P=O"), secs(40))
+            .is_err());
+        assert_eq!(clocks.remaining(PlayerId(2), secs(40)), Some(secs(30)));
+        assert_eq!(clocks.remaining(PlayerId(3), secs(40)), None);
+    }
+
+    #[test]
+    fn players_out_of_time_cannot_claim() {
+        let mut game = game(BOARD);
+        let events = game.subscribe();
+        let mut clocks = Clocks::new(
+            TimeControl::sudden_death(secs(60)),
+            [PlayerId(2), PlayerId(1)],
+            Duration::ZERO,
+        );
+        assert_eq!(clocks.tick(&mut game, secs(59)), vec![]);
+        assert_eq!(
+            clocks.tick(&mut game, secs(60)),
+            vec![
+                GameEvent::OutOfTime(PlayerId(1)),
+                GameEvent::OutOfTime(PlayerId(2))
+            ]
+        );
+        assert_eq!(clocks.tick(&mut game, secs(61)), vec![]);
+        assert_eq!(clocks.next_flag(), None);
+        assert_eq!(
+            clocks.try_claim(&mut game, PlayerId(1), triple("1R#D 2G~S 3P=O"), secs(61)),
+            Err(ClaimError::OutOfTime)
+        );
+        assert_eq!(game.score(PlayerId(1)), 0);
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                GameEvent::OutOfTime(PlayerId(1)),
+                GameEvent::OutOfTime(PlayerId(2)),
+                GameEvent::Refused {
+                    player: PlayerId(1),
+                    error: ClaimError::OutOfTime
+                },
+            ]
+        );
+    }
+}
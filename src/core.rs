@@ -1,7 +1,14 @@
+// Conversions here are total, so untrusted input such as saves or FFI calls can never abort
+#![cfg_attr(
+    not(test),
+    deny(clippy::expect_used, clippy::unwrap_used, clippy::panic)
+)]
+
+use crate::display::Style;
+use bitvec::vec::BitVec;
 use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::{FromPrimitive, ToPrimitive};
 use once_cell::sync::Lazy;
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 pub const DECK_SIZE: u8 = 81;
 const RANK_BASE: u8 = 3;
@@ -9,14 +16,19 @@ const RANK_COLOR: u8 = RANK_BASE.pow(3);
 const RANK_COUNT: u8 = RANK_BASE.pow(2);
 const RANK_SHADE: u8 = RANK_BASE.pow(1);
 
-#[derive(Debug, PartialEq, Clone, Copy, Eq, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Clone, Copy, Eq, PartialOrd, Ord, Hash)]
 pub struct Card(u8);
 
+/// Cards are vectors of four coordinates over GF(3), the integers mod 3, one per attribute in the
+/// order of [`Attribute::ALL`], which are the digits of the card's index in base 3. Adding,
+/// subtracting and negating work on each coordinate without carrying into the next, so three
+/// distinct cards are a set exactly when they sum to the first card, `1R#D`, whose coordinates
+/// are all zero.
 impl std::ops::Add for Card {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self((self.0 + other.0).rem_euclid(DECK_SIZE))
+        Self(SUM[self.0 as usize][other.0 as usize])
     }
 }
 
@@ -24,16 +36,75 @@ impl std::ops::Sub for Card {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Self(
-            (if self.0 < other.0 {
-                self.0 + DECK_SIZE
-            } else {
-                self.0
-            }) - other.0,
-        )
+        self + -other
+    }
+}
+
+impl std::ops::Neg for Card {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(NEGATION[self.0 as usize])
     }
 }
 
+/// Read a single attribute without decoding the others, which is cheaper than going through
+/// [`CardProperties`] when filtering many cards.
+impl Card {
+    pub fn color(&self) -> Color {
+        Color::ALL[Attribute::Color.index(*self)]
+    }
+
+    pub fn count(&self) -> Count {
+        Count::ALL[Attribute::Count.index(*self)]
+    }
+
+    pub fn shade(&self) -> Shade {
+        Shade::ALL[Attribute::Shade.index(*self)]
+    }
+
+    pub fn shape(&self) -> Shape {
+        Shape::ALL[Attribute::Shape.index(*self)]
+    }
+}
+
+impl Card {
+    /// The card that makes a set with this one and `other`, `-(self + other)`.
+    pub(crate) fn third(self, other: Self) -> Self {
+        Self(THIRD[self.0 as usize][other.0 as usize])
+    }
+
+    /// The card's coordinates as a vector over GF(3), each from 0 to 2, in the order of
+    /// [`Attribute::ALL`].
+    pub fn coordinates(&self) -> [u8; 4] {
+        coordinates(self.0)
+    }
+
+    /// The card with these coordinates, or none if any is more than 2.
+    pub fn from_coordinates(coordinates: [u8; 4]) -> Option<Self> {
+        coordinates
+            .iter()
+            .all(|coordinate| *coordinate < RANK_BASE)
+            .then(|| Self(index(coordinates)))
+    }
+}
+
+const fn coordinates(index: u8) -> [u8; 4] {
+    [
+        index / RANK_COLOR % RANK_BASE,
+        index / RANK_COUNT % RANK_BASE,
+        index / RANK_SHADE % RANK_BASE,
+        index % RANK_BASE,
+    ]
+}
+
+const fn index(coordinates: [u8; 4]) -> u8 {
+    coordinates[0] * RANK_COLOR
+        + coordinates[1] * RANK_COUNT
+        + coordinates[2] * RANK_SHADE
+        + coordinates[3]
+}
+
 impl From<CardProperties> for Card {
     fn from(properties: CardProperties) -> Self {
         let CardProperties {
@@ -42,32 +113,63 @@ impl From<CardProperties> for Card {
             shade,
             shape,
         } = properties;
+        // Each value is declared in order from zero, so its discriminant is its digit
         Self(
-            (color.to_u8().expect("color did not fit in u8") * RANK_COLOR)
-                + (count.to_u8().expect("count did not fit in u8") * RANK_COUNT)
-                + (shade.to_u8().expect("shade did not fit in u8") * RANK_SHADE)
-                + shape.to_u8().expect("shape did not fit in u8"),
+            color as u8 * RANK_COLOR
+                + count as u8 * RANK_COUNT
+                + shade as u8 * RANK_SHADE
+                + shape as u8,
         )
     }
 }
 
 impl From<Card> for CardProperties {
     fn from(card: Card) -> Self {
-        let color = Color::from_u8(card.0 / RANK_COLOR).expect("invalid color enum value");
-        let remainder = card.0 % RANK_COLOR;
-        let count = Count::from_u8(remainder / RANK_COUNT).expect("invalid count enum value");
-        let remainder = remainder % RANK_COUNT;
-        let shade = Shade::from_u8(remainder / RANK_SHADE).expect("invalid shade enum value");
-        let shape = Shape::from_u8(remainder % RANK_SHADE).expect("invalid shape enum value");
         Self {
-            color,
-            count,
-            shade,
-            shape,
+            color: card.color(),
+            count: card.count(),
+            shade: card.shade(),
+            shape: card.shape(),
+        }
+    }
+}
+
+/// The position of a card in the ordered deck, from 0 to 80, for compact storage and FFI.
+impl From<Card> for u8 {
+    fn from(card: Card) -> Self {
+        card.0
+    }
+}
+
+impl TryFrom<u8> for Card {
+    type Error = InvalidCardIndex;
+
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        if index < DECK_SIZE {
+            Ok(Self(index))
+        } else {
+            Err(InvalidCardIndex(index))
         }
     }
 }
 
+/// A card index outside the deck.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct InvalidCardIndex(pub u8);
+
+impl fmt::Display for InvalidCardIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid card index {}, expected 0 to {}",
+            self.0,
+            DECK_SIZE - 1
+        )
+    }
+}
+
+impl std::error::Error for InvalidCardIndex {}
+
 /// Names drawn from https://en.wikipedia.org/wiki/Set_(card_game)
 #[derive(Debug, PartialEq, Clone)]
 pub struct CardProperties {
@@ -105,9 +207,310 @@ pub enum Shape {
     Oval = 2,
 }
 
+/// Iteration and names for an attribute's values, which are declared in order from zero.
+macro_rules! attribute_values {
+    ($attribute:ident, [$($value:ident => $name:literal),+ $(,)?]) => {
+        impl $attribute {
+            /// Every value, in declaration order.
+            pub const ALL: [Self; 3] = [$(Self::$value),+];
+            /// How many values there are.
+            pub const COUNT: usize = Self::ALL.len();
+
+            /// Iterate over every value, in declaration order.
+            pub fn iter() -> impl Iterator<Item = Self> {
+                Self::ALL.into_iter()
+            }
+
+            /// The lowercase English name of the value.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$value => $name),+
+                }
+            }
+        }
+    };
+}
+
+attribute_values!(Color, [Red => "red", Green => "green", Purple => "purple"]);
+attribute_values!(Count, [One => "one", Two => "two", Three => "three"]);
+attribute_values!(Shade, [Solid => "solid", Striped => "striped", Open => "open"]);
+attribute_values!(Shape, [Diamond => "diamond", Squiggle => "squiggle", Oval => "oval"]);
+
+/// One of the four ways cards vary, for reasoning about them generically.
+///
+/// Three cards are a set when, for every attribute, their values are all the same or all
+/// different.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Attribute {
+    Color,
+    Count,
+    Shade,
+    Shape,
+}
+
+impl Attribute {
+    /// Every attribute, from the most to the least significant digit of a card's index.
+    pub const ALL: [Self; 4] = [Self::Color, Self::Count, Self::Shade, Self::Shape];
+
+    /// Iterate over every attribute, in the order of [`Attribute::ALL`].
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
+    /// The lowercase English name of the attribute.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Color => "color",
+            Self::Count => "count",
+            Self::Shade => "shade",
+            Self::Shape => "shape",
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Color => RANK_COLOR,
+            Self::Count => RANK_COUNT,
+            Self::Shade => RANK_SHADE,
+            Self::Shape => 1,
+        }
+    }
+
+    /// The card's value for this attribute, as its position in e.g. [`Color::ALL`].
+    pub fn index(&self, card: Card) -> usize {
+        ((card.0 / self.rank()) % RANK_BASE) as usize
+    }
+
+    /// Whether the three cards' values for this attribute are all the same or all different.
+    pub fn is_consistent(&self, cards: [Card; 3]) -> bool {
+        // With three values, the indices sum to a multiple of three exactly when they are all
+        // equal (3a) or a permutation of 0, 1 and 2
+        cards.iter().map(|card| self.index(*card)).sum::<usize>() % 3 == 0
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Red => "R",
+            Self::Green => "G",
+            Self::Purple => "P",
+        })
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::One => "1",
+            Self::Two => "2",
+            Self::Three => "3",
+        })
+    }
+}
+
+impl fmt::Display for Shade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Solid => "#",
+            Self::Striped => "~",
+            Self::Open => "=",
+        })
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Diamond => "D",
+            Self::Squiggle => "S",
+            Self::Oval => "O",
+        })
+    }
+}
+
+/// Compact notation, e.g. `2R~O` for two red striped ovals, or plain English with `{:#}`.
+///
+/// See [`Card::styled`] for other styles.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = if f.alternate() {
+            Style::Verbose
+        } else {
+            Style::Compact
+        };
+        self.styled(style).fmt(f)
+    }
+}
+
+/// Parses compact notation, as written by `Display`.
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseCardError(s.to_owned());
+        let [count, color, shade, shape] = s
+            .chars()
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| error())?;
+        let count = match count {
+            '1' => Count::One,
+            '2' => Count::Two,
+            '3' => Count::Three,
+            _ => return Err(error()),
+        };
+        let color = match color {
+            'R' => Color::Red,
+            'G' => Color::Green,
+            'P' => Color::Purple,
+            _ => return Err(error()),
+        };
+        let shade = match shade {
+            '#' => Shade::Solid,
+            '~' => Shade::Striped,
+            '=' => Shade::Open,
+            _ => return Err(error()),
+        };
+        let shape = match shape {
+            'D' => Shape::Diamond,
+            'S' => Shape::Squiggle,
+            'O' => Shape::Oval,
+            _ => return Err(error()),
+        };
+        Ok(Self::from(CardProperties {
+            color,
+            count,
+            shade,
+            shape,
+        }))
+    }
+}
+
+/// The input was not a card in compact notation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseCardError(String);
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid card '{}', expected notation such as '2R~O'",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Serialized as compact notation, the same as `Display`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Card {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let notation = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+        notation.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Card {
+    fn schema_name() -> String {
+        "Card".to_owned()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, Metadata, SchemaObject, StringValidation};
+
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "Compact notation: count 1-3, color Red/Green/Purple, \
+                     shade #solid/~striped/=open and shape Diamond/Squiggle/Oval"
+                        .to_owned(),
+                ),
+                examples: vec!["2R~O".into()],
+                ..Default::default()
+            })),
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^[123][RGP][#~=][DSO]$".to_owned()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 /// A complete, ordered deck.
 pub static DECK: Lazy<Vec<Card>> = Lazy::new(|| (0..DECK_SIZE).map(Card).collect());
 
+/// The sum of each pair of cards as vectors, by index, adding each coordinate mod 3.
+static SUM: [[u8; DECK_SIZE as usize]; DECK_SIZE as usize] = {
+    let mut table = [[0; DECK_SIZE as usize]; DECK_SIZE as usize];
+    let mut a = 0;
+    while a < DECK_SIZE {
+        let mut b = 0;
+        while b < DECK_SIZE {
+            let (x, y) = (coordinates(a), coordinates(b));
+            let mut sum = [0; 4];
+            let mut i = 0;
+            while i < sum.len() {
+                sum[i] = (x[i] + y[i]) % RANK_BASE;
+                i += 1;
+            }
+            table[a as usize][b as usize] = index(sum);
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+};
+
+/// The negation of each card as a vector, by index.
+static NEGATION: [u8; DECK_SIZE as usize] = {
+    let mut table = [0; DECK_SIZE as usize];
+    let mut a = 0;
+    while a < DECK_SIZE {
+        let x = coordinates(a);
+        let mut negation = [0; 4];
+        let mut i = 0;
+        while i < negation.len() {
+            negation[i] = (RANK_BASE - x[i]) % RANK_BASE;
+            i += 1;
+        }
+        table[a as usize] = index(negation);
+        a += 1;
+    }
+    table
+};
+
+/// The card completing a set with each pair of cards, by index: minus their sum, so that the
+/// three sum to zero and every attribute's coordinates to a multiple of three.
+static THIRD: [[u8; DECK_SIZE as usize]; DECK_SIZE as usize] = {
+    let mut table = [[0; DECK_SIZE as usize]; DECK_SIZE as usize];
+    let mut a = 0;
+    while a < DECK_SIZE as usize {
+        let mut b = 0;
+        while b < DECK_SIZE as usize {
+            table[a][b] = NEGATION[SUM[a][b] as usize];
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+};
+
 /// A selection of three cards.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Triple {
@@ -121,21 +524,70 @@ impl From<(Card, Card, Card)> for Triple {
 }
 
 impl Triple {
+    pub fn cards(&self) -> [Card; 3] {
+        let (a, b, c) = self.cards;
+        [a, b, c]
+    }
+
+    /// The attributes that stop the cards being a set, in the order of [`Attribute::ALL`].
+    pub fn broken_attributes(&self) -> impl Iterator<Item = Attribute> {
+        let (a, b, c) = self.cards;
+        Attribute::iter().filter(move |attribute| !attribute.is_consistent([a, b, c]))
+    }
+
+    /// How hard the set is to spot, from 0 to 4: the number of attributes that are all different.
+    ///
+    /// People find sets by matching, so a set sharing three attributes is spotted first and one
+    /// that differs in everything is the hardest. Only meaningful if the cards are a set.
+    pub fn difficulty(&self) -> u8 {
+        let (a, b, c) = self.cards;
+        Attribute::iter()
+            .filter(|attribute| {
+                let [a, b, c] = [a, b, c].map(|card| attribute.index(card));
+                a != b && b != c && a != c
+            })
+            .count() as u8
+    }
+
     /// Return whether the three given cards are a set.
     pub fn is_set(&self) -> bool {
-        let (a, b, c) = &self.cards;
-        let mut cards = [a, b, c];
-        cards.sort();
-        let [a, b, c] = cards;
-        *a - *b == *b - *c
+        let (a, b, c) = self.cards;
+        a.third(b) == c
     }
 }
 
+/// Whether each of many triples is a set, as one bit per triple in order.
+///
+/// This is for analysis that checks millions of candidates, where a bit per result is an eighth
+/// of the memory of a `bool`. Triples are checked 64 at a time into a whole word of bits.
+pub fn is_set_batch(triples: &[(Card, Card, Card)]) -> BitVec<u64> {
+    let mut words = Vec::with_capacity(triples.len().div_ceil(64));
+    for chunk in triples.chunks(64) {
+        let word = chunk.iter().enumerate().fold(0u64, |word, (i, (a, b, c))| {
+            word | (u64::from(a.third(*b) == *c) << i)
+        });
+        words.push(word);
+    }
+    let mut bits = BitVec::from_vec(words);
+    bits.truncate(triples.len());
+    bits
+}
+
+/// The version of the shuffle behind [`Deck::new_from_seed`], raised whenever a seed would deal
+/// its cards in another order, so that records of a seed can say which shuffle they expect.
+pub const SHUFFLE_VERSION: u32 = 1;
+
+/// The cards left to deal, with the next card last.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Deck(pub Vec<Card>);
 
 impl Deck {
     /// Helper for setting up a fresh deck without listing the order.
     #[cfg(feature = "rand")]
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug"))]
     pub fn new_from_seed(seed: u64) -> Self {
         use rand::{seq::SliceRandom, SeedableRng};
         use rand_pcg::Pcg64;
@@ -148,52 +600,246 @@ impl Deck {
 
     /// Deal a single card from the deck.
     pub fn deal(&mut self) -> Option<Card> {
-        self.0.pop()
+        let card = self.0.pop();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(card = ?card, remaining = self.0.len(), "dealt card");
+        card
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::triple;
+    use num_traits::ToPrimitive;
     use pretty_assertions::assert_eq;
 
     #[test]
     fn card_display() {
-        let mut buf = String::new();
-        for card in DECK.clone().into_iter() {
-            buf.push_str(&card.to_string());
+        assert_eq!(Card(0).to_string(), "1R#D");
+        assert_eq!(Card(40).to_string(), "2G~S");
+        assert_eq!(Card(80).to_string(), "3P=O");
+
+        let mut displayed: Vec<_> = DECK.iter().map(Card::to_string).collect();
+        displayed.sort();
+        displayed.dedup();
+        assert_eq!(displayed.len(), DECK_SIZE as usize);
+    }
+
+    #[test]
+    fn card_from_str() {
+        assert_eq!("1R#D".parse(), Ok(Card(0)));
+        assert_eq!("3P=O".parse(), Ok(Card(80)));
+        for card in DECK.iter() {
+            assert_eq!(card.to_string().parse(), Ok(*card));
+        }
+
+        for invalid in ["", "1R#", "1R#DD", "4R#D", "1r#D", "1R*D", "1R#X"] {
+            assert_eq!(
+                invalid.parse::<Card>(),
+                Err(ParseCardError(invalid.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn attribute_values() {
+        assert_eq!(Color::COUNT, 3);
+        assert_eq!(
+            Color::iter().collect::<Vec<_>>(),
+            vec![Color::Red, Color::Green, Color::Purple]
+        );
+        // Declaration order matches the numeric values used to index cards
+        for (i, count) in Count::iter().enumerate() {
+            assert_eq!(count.to_usize(), Some(i));
         }
-        panic!("{}", buf);
+        assert_eq!(
+            Shade::ALL.map(|shade| shade.name()),
+            ["solid", "striped", "open"]
+        );
+        assert_eq!(Shape::Squiggle.name(), "squiggle");
+
+        let cards: Vec<_> = Color::iter()
+            .flat_map(|color| Count::iter().map(move |count| (color, count)))
+            .flat_map(|(color, count)| Shade::iter().map(move |shade| (color, count, shade)))
+            .flat_map(|(color, count, shade)| {
+                Shape::iter().map(move |shape| {
+                    Card::from(CardProperties {
+                        color,
+                        count,
+                        shade,
+                        shape,
+                    })
+                })
+            })
+            .collect();
+        assert_eq!(cards, *DECK);
+    }
+
+    #[test]
+    fn attribute_index() {
+        let card: Card = "2R~O".parse().unwrap();
+        let properties = CardProperties::from(card);
+        assert_eq!(Attribute::Color.index(card), properties.color as usize);
+        assert_eq!(Attribute::Count.index(card), properties.count as usize);
+        assert_eq!(Attribute::Shade.index(card), properties.shade as usize);
+        assert_eq!(Attribute::Shape.index(card), properties.shape as usize);
+    }
+
+    #[test]
+    fn attribute_consistency() {
+        fn consistent<T: PartialEq>(a: T, b: T, c: T) -> bool {
+            a == b && b == c || a != b && b != c && a != c
+        }
+
+        for a in DECK.iter().step_by(2) {
+            for b in DECK.iter().step_by(3) {
+                for c in DECK.iter().step_by(5) {
+                    let cards = [*a, *b, *c];
+                    let [pa, pb, pc] = cards.map(CardProperties::from);
+                    let expected = [
+                        consistent(pa.color, pb.color, pc.color),
+                        consistent(pa.count, pb.count, pc.count),
+                        consistent(pa.shade, pb.shade, pc.shade),
+                        consistent(pa.shape, pb.shape, pc.shape),
+                    ];
+                    assert_eq!(
+                        Attribute::ALL.map(|attribute| attribute.is_consistent(cards)),
+                        expected,
+                        "{a} {b} {c}"
+                    );
+                }
+            }
+        }
+
+        let cards = ["1R#D", "2R~D", "3P=D"].map(|card| card.parse::<Card>().unwrap());
+        let triple = Triple::from((cards[0], cards[1], cards[2]));
+        assert_eq!(
+            triple.broken_attributes().collect::<Vec<_>>(),
+            vec![Attribute::Color]
+        );
+        assert!(Attribute::Count.is_consistent(cards));
+        assert!(Attribute::Shape.is_consistent(cards));
+    }
+
+    #[test]
+    fn card_getters_match_properties() {
+        for card in DECK.iter() {
+            let properties = CardProperties::from(*card);
+            assert_eq!(card.color(), properties.color);
+            assert_eq!(card.count(), properties.count);
+            assert_eq!(card.shade(), properties.shade);
+            assert_eq!(card.shape(), properties.shape);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn card_serde() {
+        let card: Card = "2R~O".parse().unwrap();
+        assert_eq!(serde_json::to_string(&card).unwrap(), "\"2R~O\"");
+        assert_eq!(serde_json::from_str::<Card>("\"2R~O\"").unwrap(), card);
+        assert!(serde_json::from_str::<Card>("\"2R~X\"").is_err());
+        assert!(serde_json::from_str::<Card>("40").is_err());
+    }
+
+    #[test]
+    fn card_index() {
+        for card in DECK.iter() {
+            assert_eq!(Card::try_from(u8::from(*card)), Ok(*card));
+        }
+        assert_eq!(Card::try_from(81), Err(InvalidCardIndex(81)));
+        assert_eq!(
+            Card::try_from(255).unwrap_err().to_string(),
+            "invalid card index 255, expected 0 to 80"
+        );
+    }
+
+    #[test]
+    fn is_set_agrees_with_attributes() {
+        for a in DECK.iter() {
+            for b in DECK.iter() {
+                assert!(Triple::from((*a, *b, a.third(*b)))
+                    .broken_attributes()
+                    .next()
+                    .is_none());
+                for c in DECK.iter().step_by(4) {
+                    let triple = Triple::from((*a, *b, *c));
+                    assert_eq!(triple.is_set(), triple.broken_attributes().next().is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn batch_matches_is_set() {
+        let triples: Vec<_> = DECK
+            .iter()
+            .flat_map(|a| DECK.iter().step_by(3).map(move |b| (*a, *b)))
+            .flat_map(|(a, b)| DECK.iter().step_by(5).map(move |c| (a, b, *c)))
+            .chain([(Card(0), Card(1), Card(2))])
+            .collect();
+        let batch = is_set_batch(&triples);
+        assert_eq!(batch.len(), triples.len());
+        for (triple, is_set) in triples.iter().zip(batch.iter()) {
+            assert_eq!(*is_set, Triple::from(*triple).is_set(), "{triple:?}");
+        }
+        assert!(batch.any());
+        assert!(is_set_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn triple_difficulty() {
+        let difficulty = |notation: &str| triple(notation).difficulty();
+        assert_eq!(difficulty("1R#D 1R#D 1R#D"), 0);
+        assert_eq!(difficulty("1R#D 2R#D 3R#D"), 1);
+        assert_eq!(difficulty("1R#D 1R~O 1R=S"), 2);
+        assert_eq!(difficulty("1R#D 2G~S 3P=O"), 4);
     }
 
     #[test]
     fn card_add() {
+        // 1 1 1 1, 2 2 2 2 and 0 0 0 0 as vectors
         assert_eq!(Card(0) + Card(40), Card(40));
         assert_eq!(Card(40) + Card(40), Card(80));
-        assert_eq!(Card(80) + Card(40), Card(39));
-        assert_eq!(Card(80) + Card(1), Card(0));
+        assert_eq!(Card(80) + Card(40), Card(0));
+        // Without carrying from the shape into the shade
+        assert_eq!(Card(2) + Card(1), Card(0));
+        assert_eq!(Card(80) + Card(1), Card(78));
     }
 
     #[test]
     fn card_sub() {
         assert_eq!(Card(80) - Card(40), Card(40));
         assert_eq!(Card(40) - Card(40), Card(0));
-        assert_eq!(Card(0) - Card(40), Card(41));
-        assert_eq!(Card(0) - Card(1), Card(80));
+        assert_eq!(Card(0) - Card(40), Card(80));
+        assert_eq!(Card(0) - Card(1), Card(2));
+        assert_eq!(-Card(5), Card(7));
     }
 
     #[test]
-    fn card_sub_exhaustive() {
-        for a in 0..DECK_SIZE {
-            for b in 0..DECK_SIZE {
-                let result = std::panic::catch_unwind(|| {
-                    let _ = Card(a) - Card(b);
-                });
-                if result.is_err() {
-                    panic!("card subtraction panicked on '{a} - {b}'");
+    fn card_arithmetic_exhaustive() {
+        for a in DECK.iter().copied() {
+            assert_eq!(Card::from_coordinates(a.coordinates()), Some(a));
+            assert_eq!(a + -a, Card(0));
+            for b in DECK.iter().copied() {
+                let sum = a + b;
+                assert_eq!(sum, b + a);
+                assert_eq!(sum - b, a);
+                for attribute in Attribute::iter() {
+                    let position = Attribute::ALL.iter().position(|x| *x == attribute).unwrap();
+                    assert_eq!(
+                        attribute.index(sum),
+                        (attribute.index(a) + attribute.index(b)) % 3
+                    );
+                    assert_eq!(attribute.index(a), a.coordinates()[position] as usize);
                 }
+                let third = a.third(b);
+                assert_eq!(third, -(a + b));
+                assert!(Attribute::iter().all(|x| x.is_consistent([a, b, third])));
             }
         }
+        assert_eq!(Card::from_coordinates([0, 1, 2, 3]), None);
     }
 
     #[test]
@@ -231,7 +877,7 @@ mod tests {
     fn card_properties_roundtrip_exhaustive() {
         for card in DECK.iter() {
             let result = std::panic::catch_unwind(|| {
-                assert_eq!(Card::from(CardProperties::from(card.clone())), card.clone());
+                assert_eq!(Card::from(CardProperties::from(*card)), *card);
             });
             if result.is_err() {
                 panic!("card properties roundtrip panicked on '{card:?}'");
@@ -254,7 +900,7 @@ mod tests {
         fn permutations(&self) -> Box<dyn Iterator<Item = Self>> {
             use itertools::Itertools;
 
-            let (a, b, c) = self.cards.clone();
+            let (a, b, c) = self.cards;
             Box::new(
                 [a, b, c]
                     .into_iter()
@@ -291,44 +937,61 @@ mod tests {
         // All the same card is tecnically a set
         assert_all_permutations_is_set(
             Triple::from((
-                RED_ONE_SOLID_DIAMOND.clone(),
-                RED_ONE_SOLID_DIAMOND.clone(),
-                RED_ONE_SOLID_DIAMOND.clone(),
+                RED_ONE_SOLID_DIAMOND,
+                RED_ONE_SOLID_DIAMOND,
+                RED_ONE_SOLID_DIAMOND,
             )),
             true,
         );
         // A mixed set
         assert_all_permutations_is_set(
             Triple::from((
-                GREEN_TWO_SOLID_DIAMOND.clone(),
-                RED_ONE_SOLID_DIAMOND.clone(),
-                PURPLE_THREE_SOLID_DIAMOND.clone(),
+                GREEN_TWO_SOLID_DIAMOND,
+                RED_ONE_SOLID_DIAMOND,
+                PURPLE_THREE_SOLID_DIAMOND,
             )),
             true,
         );
         // All different set
         assert_all_permutations_is_set(
             Triple::from((
-                RED_ONE_SOLID_DIAMOND.clone(),
-                GREEN_TWO_STRIPED_OVAL.clone(),
-                PURPLE_THREE_OPEN_SQUIGGLE.clone(),
+                RED_ONE_SOLID_DIAMOND,
+                GREEN_TWO_STRIPED_OVAL,
+                PURPLE_THREE_OPEN_SQUIGGLE,
+            )),
+            true,
+        );
+        // Sets whose indices are not evenly spaced, as attributes do not carry into each other
+        assert_all_permutations_is_set(
+            Triple::from((
+                "1R#D".parse().unwrap(),
+                "1R~O".parse().unwrap(),
+                "1R=S".parse().unwrap(),
             )),
             true,
         );
         // Not sets
         assert_all_permutations_is_set(
             Triple::from((
-                RED_ONE_SOLID_DIAMOND.clone(),
-                RED_ONE_SOLID_DIAMOND.clone(),
-                PURPLE_THREE_SOLID_DIAMOND.clone(),
+                RED_ONE_SOLID_DIAMOND,
+                RED_ONE_SOLID_DIAMOND,
+                PURPLE_THREE_SOLID_DIAMOND,
+            )),
+            false,
+        );
+        assert_all_permutations_is_set(
+            Triple::from((
+                RED_ONE_SOLID_DIAMOND,
+                GREEN_TWO_SOLID_DIAMOND,
+                PURPLE_THREE_OPEN_SQUIGGLE,
             )),
             false,
         );
         assert_all_permutations_is_set(
             Triple::from((
-                RED_ONE_SOLID_DIAMOND.clone(),
-                GREEN_TWO_SOLID_DIAMOND.clone(),
-                PURPLE_THREE_OPEN_SQUIGGLE.clone(),
+                "3R=O".parse().unwrap(),
+                "3R#S".parse().unwrap(),
+                "2R~D".parse().unwrap(),
             )),
             false,
         );
@@ -0,0 +1,129 @@
+use crate::core::{Card, CardProperties, Color, Count, Shade, Shape};
+use std::fmt;
+
+/// How to write a card out as text.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Style {
+    /// Compact notation, e.g. `2R~O`. This is what `Display` writes.
+    #[default]
+    Compact,
+    /// Plain English, e.g. `two red striped ovals`. Also written by `{:#}`.
+    Verbose,
+    /// An emoji approximation for sharing in chat, e.g. `🔴🌓 🥚🥚`.
+    ///
+    /// The first emoji is the color, the second the shade as a moon phase (new for solid, half for
+    /// striped, full for open), then one shape emoji per symbol on the card.
+    Emoji,
+}
+
+/// A card written in a chosen [`Style`], from [`Card::styled`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Styled {
+    card: Card,
+    style: Style,
+}
+
+/// A way to draw cards, such as text in a [`Style`], symbols in a terminal, or SVG.
+///
+/// Frontends and exporters accept any renderer whose output they can show, so a new backend only
+/// needs to implement this, not change the code that lays out the board.
+pub trait CardRenderer {
+    type Output;
+
+    fn render(&self, card: Card) -> Self::Output;
+}
+
+impl CardRenderer for Style {
+    type Output = String;
+
+    fn render(&self, card: Card) -> String {
+        card.styled(*self).to_string()
+    }
+}
+
+impl Card {
+    /// Wrap the card to display it in the given style.
+    pub fn styled(self, style: Style) -> Styled {
+        Styled { card: self, style }
+    }
+}
+
+impl fmt::Display for Styled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let CardProperties {
+            color,
+            count,
+            shade,
+            shape,
+        } = CardProperties::from(self.card);
+        match self.style {
+            Style::Compact => write!(f, "{count}{color}{shade}{shape}"),
+            Style::Verbose => {
+                let plural = if count == Count::One { "" } else { "s" };
+                write!(
+                    f,
+                    "{} {} {} {}{plural}",
+                    count.name(),
+                    color.name(),
+                    shade.name(),
+                    shape.name()
+                )
+            }
+            Style::Emoji => {
+                let color = match color {
+                    Color::Red => "\u{1F534}",
+                    Color::Green => "\u{1F7E2}",
+                    Color::Purple => "\u{1F7E3}",
+                };
+                let shade = match shade {
+                    Shade::Solid => "\u{1F311}",
+                    Shade::Striped => "\u{1F313}",
+                    Shade::Open => "\u{1F315}",
+                };
+                let shape = match shape {
+                    Shape::Diamond => "\u{1F48E}",
+                    Shape::Squiggle => "\u{1F30A}",
+                    Shape::Oval => "\u{1F95A}",
+                };
+                let count = match count {
+                    Count::One => 1,
+                    Count::Two => 2,
+                    Count::Three => 3,
+                };
+                write!(f, "{color}{shade} {}", shape.repeat(count))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn styled_display() {
+        let card: Card = "2R~O".parse().unwrap();
+        assert_eq!(card.styled(Style::Compact).to_string(), "2R~O");
+        assert_eq!(
+            card.styled(Style::Verbose).to_string(),
+            "two red striped ovals"
+        );
+        assert_eq!(card.styled(Style::Emoji).to_string(), "🔴🌓 🥚🥚");
+
+        let card: Card = "1G#D".parse().unwrap();
+        assert_eq!(
+            card.styled(Style::Verbose).to_string(),
+            "one green solid diamond"
+        );
+        assert_eq!(card.styled(Style::Emoji).to_string(), "🟢🌑 💎");
+        assert_eq!(Style::Compact.render(card), "1G#D");
+    }
+
+    #[test]
+    fn alternate_flag_is_verbose() {
+        let card: Card = "3P=S".parse().unwrap();
+        assert_eq!(format!("{card:#}"), "three purple open squiggles");
+        assert_eq!(format!("{card}"), "3P=S");
+    }
+}
@@ -0,0 +1,171 @@
+//! The "find them all" puzzle: a board that never changes, on which every set must be found.
+//!
+//! Unlike a game, claiming a set leaves its cards on the board, as they may be in other sets
+//! too, and nothing is ever dealt. Each set may only be found once, in any order of its cards.
+//! A [`ClaimConstraint`] can limit which set may be found next, as in a
+//! [`ChainPuzzle`](crate::ChainPuzzle).
+
+use crate::{
+    card_set::CardSet,
+    core::{Card, Triple},
+    game::ClaimError,
+    line::Line,
+    solver::find_sets,
+};
+use std::{fmt, sync::Arc};
+
+/// A rule for which set may be found next, given the set found before it, if any.
+pub trait ClaimConstraint: Send + Sync {
+    fn allows(&self, previous: Option<Line>, next: Line) -> bool;
+}
+
+/// A fixed board and the sets found on it so far.
+#[derive(Clone)]
+pub struct FindAll {
+    cards: Vec<Card>,
+    /// Every set on the board, worked out up front.
+    sets: Vec<Line>,
+    found: Vec<Line>,
+    constraint: Option<Arc<dyn ClaimConstraint>>,
+}
+
+impl fmt::Debug for FindAll {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FindAll")
+            .field("cards", &self.cards)
+            .field("sets", &self.sets)
+            .field("found", &self.found)
+            .field("constrained", &self.constraint.is_some())
+            .finish()
+    }
+}
+
+impl FindAll {
+    pub fn new(cards: Vec<Card>) -> Self {
+        let sets = find_sets(&cards)
+            .iter()
+            .filter_map(|set| Line::through(set.cards()[0], set.cards()[1]))
+            .collect();
+        Self {
+            cards,
+            sets,
+            found: Vec::new(),
+            constraint: None,
+        }
+    }
+
+    /// Only allow the sets the constraint does, refusing the rest with
+    /// [`ClaimError::Disallowed`].
+    pub fn with_constraint(mut self, constraint: impl ClaimConstraint + 'static) -> Self {
+        self.constraint = Some(Arc::new(constraint));
+        self
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// How many sets there are to find.
+    pub fn total(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// The sets found so far, in the order they were found.
+    pub fn found(&self) -> &[Line] {
+        &self.found
+    }
+
+    /// The sets still to find, in board order.
+    pub fn remaining(&self) -> impl Iterator<Item = Line> + '_ {
+        self.sets
+            .iter()
+            .copied()
+            .filter(|set| !self.found.contains(set))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.found.len() == self.sets.len()
+    }
+
+    /// Whether sets are left to find but the constraint allows none of them next.
+    pub fn is_stuck(&self) -> bool {
+        let Some(constraint) = &self.constraint else {
+            return false;
+        };
+        let last = self.found.last().copied();
+        !self.is_complete() && !self.remaining().any(|set| constraint.allows(last, set))
+    }
+
+    /// Claim a set on the board, returning it once found.
+    pub fn try_claim(&mut self, triple: Triple) -> Result<Line, ClaimError> {
+        let cards = triple.cards();
+        let on_board = CardSet::from(&self.cards[..]);
+        if let Some(card) = cards.iter().find(|card| !on_board.contains(**card)) {
+            return Err(ClaimError::NotOnBoard(*card));
+        }
+        let broken: Vec<_> = triple.broken_attributes().collect();
+        let set = Line::through(cards[0], cards[1])
+            .filter(|set| broken.is_empty() && set.contains(cards[2]) && cards[2] != cards[0])
+            .ok_or(ClaimError::NotASet { cards, broken })?;
+        if self.found.contains(&set) {
+            return Err(ClaimError::AlreadyFound);
+        }
+        if let Some(constraint) = &self.constraint {
+            if !constraint.allows(self.found.last().copied(), set) {
+                return Err(ClaimError::Disallowed);
+            }
+        }
+        self.found.push(set);
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::Attribute,
+        test_util::{cards, triple},
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn every_set_is_found_once() {
+        // Two sets sharing 1R#D
+        let mut puzzle = FindAll::new(cards("1R#D 2G~S 3P=O 1R#S 1R#O 2G=O"));
+        assert_eq!(puzzle.total(), 2);
+        let first = puzzle.try_claim(triple("3P=O 2G~S 1R#D")).unwrap();
+        assert_eq!(first.cards().to_vec(), {
+            let mut set = cards("1R#D 2G~S 3P=O");
+            set.sort();
+            set
+        });
+        assert_eq!(
+            puzzle.try_claim(triple("1R#D 2G~S 3P=O")),
+            Err(ClaimError::AlreadyFound)
+        );
+        assert_eq!(
+            puzzle.try_claim(triple("1R#D 2G~S 1R#S")),
+            Err(ClaimError::NotASet {
+                cards: cards("1R#D 2G~S 1R#S").try_into().unwrap(),
+                broken: vec![
+                    Attribute::Color,
+                    Attribute::Count,
+                    Attribute::Shade,
+                    Attribute::Shape
+                ],
+            })
+        );
+        assert_eq!(
+            puzzle.try_claim(triple("1R#D 2R#D 3R#D")),
+            Err(ClaimError::NotOnBoard(cards("2R#D")[0]))
+        );
+        assert!(!puzzle.is_complete());
+        assert_eq!(puzzle.remaining().count(), 1);
+        puzzle.try_claim(triple("1R#D 1R#S 1R#O")).unwrap();
+        assert!(puzzle.is_complete());
+        assert_eq!(puzzle.found().len(), 2);
+        // The board is as it was
+        assert_eq!(puzzle.cards(), cards("1R#D 2G~S 3P=O 1R#S 1R#O 2G=O"));
+    }
+}
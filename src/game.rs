@@ -0,0 +1,681 @@
+use crate::{
+    board::Board,
+    core::{Attribute, Card, Deck, Triple},
+    scoring::{ScoringPolicy, Tally},
+    solver::find_first_set,
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::Duration,
+};
+
+/// Number of cards dealt to the board at the start of a game.
+pub const BOARD_SIZE: usize = 12;
+
+/// Who made a claim, for frontends with more than one player.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct PlayerId(pub u32);
+
+/// A game in progress: the board, the cards still to deal, and each player's score.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GameState {
+    board: Board,
+    deck: Deck,
+    #[cfg_attr(feature = "serde", serde(default))]
+    scoring: ScoringPolicy,
+    tallies: HashMap<PlayerId, Tally>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "schema", schemars(skip))]
+    subscribers: Subscribers,
+}
+
+/// Channels to send game events to. Copies of a game start without any, so that snapshots and
+/// search branches stay quiet.
+#[derive(Default)]
+struct Subscribers(Vec<Sender<GameEvent>>);
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} subscribers", self.0.len())
+    }
+}
+
+impl GameState {
+    /// Start a game by dealing the opening board from the deck.
+    pub fn new(mut deck: Deck) -> Self {
+        let board = std::iter::from_fn(|| deck.deal())
+            .take(BOARD_SIZE)
+            .collect();
+        Self {
+            board,
+            deck,
+            scoring: ScoringPolicy::default(),
+            tallies: HashMap::new(),
+            subscribers: Subscribers::default(),
+        }
+    }
+
+    /// Start a game from a board already laid out, of any size, dealing the rest from the deck,
+    /// e.g. to carry on from a position transcribed from a real table.
+    pub fn from_board(board: Board, deck: Deck) -> Self {
+        Self {
+            board,
+            deck,
+            scoring: ScoringPolicy::default(),
+            tallies: HashMap::new(),
+            subscribers: Subscribers::default(),
+        }
+    }
+
+    /// Score the game by the given policy rather than a point per set.
+    pub fn with_scoring(mut self, scoring: ScoringPolicy) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Receive every event from now on, e.g. to log the game or keep statistics.
+    ///
+    /// Events queue until received. Dropping the receiver unsubscribes.
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.0.push(sender);
+        receiver
+    }
+
+    pub(crate) fn emit(&mut self, event: GameEvent) {
+        self.subscribers
+            .0
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    pub fn scoring(&self) -> ScoringPolicy {
+        self.scoring
+    }
+
+    /// The player's score by the game's [`ScoringPolicy`].
+    pub fn score(&self, player: PlayerId) -> u32 {
+        self.scoring.score(&self.tally(player))
+    }
+
+    /// Everything that counts towards the player's score.
+    pub fn tally(&self, player: PlayerId) -> Tally {
+        self.tallies.get(&player).copied().unwrap_or_default()
+    }
+
+    /// Take points from the player, which sets they found before or find later pay for.
+    pub(crate) fn penalize(&mut self, player: PlayerId, points: u32) {
+        self.tallies.entry(player).or_default().penalty += points;
+    }
+
+    /// The game as it is now, to go back to with [`GameState::restore`], e.g. to undo a move or
+    /// to try moves in a search without replaying the game from the start.
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            board: self.board.clone(),
+            deck: self.deck.clone(),
+            tallies: self.tallies.clone(),
+        }
+    }
+
+    /// Put the game back as it was at the snapshot. Subscribers stay subscribed, and hear nothing
+    /// of the change.
+    pub fn restore(&mut self, snapshot: GameSnapshot) {
+        let GameSnapshot {
+            board,
+            deck,
+            tallies,
+        } = snapshot;
+        self.board = board;
+        self.deck = deck;
+        self.tallies = tallies;
+    }
+
+    /// Whether the deck is empty and no set is left on the board.
+    pub fn is_over(&self) -> bool {
+        self.deck.0.is_empty() && !contains_set(self.board.cards())
+    }
+
+    /// Claim three cards on the board as a set for the player.
+    ///
+    /// If they are one, the player scores and the cards are replaced from the deck in place. A
+    /// board that was expanded with [`GameState::deal_extra`] shrinks back instead.
+    ///
+    /// Subscribers see the claim or its refusal, and the end of the game if it was the last set.
+    pub fn try_claim(
+        &mut self,
+        player: PlayerId,
+        triple: Triple,
+    ) -> Result<ClaimAccepted, ClaimError> {
+        self.settle_claim(player, triple, None)
+    }
+
+    /// Claim a set made at `at`, as [`GameState::try_claim`], so that it can earn a
+    /// [`TimeBonus`](crate::TimeBonus).
+    ///
+    /// Times are on a clock shared by every claim in the game, as for [`Arbiter`](crate::Arbiter).
+    pub fn try_claim_at(
+        &mut self,
+        player: PlayerId,
+        triple: Triple,
+        at: Duration,
+    ) -> Result<ClaimAccepted, ClaimError> {
+        self.settle_claim(player, triple, Some(at))
+    }
+
+    fn settle_claim(
+        &mut self,
+        player: PlayerId,
+        triple: Triple,
+        at: Option<Duration>,
+    ) -> Result<ClaimAccepted, ClaimError> {
+        #[cfg(feature = "debug-invariants")]
+        let before = self.snapshot();
+        let result = self.claim(player, triple, at);
+        if let Err(ClaimError::NotASet { .. }) = result {
+            let tally = self.tallies.entry(player).or_default();
+            self.scoring.misclaimed(tally);
+        }
+        let event = match &result {
+            Ok(claim) => GameEvent::Claimed {
+                player,
+                claim: claim.clone(),
+            },
+            Err(error) => GameEvent::Refused {
+                player,
+                error: error.clone(),
+            },
+        };
+        #[cfg(feature = "debug-invariants")]
+        self.check_invariants(&before, &event);
+        self.emit(event);
+        if result.is_ok() && self.is_over() {
+            self.emit(GameEvent::GameOver);
+        }
+        result
+    }
+
+    fn claim(
+        &mut self,
+        player: PlayerId,
+        triple: Triple,
+        at: Option<Duration>,
+    ) -> Result<ClaimAccepted, ClaimError> {
+        if self.is_over() {
+            return Err(ClaimError::GameOver);
+        }
+        let cards = triple.cards();
+        let mut positions = [0; 3];
+        for (position, card) in positions.iter_mut().zip(cards) {
+            *position = self
+                .board
+                .0
+                .iter()
+                .position(|on_board| *on_board == card)
+                .ok_or(ClaimError::NotOnBoard(card))?;
+        }
+        let broken: Vec<_> = triple.broken_attributes().collect();
+        // Sorted, a card claimed twice is next to itself whichever places it was claimed in
+        positions.sort_unstable();
+        if !broken.is_empty() || positions[0] == positions[1] || positions[1] == positions[2] {
+            return Err(ClaimError::NotASet { cards, broken });
+        }
+
+        let tally = self.tallies.entry(player).or_default();
+        self.scoring.found(tally, at);
+        let score = self.score(player);
+        let refill = self.board.0.len() <= BOARD_SIZE;
+        let mut dealt = Vec::new();
+        for position in positions.into_iter().rev() {
+            match refill.then(|| self.deck.deal()).flatten() {
+                Some(card) => {
+                    self.board.0[position] = card;
+                    dealt.push(card);
+                }
+                None => {
+                    self.board.0.remove(position);
+                }
+            }
+        }
+        Ok(ClaimAccepted {
+            cards,
+            dealt,
+            score,
+        })
+    }
+
+    /// Deal up to three more cards onto the board, for when no set can be found. Returns the
+    /// cards dealt, which is none once the deck is empty.
+    pub fn deal_extra(&mut self) -> Vec<Card> {
+        #[cfg(feature = "debug-invariants")]
+        let before = self.snapshot();
+        let dealt: Vec<_> = std::iter::from_fn(|| self.deck.deal()).take(3).collect();
+        self.board.0.extend(&dealt);
+        if !dealt.is_empty() {
+            let event = GameEvent::DealtExtra(dealt.clone());
+            #[cfg(feature = "debug-invariants")]
+            self.check_invariants(&before, &event);
+            self.emit(event);
+        }
+        dealt
+    }
+
+    /// Panic if the change from `before` that made the event broke a rule of the engine: a card
+    /// is in the game twice, cards left the game other than by being claimed as a set, or the
+    /// sets counted for players do not match the claims.
+    #[cfg(feature = "debug-invariants")]
+    fn check_invariants(&self, before: &GameSnapshot, event: &GameEvent) {
+        use crate::card_set::CardSet;
+
+        let broken = |rule: String| panic!("engine invariant broken: {rule}, after {event:?}");
+        let in_game = |board: &Board, deck: &Deck| {
+            let mut in_game = CardSet::new();
+            for card in board.cards().iter().chain(&deck.0) {
+                if !in_game.insert(*card) {
+                    broken(format!("{card} is in the game twice"));
+                }
+            }
+            in_game
+        };
+        let sets = |tallies: &HashMap<PlayerId, Tally>| -> u32 {
+            tallies.values().map(|tally| tally.sets).sum()
+        };
+        let was_in_game = in_game(&before.board, &before.deck);
+        let is_in_game = in_game(&self.board, &self.deck);
+        let left = was_in_game - is_in_game;
+        if !is_in_game.is_subset(&was_in_game) {
+            broken(format!("{:?} joined the game", is_in_game - was_in_game));
+        }
+        match event {
+            GameEvent::Claimed { player, claim } => {
+                let [a, b, c] = claim.cards;
+                let claimed: CardSet = claim.cards.into_iter().collect();
+                if claimed.len() != 3 || !Triple::from((a, b, c)).is_set() {
+                    broken(format!("{a} {b} {c} was accepted but is not a set"));
+                }
+                if left != claimed {
+                    broken(format!("{left:?} left the game, not {claimed:?}"));
+                }
+                let found = |tallies: &HashMap<PlayerId, Tally>| {
+                    tallies
+                        .get(player)
+                        .map(|tally| tally.sets)
+                        .unwrap_or_default()
+                };
+                if found(&self.tallies) != found(&before.tallies) + 1
+                    || sets(&self.tallies) != sets(&before.tallies) + 1
+                {
+                    broken(format!("sets found did not go up by one for {player:?}"));
+                }
+            }
+            GameEvent::DealtExtra(dealt) => {
+                let mut expected = before.board.cards().to_vec();
+                expected.extend(dealt);
+                if !left.is_empty() || self.board.cards() != expected {
+                    broken(format!("the board is not the old board and {dealt:?}"));
+                }
+            }
+            _ => {
+                if !left.is_empty() || self.board != before.board {
+                    broken("the board changed".to_owned());
+                }
+                if sets(&self.tallies) != sets(&before.tallies) {
+                    broken("sets found changed".to_owned());
+                }
+            }
+        }
+    }
+}
+
+/// A game at a moment, from [`GameState::snapshot`].
+///
+/// It holds only what moves change, and not the scoring policy or subscribers.
+#[derive(Debug, Clone)]
+pub struct GameSnapshot {
+    board: Board,
+    deck: Deck,
+    tallies: HashMap<PlayerId, Tally>,
+}
+
+impl GameSnapshot {
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+}
+
+/// A game shared between threads, such as a frontend and bots playing against it.
+///
+/// Clones refer to the same game. Each call locks it for its duration, so concurrent claims are
+/// applied one at a time and only the first of two claims for the same cards succeeds.
+#[derive(Debug, Clone)]
+pub struct SharedGame(Arc<Mutex<GameState>>);
+
+impl From<GameState> for SharedGame {
+    fn from(game: GameState) -> Self {
+        Self(Arc::new(Mutex::new(game)))
+    }
+}
+
+impl SharedGame {
+    fn lock(&self) -> MutexGuard<'_, GameState> {
+        // Every change to the game completes before it can panic, so it is never left half done
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// See [`GameState::try_claim`].
+    pub fn try_claim(&self, player: PlayerId, triple: Triple) -> Result<ClaimAccepted, ClaimError> {
+        self.lock().try_claim(player, triple)
+    }
+
+    /// See [`GameState::try_claim_at`].
+    pub fn try_claim_at(
+        &self,
+        player: PlayerId,
+        triple: Triple,
+        at: Duration,
+    ) -> Result<ClaimAccepted, ClaimError> {
+        self.lock().try_claim_at(player, triple, at)
+    }
+
+    /// See [`GameState::deal_extra`].
+    pub fn deal_extra(&self) -> Vec<Card> {
+        self.lock().deal_extra()
+    }
+
+    /// Read the game, holding the lock so that everything read is from the same moment.
+    pub fn read<T>(&self, read: impl FnOnce(&GameState) -> T) -> T {
+        read(&self.lock())
+    }
+
+    /// A copy of the game as it is now.
+    pub fn snapshot(&self) -> GameState {
+        self.lock().clone()
+    }
+
+    /// See [`GameState::restore`].
+    pub fn restore(&self, snapshot: GameSnapshot) {
+        self.lock().restore(snapshot)
+    }
+}
+
+/// Whether any three distinct cards are a set.
+fn contains_set(cards: &[Card]) -> bool {
+    find_first_set(cards).is_some()
+}
+
+/// Something that happened in a game, for frontends following it without owning it.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum GameEvent {
+    /// A player claimed a set.
+    Claimed {
+        player: PlayerId,
+        claim: ClaimAccepted,
+    },
+    /// A player's claim was refused.
+    Refused { player: PlayerId, error: ClaimError },
+    /// Extra cards were dealt onto the board.
+    DealtExtra(Vec<Card>),
+    /// The deck is empty and no set is left on the board.
+    GameOver,
+    /// A player's time ran out, from [`Clocks`](crate::Clocks).
+    OutOfTime(PlayerId),
+    /// A player was shown a card in a set, from [`Hints`](crate::Hints).
+    Hinted {
+        player: PlayerId,
+        card: Card,
+        /// The player's score after paying for the hint.
+        score: u32,
+    },
+}
+
+/// A successful claim, from [`GameState::try_claim`].
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ClaimAccepted {
+    pub cards: [Card; 3],
+    /// Cards dealt from the deck to replace the claimed ones, possibly none.
+    pub dealt: Vec<Card>,
+    /// The player's score after the claim.
+    pub score: u32,
+}
+
+/// Why a claim was refused. The game is unchanged.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ClaimError {
+    /// The cards are not a set. Repeating a card is never a set, even with no broken attribute.
+    NotASet {
+        cards: [Card; 3],
+        /// The attributes that are neither all the same nor all different.
+        broken: Vec<Attribute>,
+    },
+    /// A claimed card is not on the board.
+    NotOnBoard(Card),
+    /// The game has ended, so nothing can be claimed.
+    GameOver,
+    /// Another player claimed some of the same cards first, from [`Arbiter`](crate::Arbiter).
+    Beaten { by: PlayerId },
+    /// The player's time has run out, from [`Clocks`](crate::Clocks).
+    OutOfTime,
+    /// The set has been found before, from [`FindAll`](crate::FindAll).
+    AlreadyFound,
+    /// The set may not be found next, by a [`ClaimConstraint`](crate::ClaimConstraint).
+    Disallowed,
+}
+
+impl fmt::Display for ClaimError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotASet {
+                cards: [a, b, c], ..
+            } => write!(f, "{a} {b} {c} is not a set"),
+            Self::NotOnBoard(card) => write!(f, "{card} is not on the board"),
+            Self::GameOver => f.write_str("the game is over"),
+            Self::Beaten { by } => write!(f, "player {} claimed those cards first", by.0),
+            Self::OutOfTime => f.write_str("your time has run out"),
+            Self::AlreadyFound => f.write_str("that set has already been found"),
+            Self::Disallowed => f.write_str("that set may not be found next"),
+        }
+    }
+}
+
+impl std::error::Error for ClaimError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{cards, game, triple, BOARD};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn claim_refills_in_place() {
+        let mut game = game(&format!("{BOARD} 3P~S 3P~O 3P~D"));
+        let player = PlayerId(1);
+        assert_eq!(
+            game.try_claim(player, triple("3P=O 1R#D 2G~S")),
+            Ok(ClaimAccepted {
+                cards: triple("3P=O 1R#D 2G~S").cards(),
+                dealt: cards("3P~S 3P~O 3P~D"),
+                score: 1,
+            })
+        );
+        assert_eq!(game.board().cards()[..3], cards("3P~D 3P~O 3P~S"));
+        assert_eq!(game.score(player), 1);
+        assert_eq!(game.score(PlayerId(2)), 0);
+        assert_eq!(game.deck().0, vec![]);
+    }
+
+    #[test]
+    fn claim_errors() {
+        let mut game = game(BOARD).with_scoring(ScoringPolicy {
+            misclaim: 1,
+            ..ScoringPolicy::default()
+        });
+        let player = PlayerId::default();
+        assert_eq!(
+            game.try_claim(player, triple("1R#D 2G~S 3P~O")),
+            Err(ClaimError::NotOnBoard(cards("3P~O")[0]))
+        );
+        assert_eq!(
+            game.try_claim(player, triple("1R#D 2G~S 1R#S")),
+            Err(ClaimError::NotASet {
+                cards: triple("1R#D 2G~S 1R#S").cards(),
+                broken: Attribute::ALL.to_vec(),
+            })
+        );
+        assert_eq!(
+            game.try_claim(player, triple("1R#D 1R#D 1R#D")),
+            Err(ClaimError::NotASet {
+                cards: triple("1R#D 1R#D 1R#D").cards(),
+                broken: vec![],
+            })
+        );
+        assert_eq!(
+            game.try_claim(player, triple("1R#D 2G~S 1R#D")),
+            Err(ClaimError::NotASet {
+                cards: triple("1R#D 2G~S 1R#D").cards(),
+                broken: Attribute::ALL.to_vec(),
+            })
+        );
+        assert_eq!(game.score(player), 0);
+        // Only the claims that were not sets count as misclaims
+        assert_eq!(game.tally(player).misclaims, 3);
+        assert_eq!(game.board().cards(), cards(BOARD));
+    }
+
+    #[test]
+    fn shared_game_claims_once() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<GameState>();
+        assert_send_sync::<SharedGame>();
+
+        let shared = SharedGame::from(game(BOARD));
+        let results: Vec<_> = (0..8)
+            .map(|player| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    shared.try_claim(PlayerId(player), triple("1R#D 2G~S 3P=O"))
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect();
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+        assert!(results.iter().all(
+            |result| result.is_ok() || *result == Err(ClaimError::NotOnBoard(cards("1R#D")[0]))
+        ));
+        let total: u32 =
+            shared.read(|game| (0..8).map(|player| game.score(PlayerId(player))).sum());
+        assert_eq!(total, 1);
+        assert_eq!(shared.snapshot().board().cards().len(), 9);
+    }
+
+    #[test]
+    fn subscribers_receive_every_event() {
+        let mut game = game(&format!("{BOARD} 3P~S 3P~O 3P~D"));
+        let events = game.subscribe();
+        let unsubscribed = game.subscribe();
+        drop(unsubscribed);
+        let player = PlayerId(3);
+
+        let claim = game.try_claim(player, triple("1R#D 2G~S 3P=O")).unwrap();
+        let refused = game.try_claim(player, triple("1R#D 2G~S 3P=O"));
+        // Copies do not inherit subscribers
+        let _ = game.clone().try_claim(player, triple("3P~S 3P~O 3P~D"));
+        assert_eq!(game.deal_extra(), vec![]);
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                GameEvent::Claimed { player, claim },
+                GameEvent::Refused {
+                    player,
+                    error: refused.unwrap_err()
+                },
+            ]
+        );
+        assert!(format!("{game:?}").contains("1 subscribers"));
+    }
+
+    #[test]
+    fn restore_undoes_moves() {
+        let mut game = game(&format!("{BOARD} 3P~S 3P~O 3P~D"));
+        let events = game.subscribe();
+        let player = PlayerId::default();
+        let before = game.snapshot();
+        game.try_claim(player, triple("1R#D 2G~S 3P=O")).unwrap();
+        assert_eq!(game.score(player), 1);
+        assert!(before.deck().0.len() > game.deck().0.len());
+
+        game.restore(before.clone());
+        assert_eq!(game.board(), before.board());
+        assert_eq!(game.board().cards(), cards(BOARD));
+        assert_eq!(game.deck().0.len(), 3);
+        assert_eq!(game.score(player), 0);
+        // Still subscribed, to the game as restored
+        assert_eq!(events.try_iter().count(), 1);
+        game.try_claim(player, triple("1R#D 2G~S 3P=O")).unwrap();
+        assert_eq!(events.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn games_start_from_any_board() {
+        let board: Board = cards("1R#D 2G~S 3P=O").into();
+        let mut game = GameState::from_board(board.clone(), Deck(cards("3P~S")));
+        assert_eq!(game.board(), &board);
+        assert!(!game.is_over());
+        game.try_claim(PlayerId::default(), triple("1R#D 2G~S 3P=O"))
+            .unwrap();
+        assert!(game.deck().0.is_empty());
+    }
+
+    #[test]
+    fn board_shrinks_once_the_deck_is_empty() {
+        let mut game = game(BOARD);
+        assert_eq!(game.deal_extra(), vec![]);
+        let claimed = game.try_claim(PlayerId::default(), triple("1R#S 1R#O 1R#D"));
+        assert_eq!(claimed.map(|claim| claim.dealt), Ok(vec![]));
+        assert_eq!(game.board().cards().len(), 9);
+        assert!(!game.is_over());
+
+        let mut game = self::game("1R#D 2G~S 1R#S");
+        assert!(game.is_over());
+        assert_eq!(
+            game.try_claim(PlayerId::default(), triple("1R#D 2G~S 1R#S")),
+            Err(ClaimError::GameOver)
+        );
+    }
+}
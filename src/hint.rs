@@ -0,0 +1,323 @@
+//! Hints as a rule of the game, so that they come at a price.
+//!
+//! Each hint points out a card in a set on the board, and asking again while that set is still
+//! there points out its next card. Once every card of a set has been pointed out, the next hint
+//! is in another set. A player may only ask a few times a game, must wait a while
+//! between hints, and loses points for each, so scores say how much help was needed.
+
+use crate::{
+    core::Card,
+    game::{GameEvent, GameState, PlayerId},
+    solver::find_sets,
+};
+use std::{collections::HashMap, fmt, time::Duration};
+
+/// How many hints each player may have and what they cost.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HintRules {
+    /// Hints each player may have in a game.
+    pub allowed: u32,
+    /// Time a player must wait after a hint before the next.
+    pub cooldown: Duration,
+    /// Points taken from the player's score for each hint.
+    pub cost: u32,
+}
+
+impl Default for HintRules {
+    /// Three hints a game, half a minute apart, for a point each.
+    fn default() -> Self {
+        Self {
+            allowed: 3,
+            cooldown: Duration::from_secs(30),
+            cost: 1,
+        }
+    }
+}
+
+/// The hints given in a game.
+///
+/// Times are on a clock shared by every call, as for [`Clocks`](crate::Clocks).
+#[derive(Debug, Clone)]
+pub struct Hints {
+    rules: HintRules,
+    /// When each hint was given to each player.
+    given: HashMap<PlayerId, Vec<Duration>>,
+    /// The cards pointed out so far in the set each player was last hinted at.
+    revealed: HashMap<PlayerId, Vec<Card>>,
+    /// The sets each player has had every card of pointed out.
+    shown: HashMap<PlayerId, Vec<[Card; 3]>>,
+}
+
+impl Hints {
+    pub fn new(rules: HintRules) -> Self {
+        Self {
+            rules,
+            given: HashMap::new(),
+            revealed: HashMap::new(),
+            shown: HashMap::new(),
+        }
+    }
+
+    pub fn rules(&self) -> HintRules {
+        self.rules
+    }
+
+    /// Hints the player has had so far.
+    pub fn used(&self, player: PlayerId) -> u32 {
+        self.given
+            .get(&player)
+            .map_or(0, |given| given.len() as u32)
+    }
+
+    pub fn remaining(&self, player: PlayerId) -> u32 {
+        self.rules.allowed.saturating_sub(self.used(player))
+    }
+
+    /// When the player may have their next hint, or none once they have had them all.
+    pub fn ready_at(&self, player: PlayerId) -> Option<Duration> {
+        if self.remaining(player) == 0 {
+            return None;
+        }
+        let last = self.given.get(&player).and_then(|given| given.last());
+        Some(last.map_or(Duration::ZERO, |last| *last + self.rules.cooldown))
+    }
+
+    /// Point out a card in a set on the board to the player at `now`, charging them for it.
+    ///
+    /// Subscribers see the hint, with the player's score after paying for it.
+    pub fn try_hint(
+        &mut self,
+        game: &mut GameState,
+        player: PlayerId,
+        now: Duration,
+    ) -> Result<Hint, HintError> {
+        if game.is_over() {
+            return Err(HintError::GameOver);
+        }
+        let ready_at = self.ready_at(player).ok_or(HintError::NoneLeft)?;
+        if now < ready_at {
+            return Err(HintError::CoolingDown { ready_at });
+        }
+        let revealed = self.revealed.entry(player).or_default();
+        let shown = self.shown.entry(player).or_default();
+        let cards = game.board().cards();
+        // Carry on with the same set while it is on the board, otherwise start on another that
+        // has not been shown in full
+        let sets: Vec<_> = find_sets(cards).iter().map(|set| set.cards()).collect();
+        let set = match sets
+            .iter()
+            .copied()
+            .find(|set| revealed.iter().all(|card| set.contains(card)))
+        {
+            Some(set) if revealed.len() < set.len() => set,
+            _ => {
+                let set =
+                    sets.iter()
+                        .find(|set| !shown.contains(set))
+                        .ok_or(if sets.is_empty() {
+                            HintError::NoSet
+                        } else {
+                            HintError::AllShown
+                        })?;
+                revealed.clear();
+                *set
+            }
+        };
+        let card = set
+            .into_iter()
+            .find(|card| !revealed.contains(card))
+            .expect("a set not shown in full has a card left to show");
+        revealed.push(card);
+        if revealed.len() == set.len() {
+            shown.push(set);
+        }
+        Ok(self.give(game, player, card, now))
+    }
+
+    /// Count a hint the player was given earlier, e.g. replaying a record, charging them for it
+    /// without checking the rules.
+    pub fn restore(
+        &mut self,
+        game: &mut GameState,
+        player: PlayerId,
+        card: Card,
+        at: Duration,
+    ) -> Hint {
+        self.give(game, player, card, at)
+    }
+
+    fn give(&mut self, game: &mut GameState, player: PlayerId, card: Card, at: Duration) -> Hint {
+        self.given.entry(player).or_default().push(at);
+        game.penalize(player, self.rules.cost);
+        let score = game.score(player);
+        game.emit(GameEvent::Hinted {
+            player,
+            card,
+            score,
+        });
+        Hint { card, score }
+    }
+}
+
+/// A hint, from [`Hints::try_hint`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Hint {
+    /// A card in a set on the board.
+    pub card: Card,
+    /// The player's score after paying for the hint.
+    pub score: u32,
+}
+
+/// Why no hint was given. Nothing is charged.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HintError {
+    /// The player has had every hint they are allowed.
+    NoneLeft,
+    /// The player had a hint too recently.
+    CoolingDown { ready_at: Duration },
+    /// There is no set on the board, so more cards should be dealt.
+    NoSet,
+    /// Every card of every set on the board has been pointed out already.
+    AllShown,
+    /// The game has ended.
+    GameOver,
+}
+
+impl fmt::Display for HintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoneLeft => f.write_str("no hints left"),
+            Self::CoolingDown { ready_at } => {
+                write!(f, "the next hint is ready at {}s", ready_at.as_secs())
+            }
+            Self::NoSet => f.write_str("there is no set on the board"),
+            Self::AllShown => f.write_str("every set on the board has been shown"),
+            Self::GameOver => f.write_str("the game is over"),
+        }
+    }
+}
+
+impl std::error::Error for HintError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::Triple,
+        scoring::Tally,
+        test_util::{cards, game, BOARD},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn hints_reveal_a_set_one_card_at_a_time() {
+        let mut game = game(&format!("{BOARD} 3P~S"));
+        let events = game.subscribe();
+        let player = PlayerId(1);
+        let mut hints = Hints::new(HintRules {
+            allowed: 4,
+            cooldown: secs(10),
+            cost: 1,
+        });
+
+        let revealed: Vec<_> = [0, 10, 20]
+            .into_iter()
+            .map(|now| hints.try_hint(&mut game, player, secs(now)).unwrap().card)
+            .collect();
+        assert_eq!(revealed, cards("1R#D 2G~S 3P=O"));
+        assert_eq!(game.score(player), 0);
+        assert_eq!(game.tally(player).penalty, 3);
+        assert_eq!(
+            events.try_iter().last(),
+            Some(GameEvent::Hinted {
+                player,
+                card: revealed[2],
+                score: 0
+            })
+        );
+        // Points lost to hints come off sets found
+        let claim = game
+            .try_claim(
+                player,
+                Triple::from((revealed[0], revealed[1], revealed[2])),
+            )
+            .unwrap();
+        assert_eq!(claim.score, 0);
+        // A new set is hinted at once the last one has gone
+        let hint = hints.try_hint(&mut game, player, secs(30)).unwrap();
+        assert!(!revealed.contains(&hint.card));
+        assert_eq!(hints.remaining(player), 0);
+        assert_eq!(hints.ready_at(player), None);
+    }
+
+    #[test]
+    fn sets_shown_in_full_are_not_hinted_again() {
+        // A board with one set, 1R#D 1R#S 1R#O
+        let mut game = game("1R#D 1R#S 1R~D 1R~S 1G#D 1G#S 1G~D 1G~S 2R#D 2R#S 2R~D 1R#O");
+        let player = PlayerId(1);
+        let mut hints = Hints::new(HintRules {
+            allowed: 4,
+            cooldown: Duration::ZERO,
+            cost: 1,
+        });
+        let revealed: Vec<_> = (0..3)
+            .map(|_| hints.try_hint(&mut game, player, secs(0)).unwrap().card)
+            .collect();
+        assert_eq!(revealed, cards("1R#D 1R#S 1R#O"));
+        assert_eq!(
+            hints.try_hint(&mut game, player, secs(0)),
+            Err(HintError::AllShown)
+        );
+        assert_eq!(hints.used(player), 3);
+        assert_eq!(game.tally(player).penalty, 3);
+    }
+
+    #[test]
+    fn hint_rules_are_enforced() {
+        let mut game = game(BOARD);
+        let player = PlayerId(1);
+        let mut hints = Hints::new(HintRules {
+            allowed: 2,
+            ..HintRules::default()
+        });
+        assert_eq!(hints.ready_at(player), Some(Duration::ZERO));
+        assert!(hints.try_hint(&mut game, player, secs(5)).is_ok());
+        assert_eq!(
+            hints.try_hint(&mut game, player, secs(20)),
+            Err(HintError::CoolingDown { ready_at: secs(35) })
+        );
+        assert!(hints.try_hint(&mut game, player, secs(35)).is_ok());
+        assert_eq!(
+            hints.try_hint(&mut game, player, secs(100)),
+            Err(HintError::NoneLeft)
+        );
+        // Other players have their own
+        assert_eq!(hints.remaining(PlayerId(2)), 2);
+
+        let mut hints = Hints::new(HintRules::default());
+        let mut stuck =
+            self::game("1R#D 1R#S 1R~D 1R~S 1G#D 1G#S 1G~D 1G~S 2R#D 2R#S 2R~D 2R~S 3P=O");
+        assert_eq!(
+            hints.try_hint(&mut stuck, player, secs(0)),
+            Err(HintError::NoSet)
+        );
+        let mut over = self::game("1R#D 2G~S 1R#S");
+        assert_eq!(
+            hints.try_hint(&mut over, player, secs(0)),
+            Err(HintError::GameOver)
+        );
+        assert_eq!(hints.used(player), 0);
+        assert_eq!(stuck.tally(player), Tally::default());
+    }
+}
@@ -1,3 +1,58 @@
+mod arbiter;
+#[cfg(feature = "async")]
+mod async_game;
+mod board;
+mod card_set;
+mod chain;
+mod clock;
 mod core;
+mod display;
+mod find_all;
+mod game;
+mod hint;
+mod line;
+mod one_set;
+mod pattern;
+mod position;
+mod query;
+mod quiz;
+mod replay;
+mod scoring;
+pub mod setwithfriends;
+mod sgn;
+mod solver;
+mod swap;
+#[cfg(test)]
+pub(crate) mod test_util;
+pub mod test_vectors;
 
-pub use crate::core::{Card, CardProperties, Color, Count, Deck, Shade, Shape};
+pub use crate::arbiter::{Arbiter, PendingClaim};
+#[cfg(feature = "async")]
+pub use crate::async_game::AsyncGame;
+pub use crate::board::{Board, BoardDiff};
+pub use crate::card_set::{CardSet, Iter as CardSetIter, TrackedDeck};
+pub use crate::chain::{follows, full_chain, ChainPuzzle, Chained};
+pub use crate::clock::{Clocks, TimeControl};
+pub use crate::core::{
+    is_set_batch, Attribute, Card, CardProperties, Color, Count, Deck, InvalidCardIndex,
+    ParseCardError, Shade, Shape, Triple, SHUFFLE_VERSION,
+};
+pub use crate::display::{CardRenderer, Style, Styled};
+pub use crate::find_all::{ClaimConstraint, FindAll};
+pub use crate::game::{
+    ClaimAccepted, ClaimError, GameEvent, GameSnapshot, GameState, PlayerId, SharedGame, BOARD_SIZE,
+};
+pub use crate::hint::{Hint, HintError, HintRules, Hints};
+pub use crate::line::Line;
+pub use crate::one_set::OneSetBoard;
+pub use crate::pattern::{ParsePatternError, PatternCounts, PatternFilter, SetPattern};
+pub use crate::position::{InvalidPosition, ParsePositionError, Position, POSITION_VERSION};
+pub use crate::query::{CardQuery, ParseQueryError};
+pub use crate::quiz::{CountAnswer, CountQuiz, CountRules};
+pub use crate::replay::{Replay, ReplayError};
+pub use crate::scoring::{ScoringPolicy, StreakBonus, Tally, TimeBonus};
+pub use crate::sgn::{GameRecord, Move, ParseGameError, Start, SGN_VERSION};
+pub use crate::solver::{
+    find_first_set, find_sets, find_sets_ordered, find_sets_traced, PairExamined, SetOrder,
+};
+pub use crate::swap::{suggest_swaps, Swap, SwapTarget};
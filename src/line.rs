@@ -0,0 +1,242 @@
+//! Sets as lines in the affine space of cards.
+//!
+//! Each attribute has three values, so a card is a point with four coordinates in {0, 1, 2}, one
+//! per [`Attribute`]. Three distinct cards are a set exactly when they lie on a line: in every
+//! coordinate they are all the same or all different, which is when the coordinates sum to zero
+//! mod 3. Any two distinct cards therefore complete to exactly one set, and the 81 cards hold
+//! 81 × 80 / 6 = 1080 sets.
+//!
+//! `Card`'s `Add`, `Sub` and `Neg` work on these coordinates, so the third card of the line
+//! through `a` and `b` is `-(a + b)`, and the direction of a line is the difference between two of
+//! its cards.
+
+use crate::{
+    core::{Attribute, Card, CardProperties, Count, DECK},
+    pattern::SetPattern,
+};
+
+/// The three cards of a set, in ascending order.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub struct Line([Card; 3]);
+
+/// A direction in card space, as the difference between cards on a line, normalized so its
+/// first non-zero coordinate is 1. Lines are parallel when they share a direction.
+type Direction = Card;
+
+impl Line {
+    /// The line through two distinct cards, or `None` if they are the same card.
+    pub fn through(a: Card, b: Card) -> Option<Self> {
+        if a == b {
+            return None;
+        }
+        let mut cards = [a, b, a.third(b)];
+        cards.sort();
+        Some(Self(cards))
+    }
+
+    /// Every line, each once, in ascending order.
+    pub fn all() -> impl Iterator<Item = Self> {
+        DECK.iter().enumerate().flat_map(|(i, a)| {
+            DECK[i + 1..]
+                .iter()
+                .filter(move |b| a.third(**b) > **b)
+                .map(move |b| Self([*a, *b, a.third(*b)]))
+        })
+    }
+
+    pub fn cards(&self) -> [Card; 3] {
+        self.0
+    }
+
+    pub fn contains(&self, card: Card) -> bool {
+        self.0.contains(&card)
+    }
+
+    /// Which attributes are alike on all three cards.
+    pub fn pattern(&self) -> SetPattern {
+        SetPattern::of(*self)
+    }
+
+    /// Why the cards are a set, in plain English, e.g. "all different colors and counts, all
+    /// striped ovals", for commentary, tutorials and screen readers.
+    ///
+    /// The attributes that differ are named first. Those alike then describe every card at once,
+    /// e.g. "two red shapes on every card" when the count and color are alike.
+    pub fn describe(&self) -> String {
+        let pattern = self.pattern();
+        let mut clauses = Vec::new();
+        let different: Vec<_> = pattern
+            .different()
+            .map(|attribute| match attribute {
+                Attribute::Color => "colors",
+                Attribute::Count => "counts",
+                Attribute::Shade => "shades",
+                Attribute::Shape => "shapes",
+            })
+            .collect();
+        if !different.is_empty() {
+            clauses.push(format!("all different {}", list(&different)));
+        }
+
+        let card = CardProperties::from(self.0[0]);
+        let alike = |attribute| pattern.is_alike(attribute);
+        let mut words = Vec::new();
+        if alike(Attribute::Shade) {
+            words.push(card.shade.name().to_owned());
+        }
+        if alike(Attribute::Color) {
+            words.push(card.color.name().to_owned());
+        }
+        let plural = |shape: &str| format!("{shape}s");
+        if alike(Attribute::Count) {
+            // Counted, the shapes need a noun even when they differ
+            let shape = if alike(Attribute::Shape) {
+                card.shape.name()
+            } else {
+                "shape"
+            };
+            let shape = match card.count {
+                Count::One => shape.to_owned(),
+                _ => plural(shape),
+            };
+            words.insert(0, card.count.name().to_owned());
+            words.push(shape);
+            clauses.push(format!("{} on every card", words.join(" ")));
+        } else {
+            if alike(Attribute::Shape) {
+                words.push(plural(card.shape.name()));
+            }
+            if !words.is_empty() {
+                clauses.push(format!("all {}", words.join(" ")));
+            }
+        }
+        clauses.join(", ")
+    }
+
+    fn direction(&self) -> Direction {
+        let [a, b, _] = self.0;
+        let difference = b - a;
+        if difference.coordinates().iter().find(|x| **x != 0) == Some(&2) {
+            -difference
+        } else {
+            difference
+        }
+    }
+
+    /// Whether the lines never meet and run in the same direction. A line is parallel to itself.
+    pub fn is_parallel(&self, other: &Self) -> bool {
+        self.direction() == other.direction()
+    }
+
+    /// The 27 lines parallel to this one, including itself, which between them hold every card
+    /// exactly once.
+    pub fn parallel_class(&self) -> impl Iterator<Item = Self> {
+        let direction = self.direction();
+        Self::all().filter(move |line| line.direction() == direction)
+    }
+}
+
+/// Words joined as in a sentence, e.g. "colors, counts and shapes".
+fn list(words: &[&str]) -> String {
+    match words {
+        [] => String::new(),
+        [word] => (*word).to_owned(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{cards, triple};
+    use pretty_assertions::assert_eq;
+
+    /// The line through two cards in notation.
+    fn through(pair: &str) -> Option<Line> {
+        let [a, b] = cards(pair).try_into().unwrap();
+        Line::through(a, b)
+    }
+
+    #[test]
+    fn line_through() {
+        assert_eq!(through("1R#D 1R#D"), None);
+        assert_eq!(
+            through("3P=O 1R#D"),
+            Some(Line(triple("1R#D 2G~S 3P=O").cards()))
+        );
+        assert_eq!(
+            through("1R~O 1R#D").map(|line| line.cards()),
+            Some(triple("1R#D 1R~O 1R=S").cards())
+        );
+    }
+
+    #[test]
+    fn lines_describe_why_they_are_sets() {
+        let describe = |pair| through(pair).unwrap().describe();
+        assert_eq!(
+            describe("1R~O 2G~O"),
+            "all different colors and counts, all striped ovals"
+        );
+        assert_eq!(
+            describe("1R#D 2G~S"),
+            "all different colors, counts, shades and shapes"
+        );
+        assert_eq!(
+            describe("2R#D 2R~S"),
+            "all different shades and shapes, two red shapes on every card"
+        );
+        assert_eq!(
+            describe("1R#D 1G#D"),
+            "all different colors, one solid diamond on every card"
+        );
+        assert_eq!(
+            describe("1P#D 2P~S"),
+            "all different counts, shades and shapes, all purple"
+        );
+        assert_eq!(
+            describe("1R=S 2G=S"),
+            "all different colors and counts, all open squiggles"
+        );
+    }
+
+    #[test]
+    fn every_pair_is_on_one_line() {
+        let lines: Vec<_> = Line::all().collect();
+        assert_eq!(lines.len(), 1080);
+        for a in DECK.iter() {
+            for b in DECK.iter().filter(|b| *b != a) {
+                let through: Vec<_> = lines
+                    .iter()
+                    .filter(|line| line.contains(*a) && line.contains(*b))
+                    .collect();
+                assert_eq!(through, vec![&Line::through(*a, *b).unwrap()]);
+            }
+        }
+    }
+
+    #[test]
+    fn lines_are_consistent_in_every_attribute() {
+        for line in Line::all() {
+            for attribute in Attribute::iter() {
+                assert!(attribute.is_consistent(line.cards()), "{line:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_classes_partition_the_deck() {
+        let mut classes = 0;
+        for line in Line::all() {
+            let class: Vec<_> = line.parallel_class().collect();
+            assert_eq!(class.len(), 27);
+            assert!(class.iter().all(|other| other.is_parallel(&line)));
+            let mut cards: Vec<_> = class.iter().flat_map(Line::cards).collect();
+            cards.sort();
+            assert_eq!(cards, *DECK);
+            if class[0] == line {
+                classes += 1;
+            }
+        }
+        assert_eq!(classes, 40);
+    }
+}
@@ -0,0 +1,128 @@
+//! Boards with exactly one set, built card by card rather than found by dealing at random.
+//!
+//! Every set on a board includes whichever of its cards was placed last, so a board built by
+//! only ever placing cards that make at most one new set, and the first set only once, never
+//! holds two. A single pass over a shuffled deck builds boards of up to 17 cards almost every
+//! time, where dealing and checking rarely finds a board of 15 or more cards with a single set.
+//! Larger boards often run out of cards that fit, and are quicker to retry with another order.
+
+use crate::{card_set::CardSet, core::Card, line::Line};
+use std::collections::HashMap;
+
+/// A board with exactly one set, and that set.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OneSetBoard {
+    cards: Vec<Card>,
+    set: Line,
+}
+
+impl OneSetBoard {
+    /// Build a board of `size` cards from those offered, in order, skipping any card that would
+    /// make a second set, or none if no more offered cards fit first.
+    ///
+    /// Offering the cards of a shuffled deck gives a board whose set is anywhere on it.
+    pub fn construct(offered: impl IntoIterator<Item = Card>, size: usize) -> Option<Self> {
+        let mut cards: Vec<Card> = Vec::with_capacity(size);
+        let mut placed = CardSet::new();
+        // How many pairs on the board each card would complete to a set
+        let mut completes: HashMap<Card, usize> = HashMap::new();
+        let mut set = None;
+        for card in offered {
+            if cards.len() == size {
+                break;
+            }
+            if placed.contains(card) {
+                continue;
+            }
+            let new_sets = completes.get(&card).copied().unwrap_or_default();
+            let last = cards.len() + 1 == size;
+            let allowed = match (set, new_sets) {
+                (_, 0) => set.is_some() || !last,
+                (None, 1) => true,
+                _ => false,
+            };
+            if !allowed {
+                continue;
+            }
+            if new_sets == 1 {
+                set = cards
+                    .iter()
+                    .filter_map(|other| Line::through(*other, card))
+                    .find(|line| {
+                        line.cards()
+                            .iter()
+                            .all(|third| *third == card || placed.contains(*third))
+                    });
+            }
+            for other in &cards {
+                if let Some(line) = Line::through(*other, card) {
+                    for third in line.cards() {
+                        if third != card && third != *other {
+                            *completes.entry(third).or_default() += 1;
+                        }
+                    }
+                }
+            }
+            cards.push(card);
+            placed.insert(card);
+        }
+        let set = set?;
+        (cards.len() == size).then_some(Self { cards, set })
+    }
+
+    /// Check that the cards hold exactly one set, returning it with them if so.
+    pub fn verify(cards: &[Card]) -> Option<Self> {
+        let [set] = &crate::solver::find_sets(cards)[..] else {
+            return None;
+        };
+        let [a, b, _] = set.cards();
+        Some(Self {
+            cards: cards.to_vec(),
+            set: Line::through(a, b)?,
+        })
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// The board's only set.
+    pub fn set(&self) -> Line {
+        self.set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::cards;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn constructed_boards_have_one_set() {
+        // Orders of the deck that step through it by a number coprime to its size
+        for step in (1..81).filter(|step| step % 3 != 0) {
+            let offered = (0..81u32).map(|i| Card::try_from(((i * step + 40) % 81) as u8).unwrap());
+            for size in [3, 12, 15, 17] {
+                let board = OneSetBoard::construct(offered.clone(), size).unwrap();
+                assert_eq!(board.cards().len(), size);
+                assert_eq!(OneSetBoard::verify(board.cards()), Some(board));
+            }
+        }
+    }
+
+    #[test]
+    fn construction_can_run_out_of_cards() {
+        let cards = cards("1R#D 2G~S 3P=O 1R#S");
+        assert_eq!(OneSetBoard::construct(cards.clone(), 5), None);
+        // Three cards that are not a set can never be a board with one
+        assert_eq!(OneSetBoard::construct(cards[1..].to_vec(), 3), None);
+        let board = OneSetBoard::construct(cards.clone(), 4).unwrap();
+        assert_eq!(board.set().cards().to_vec(), {
+            let mut set = cards[..3].to_vec();
+            set.sort();
+            set
+        });
+        assert_eq!(OneSetBoard::verify(&cards[1..]), None);
+    }
+}
@@ -0,0 +1,355 @@
+//! Sets classified by which of their attributes are alike, for difficulty, analytics and drills.
+//!
+//! In a set each attribute is either the same on all three cards or different on all three. Of
+//! the 16 ways to choose, all four alike would be three copies of one card, which leaves 15
+//! patterns. The more attributes differ, the harder a set is to spot, as for
+//! [`Triple::difficulty`](crate::Triple::difficulty).
+
+use crate::{
+    core::{Attribute, Card},
+    line::Line,
+    sgn::{GameRecord, Move},
+    solver::find_sets,
+};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// Which attributes of a set are alike, e.g. `color+shade`, the others being all different.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash)]
+pub struct SetPattern(u8);
+
+/// Every attribute alike, which no set has.
+const ALL_ALIKE: u8 = 0b1111;
+
+fn bit(attribute: Attribute) -> u8 {
+    let position = Attribute::ALL
+        .iter()
+        .position(|other| *other == attribute)
+        .unwrap_or_default();
+    1 << position
+}
+
+impl SetPattern {
+    /// No attribute alike, the hardest sets to spot.
+    pub const ALL_DIFFERENT: Self = Self(0);
+
+    /// The pattern with these attributes alike, or none if that is all four.
+    pub fn new(alike: impl IntoIterator<Item = Attribute>) -> Option<Self> {
+        let bits = alike
+            .into_iter()
+            .fold(0, |bits, attribute| bits | bit(attribute));
+        (bits != ALL_ALIKE).then_some(Self(bits))
+    }
+
+    /// The pattern of the set.
+    pub fn of(set: Line) -> Self {
+        let [a, b, _] = set.cards();
+        Self::of_pair(a, b)
+    }
+
+    /// The pattern of the set the two cards make with their third, which is alike exactly where
+    /// the two are. The same card twice has no pattern, but is given the same one as
+    /// [`SetPattern::ALL_DIFFERENT`] rather than panicking.
+    pub(crate) fn of_pair(a: Card, b: Card) -> Self {
+        let bits = Attribute::iter()
+            .filter(|attribute| attribute.index(a) == attribute.index(b))
+            .fold(0, |bits, attribute| bits | bit(attribute));
+        Self(if bits == ALL_ALIKE { 0 } else { bits })
+    }
+
+    /// Every pattern, each once, from all different to three attributes alike.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..ALL_ALIKE).map(Self)
+    }
+
+    pub fn is_alike(&self, attribute: Attribute) -> bool {
+        self.0 & bit(attribute) != 0
+    }
+
+    /// The attributes alike on all three cards, in the order of [`Attribute::ALL`].
+    pub fn alike(&self) -> impl Iterator<Item = Attribute> + '_ {
+        Attribute::iter().filter(|attribute| self.is_alike(*attribute))
+    }
+
+    /// The attributes different on all three cards, in the order of [`Attribute::ALL`].
+    pub fn different(&self) -> impl Iterator<Item = Attribute> + '_ {
+        Attribute::iter().filter(|attribute| !self.is_alike(*attribute))
+    }
+
+    /// How hard sets of this pattern are to spot, from 1 to 4: the number of attributes that
+    /// differ.
+    pub fn difficulty(&self) -> u8 {
+        4 - self.0.count_ones() as u8
+    }
+}
+
+/// The attributes alike joined with `+`, e.g. `color+shade`, or `all-different`.
+impl fmt::Display for SetPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if *self == Self::ALL_DIFFERENT {
+            return f.write_str("all-different");
+        }
+        let alike: Vec<_> = self.alike().map(|attribute| attribute.name()).collect();
+        f.write_str(&alike.join("+"))
+    }
+}
+
+impl FromStr for SetPattern {
+    type Err = ParsePatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all-different" {
+            return Ok(Self::ALL_DIFFERENT);
+        }
+        let alike = s
+            .split('+')
+            .map(|name| {
+                Attribute::iter()
+                    .find(|attribute| attribute.name() == name)
+                    .ok_or_else(|| ParsePatternError(s.to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(alike).ok_or_else(|| ParsePatternError(s.to_owned()))
+    }
+}
+
+/// The input was not a set pattern.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParsePatternError(String);
+
+impl fmt::Display for ParsePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid set pattern '{}', expected the attributes alike such as 'color+shade', \
+             at most three, or 'all-different'",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParsePatternError {}
+
+/// Serialized the same as `Display`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for SetPattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SetPattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+        pattern.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Which sets a query wants by their pattern, e.g. only sets alike in color, or none that are
+/// all different. The default wants every set.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct PatternFilter {
+    alike: u8,
+    different: u8,
+    excluded: Vec<SetPattern>,
+}
+
+impl PatternFilter {
+    /// Only sets alike in the attribute.
+    pub fn alike(mut self, attribute: Attribute) -> Self {
+        self.alike |= bit(attribute);
+        self
+    }
+
+    /// Only sets different in the attribute.
+    pub fn different(mut self, attribute: Attribute) -> Self {
+        self.different |= bit(attribute);
+        self
+    }
+
+    /// No sets of the pattern.
+    pub fn excluding(mut self, pattern: SetPattern) -> Self {
+        self.excluded.push(pattern);
+        self
+    }
+
+    pub fn matches(&self, pattern: SetPattern) -> bool {
+        pattern.0 & self.alike == self.alike
+            && pattern.0 & self.different == 0
+            && !self.excluded.contains(&pattern)
+    }
+
+    /// Whether the set is one the query wants.
+    pub fn allows(&self, set: Line) -> bool {
+        self.matches(set.pattern())
+    }
+
+    /// Whether the filter wants every set, so that there is no need to apply it.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How many sets there are of each pattern, e.g. on a board or claimed over a game.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct PatternCounts(BTreeMap<SetPattern, usize>);
+
+impl PatternCounts {
+    /// Every set on the board.
+    pub fn on_board(cards: &[Card]) -> Self {
+        find_sets(cards)
+            .iter()
+            .map(|set| SetPattern::of_pair(set.cards()[0], set.cards()[1]))
+            .collect()
+    }
+
+    /// Every set claimed in the game.
+    pub fn claimed(record: &GameRecord) -> Self {
+        record
+            .moves
+            .iter()
+            .filter_map(|played| match played {
+                Move::Set([a, b, _]) => Some(SetPattern::of_pair(*a, *b)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn add(&mut self, pattern: SetPattern) {
+        *self.0.entry(pattern).or_default() += 1;
+    }
+
+    /// Add the counts from elsewhere, e.g. to total them over many boards.
+    pub fn merge(&mut self, other: &Self) {
+        for (pattern, count) in other.iter() {
+            *self.0.entry(pattern).or_default() += count;
+        }
+    }
+
+    pub fn get(&self, pattern: SetPattern) -> usize {
+        self.0.get(&pattern).copied().unwrap_or_default()
+    }
+
+    /// How many sets were counted, of every pattern.
+    pub fn total(&self) -> usize {
+        self.0.values().sum()
+    }
+
+    /// The patterns seen and their counts, in the order of [`SetPattern::all`].
+    pub fn iter(&self) -> impl Iterator<Item = (SetPattern, usize)> + '_ {
+        self.0.iter().map(|(pattern, count)| (*pattern, *count))
+    }
+
+    /// The patterns seen, most common first, ties in the order of [`SetPattern::all`].
+    pub fn most_common(&self) -> Vec<(SetPattern, usize)> {
+        let mut counts: Vec<_> = self.iter().collect();
+        counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        counts
+    }
+}
+
+impl FromIterator<SetPattern> for PatternCounts {
+    fn from_iter<I: IntoIterator<Item = SetPattern>>(patterns: I) -> Self {
+        let mut counts = Self::default();
+        counts.extend(patterns);
+        counts
+    }
+}
+
+impl Extend<SetPattern> for PatternCounts {
+    fn extend<I: IntoIterator<Item = SetPattern>>(&mut self, patterns: I) {
+        for pattern in patterns {
+            self.add(pattern);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sgn::Start, test_util::cards};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn patterns_round_trip() {
+        let patterns: Vec<_> = SetPattern::all().collect();
+        assert_eq!(patterns.len(), 15);
+        for pattern in patterns {
+            assert_eq!(pattern.to_string().parse(), Ok(pattern));
+            assert_eq!(
+                pattern.alike().count() + pattern.different().count(),
+                Attribute::ALL.len()
+            );
+        }
+        let pattern: SetPattern = "shade+color".parse().unwrap();
+        assert_eq!(pattern.to_string(), "color+shade");
+        assert_eq!(pattern.difficulty(), 2);
+        assert!("color+count+shade+shape".parse::<SetPattern>().is_err());
+        assert!("colour".parse::<SetPattern>().is_err());
+        assert_eq!(SetPattern::new([]), Some(SetPattern::ALL_DIFFERENT));
+        assert_eq!(SetPattern::new(Attribute::ALL), None);
+    }
+
+    #[test]
+    fn filters() {
+        let pattern = |notation: &str| notation.parse::<SetPattern>().unwrap();
+        let red = PatternFilter::default().alike(Attribute::Color);
+        assert!(red.matches(pattern("color")));
+        assert!(red.matches(pattern("color+shape")));
+        assert!(!red.matches(SetPattern::ALL_DIFFERENT));
+        let not_all_different = PatternFilter::default().excluding(SetPattern::ALL_DIFFERENT);
+        assert_eq!(
+            SetPattern::all()
+                .filter(|pattern| not_all_different.matches(*pattern))
+                .count(),
+            14
+        );
+        // Alike in color yet different in count and shape, and not only in color
+        let filter = red
+            .different(Attribute::Count)
+            .different(Attribute::Shape)
+            .excluding(pattern("color"));
+        let matching: Vec<_> = SetPattern::all()
+            .filter(|pattern| filter.matches(*pattern))
+            .collect();
+        assert_eq!(matching, [pattern("color+shade")]);
+        assert!(PatternFilter::default().is_empty());
+        // Contradictions want nothing
+        let none = PatternFilter::default()
+            .alike(Attribute::Shade)
+            .different(Attribute::Shade);
+        assert_eq!(SetPattern::all().filter(|p| none.matches(*p)).count(), 0);
+    }
+
+    #[test]
+    fn counts_over_boards_and_games() {
+        let board = cards("1R#D 2G~S 3P=O 1R#S 1R#O 2G=O");
+        let counts = PatternCounts::on_board(&board);
+        let all_different = SetPattern::ALL_DIFFERENT;
+        let red_solid_one =
+            SetPattern::new([Attribute::Color, Attribute::Count, Attribute::Shade]).unwrap();
+        assert_eq!(counts.get(all_different), 1);
+        assert_eq!(counts.get(red_solid_one), 1);
+        assert_eq!(counts.total(), 2);
+        let set = Line::through(board[0], board[1]).unwrap();
+        assert_eq!(SetPattern::of(set), all_different);
+        assert_eq!(SetPattern::of(set).difficulty(), 4);
+
+        let record = GameRecord {
+            start: Start::Seed(1),
+            moves: vec![
+                Move::Set(cards("1R#D 1R#S 1R#O").try_into().unwrap()),
+                Move::DealExtra,
+                Move::Set(cards("2G~S 2G~D 2G~O").try_into().unwrap()),
+                Move::Set(cards("1R#D 2G~S 3P=O").try_into().unwrap()),
+            ],
+        };
+        let claimed = PatternCounts::claimed(&record);
+        assert_eq!(
+            claimed.most_common(),
+            [(red_solid_one, 2), (all_different, 1)]
+        );
+    }
+}
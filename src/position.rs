@@ -0,0 +1,239 @@
+//! A game part way through, as the cards on the board and the cards still to be dealt in order,
+//! for analysing real games from any point and exchanging positions with other tools.
+//!
+//! In text, the first line gives the version of the format, then the board and the deck each
+//! follow their keyword on a line of their own, the deck listing the next card to be dealt first.
+//! Blank lines are ignored, as is anything after a `;`.
+//!
+//! ```text
+//! position 1
+//! board 1R#D 2G~S 3P=O 1R#S 1R#O 2R#D 3R#D 1G#D 1P#D 2G#D 2P#D 3G#D
+//! deck 3P~S 3P~O ; and so on
+//! ```
+//!
+//! Cards only leave the game three at a time, as sets, so a position is only valid if no card is
+//! in it twice and the cards missing from it come to a multiple of three.
+
+use crate::{
+    card_set::CardSet,
+    core::{Card, Deck, DECK_SIZE},
+    game::GameState,
+};
+use std::{fmt, str::FromStr};
+
+/// The version of the format written, and the newest that can be read.
+pub const POSITION_VERSION: u32 = 1;
+
+/// The board and the deck still to be dealt.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Position {
+    board: Vec<Card>,
+    /// The next card to be dealt first.
+    deck: Vec<Card>,
+}
+
+impl Position {
+    /// A position with these cards on the board and these to be dealt, next card first, if it
+    /// could be reached in a game.
+    pub fn new(board: Vec<Card>, deck: Vec<Card>) -> Result<Self, InvalidPosition> {
+        let mut seen = CardSet::new();
+        if let Some(card) = board.iter().chain(&deck).find(|card| !seen.insert(**card)) {
+            return Err(InvalidPosition::Repeated(*card));
+        }
+        let missing = DECK_SIZE as usize - seen.len();
+        if !missing.is_multiple_of(3) {
+            return Err(InvalidPosition::Missing(missing));
+        }
+        Ok(Self { board, deck })
+    }
+
+    /// Where a game has got to.
+    pub fn from_game(game: &GameState) -> Self {
+        Self {
+            board: game.board().cards().to_vec(),
+            deck: game.deck().0.iter().rev().copied().collect(),
+        }
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    /// The cards still to be dealt, next card first.
+    pub fn deck(&self) -> &[Card] {
+        &self.deck
+    }
+
+    /// A game carrying on from the position.
+    pub fn into_game(self) -> GameState {
+        let Self { board, mut deck } = self;
+        // Decks deal from the end
+        deck.reverse();
+        GameState::from_board(board.into(), Deck(deck))
+    }
+}
+
+/// The cards could not be where they are in any game.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InvalidPosition {
+    /// The card is in the position more than once.
+    Repeated(Card),
+    /// This many cards are missing from the position, which is not a whole number of sets.
+    Missing(usize),
+}
+
+impl fmt::Display for InvalidPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Repeated(card) => write!(f, "{card} is in the position more than once"),
+            Self::Missing(missing) => write!(
+                f,
+                "{missing} cards are missing from the position, which cannot have left the game \
+                 as sets of three"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvalidPosition {}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "position {POSITION_VERSION}")?;
+        for (keyword, cards) in [("board", &self.board), ("deck", &self.deck)] {
+            f.write_str(keyword)?;
+            for card in cards {
+                write!(f, " {card}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Position {
+    type Err = ParsePositionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split(';').next().unwrap_or_default().trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let error = |line, message: String| ParsePositionError { line, message };
+        let mut line = |keyword: &str, last: usize| {
+            let (number, line) = lines
+                .next()
+                .ok_or_else(|| error(last + 1, format!("expected '{keyword}'")))?;
+            let mut words = line.split_whitespace();
+            if words.next() != Some(keyword) {
+                return Err(error(number, format!("expected '{keyword}', got '{line}'")));
+            }
+            Ok((number, words))
+        };
+
+        let (number, mut version) = line("position", 0)?;
+        let version = version
+            .next()
+            .and_then(|version| version.parse::<u32>().ok())
+            .filter(|version| *version > 0)
+            .ok_or_else(|| error(number, "invalid version".to_owned()))?;
+        if version > POSITION_VERSION {
+            return Err(error(
+                number,
+                format!(
+                    "unsupported version {version} from a newer release, expected up to \
+                     {POSITION_VERSION}"
+                ),
+            ));
+        }
+        let mut cards = |keyword, last| -> Result<_, ParsePositionError> {
+            let (number, words) = line(keyword, last)?;
+            let cards = words
+                .map(|card| card.parse().map_err(|e| error(number, format!("{e}"))))
+                .collect::<Result<Vec<Card>, _>>()?;
+            Ok((number, cards))
+        };
+        let (number, board) = cards("board", number)?;
+        let (number, deck) = cards("deck", number)?;
+        if let Some((number, line)) = lines.next() {
+            return Err(error(number, format!("unexpected '{line}' after the deck")));
+        }
+        Self::new(board, deck).map_err(|invalid| error(number, invalid.to_string()))
+    }
+}
+
+/// The input was not a valid position.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParsePositionError {
+    /// The line with the mistake, counting from 1. Mistakes in the position as a whole are
+    /// reported on the deck's line.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParsePositionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParsePositionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{Triple, DECK},
+        game::PlayerId,
+        solver::find_first_set,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn positions_round_trip() {
+        let mut game = GameState::new(Deck(DECK.clone()));
+        let [a, b, c] = find_first_set(game.board().cards()).unwrap().cards();
+        game.try_claim(PlayerId::default(), Triple::from((a, b, c)))
+            .unwrap();
+
+        let position = Position::from_game(&game);
+        assert_eq!(position.board().len(), 12);
+        assert_eq!(position.deck().len(), 66);
+        let text = position.to_string();
+        assert!(text.starts_with("position 1\nboard "));
+        assert_eq!(text.parse::<Position>(), Ok(position.clone()));
+
+        let resumed = position.into_game();
+        assert_eq!(resumed.board(), game.board());
+        assert_eq!(resumed.deck().0, game.deck().0);
+    }
+
+    #[test]
+    fn invalid_positions_are_refused() {
+        let error = |text: &str| text.parse::<Position>().unwrap_err();
+        assert_eq!(
+            Position::new(vec![DECK[0], DECK[1]], vec![DECK[2], DECK[0]]),
+            Err(InvalidPosition::Repeated(DECK[0]))
+        );
+        let board: Vec<_> = DECK.iter().skip(3).map(Card::to_string).collect();
+        let text = format!("position 1\nboard {}\n\ndeck", board.join(" "));
+        assert!(text.parse::<Position>().is_ok());
+        // One card short of a whole number of sets
+        let text = format!("position 1\nboard {}\ndeck ; empty", board[1..].join(" "));
+        assert_eq!(
+            error(&text),
+            ParsePositionError {
+                line: 3,
+                message: InvalidPosition::Missing(4).to_string()
+            }
+        );
+        assert_eq!(error("position 2\nboard\ndeck").line, 1);
+        assert_eq!(error("board 1R#D\ndeck").line, 1);
+        assert_eq!(error("position 1\nboard 9R#D\ndeck").line, 2);
+        assert_eq!(error("position 1\nboard\ndeck\n+3").line, 4);
+        assert!(error("position 1\nboard")
+            .message
+            .contains("expected 'deck'"));
+    }
+}
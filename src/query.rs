@@ -0,0 +1,351 @@
+//! A small query language over cards, for ad hoc analysis without writing Rust.
+//!
+//! A query compares attributes with values, e.g. `color=red AND count!=2`, and combines the
+//! comparisons with `AND`, `OR`, `NOT` and parentheses. `NOT` binds tightest and `OR` loosest, and
+//! keywords may be in any case. Values are the lowercase names of
+//! [`Color`](crate::Color), [`Count`](crate::Count), [`Shade`](crate::Shade) and
+//! [`Shape`](crate::Shape) values, and counts may also be written as digits.
+
+use crate::core::{Attribute, Card, Color, Count, Shade, Shape};
+use std::{fmt, str::FromStr};
+
+/// A parsed query, matching the cards it describes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CardQuery(Expr);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Expr {
+    Compare {
+        attribute: Attribute,
+        /// The value's position among the attribute's values, e.g. in [`Color::ALL`].
+        value: usize,
+        equal: bool,
+    },
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl CardQuery {
+    pub fn matches(&self, card: Card) -> bool {
+        self.0.matches(card)
+    }
+
+    /// The cards that match, in their order.
+    pub fn filter(&self, cards: &[Card]) -> Vec<Card> {
+        cards
+            .iter()
+            .copied()
+            .filter(|card| self.matches(*card))
+            .collect()
+    }
+}
+
+impl Expr {
+    fn matches(&self, card: Card) -> bool {
+        match self {
+            Self::Compare {
+                attribute,
+                value,
+                equal,
+            } => (attribute.index(card) == *value) == *equal,
+            Self::Not(inner) => !inner.matches(card),
+            Self::And(a, b) => a.matches(card) && b.matches(card),
+            Self::Or(a, b) => a.matches(card) || b.matches(card),
+        }
+    }
+
+    /// How tightly the expression binds, to know when it needs parentheses.
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Or(..) => 0,
+            Self::And(..) => 1,
+            Self::Not(_) | Self::Compare { .. } => 2,
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, min_precedence: u8) -> fmt::Result {
+        let parenthesize = self.precedence() < min_precedence;
+        if parenthesize {
+            f.write_str("(")?;
+        }
+        match self {
+            Self::Compare {
+                attribute,
+                value,
+                equal,
+            } => {
+                let operator = if *equal { "=" } else { "!=" };
+                let value = value_names(*attribute)[*value];
+                write!(f, "{}{operator}{value}", attribute.name())?;
+            }
+            Self::Not(inner) => {
+                f.write_str("NOT ")?;
+                inner.write(f, 2)?;
+            }
+            Self::And(a, b) => {
+                a.write(f, 1)?;
+                f.write_str(" AND ")?;
+                b.write(f, 2)?;
+            }
+            Self::Or(a, b) => {
+                a.write(f, 0)?;
+                f.write_str(" OR ")?;
+                b.write(f, 1)?;
+            }
+        }
+        if parenthesize {
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+/// The names of the attribute's values, in order.
+fn value_names(attribute: Attribute) -> [&'static str; 3] {
+    match attribute {
+        Attribute::Color => Color::ALL.map(|value| value.name()),
+        Attribute::Count => Count::ALL.map(|value| value.name()),
+        Attribute::Shade => Shade::ALL.map(|value| value.name()),
+        Attribute::Shape => Shape::ALL.map(|value| value.name()),
+    }
+}
+
+/// The query in a normal form that parses back to the same query, with only the parentheses it
+/// needs, e.g. `color=red AND count!=two`.
+impl fmt::Display for CardQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.write(f, 0)
+    }
+}
+
+/// The input was not a query.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseQueryError(String);
+
+impl fmt::Display for ParseQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid query: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseQueryError {}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token<'a> {
+    Word(&'a str),
+    Equal,
+    NotEqual,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "'{word}'"),
+            Self::Equal => f.write_str("'='"),
+            Self::NotEqual => f.write_str("'!='"),
+            Self::Open => f.write_str("'('"),
+            Self::Close => f.write_str("')'"),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token<'_>>, ParseQueryError> {
+    let mut tokens = Vec::new();
+    let mut rest = s.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let (token, length) = match c {
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            '=' => (Token::Equal, 1),
+            '!' if rest.starts_with("!=") => (Token::NotEqual, 2),
+            c if c.is_ascii_alphanumeric() => {
+                let length = rest
+                    .find(|c: char| !c.is_ascii_alphanumeric())
+                    .unwrap_or(rest.len());
+                (Token::Word(&rest[..length]), length)
+            }
+            c => return Err(ParseQueryError(format!("unexpected '{c}'"))),
+        };
+        tokens.push(token);
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser, one method per level of precedence.
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found =
+            matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseQueryError> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseQueryError> {
+        let mut expr = self.not()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, ParseQueryError> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseQueryError> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err(ParseQueryError("expected ')'".to_owned())),
+                }
+            }
+            Some(Token::Word(name)) => {
+                let attribute = Attribute::iter()
+                    .find(|attribute| attribute.name() == name)
+                    .ok_or_else(|| {
+                        ParseQueryError(format!(
+                            "unknown attribute '{name}', expected 'color', 'count', 'shade' or \
+                             'shape'"
+                        ))
+                    })?;
+                let equal = match self.next() {
+                    Some(Token::Equal) => true,
+                    Some(Token::NotEqual) => false,
+                    _ => {
+                        return Err(ParseQueryError(format!(
+                            "expected '=' or '!=' after '{name}'"
+                        )))
+                    }
+                };
+                let Some(Token::Word(value)) = self.next() else {
+                    return Err(ParseQueryError(format!("expected a value for '{name}'")));
+                };
+                let names = value_names(attribute);
+                let value = names
+                    .iter()
+                    .position(|name| *name == value)
+                    .or_else(|| match (attribute, value) {
+                        (Attribute::Count, "1" | "2" | "3") => {
+                            value.parse::<usize>().ok().map(|n| n - 1)
+                        }
+                        _ => None,
+                    })
+                    .ok_or_else(|| {
+                        ParseQueryError(format!(
+                            "unknown {name} '{value}', expected one of {}",
+                            names.join(", ")
+                        ))
+                    })?;
+                Ok(Expr::Compare {
+                    attribute,
+                    value,
+                    equal,
+                })
+            }
+            _ => Err(ParseQueryError(
+                "expected a comparison such as 'color=red', or '('".to_owned(),
+            )),
+        }
+    }
+}
+
+impl FromStr for CardQuery {
+    type Err = ParseQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            position: 0,
+        };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(Self(expr)),
+            Some(token) => Err(ParseQueryError(format!("unexpected {token} at the end"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::cards;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn queries_filter_cards() {
+        let board = cards("1R#D 2R~S 3R=O 1G#D 2P=S");
+        let query = |query: &str| query.parse::<CardQuery>().unwrap();
+        assert_eq!(
+            query("color=red AND count!=2").filter(&board),
+            cards("1R#D 3R=O")
+        );
+        // AND binds tighter than OR, and NOT tighter than both
+        assert_eq!(
+            query("shape=squiggle or NOT shade!=solid and color=green").filter(&board),
+            cards("2R~S 1G#D 2P=S")
+        );
+        assert_eq!(
+            query("(shape=squiggle OR shade=solid) AND color=green").filter(&board),
+            cards("1G#D")
+        );
+        assert_eq!(query("count=three").filter(&board), cards("3R=O"));
+    }
+
+    #[test]
+    fn queries_round_trip() {
+        for text in [
+            "color=red AND count!=two",
+            "(color=red OR color=green) AND NOT shape=oval",
+            "NOT (shade=open OR count=one) OR shape=diamond AND color!=purple",
+        ] {
+            let query: CardQuery = text.parse().unwrap();
+            assert_eq!(query.to_string(), text);
+        }
+        for (text, error) in [
+            ("colour=red", "unknown attribute 'colour'"),
+            ("color=blue", "unknown color 'blue'"),
+            ("color red", "expected '=' or '!='"),
+            ("(color=red", "expected ')'"),
+            ("color=red shape=oval", "unexpected 'shape' at the end"),
+            ("", "expected a comparison"),
+            ("color=red;", "unexpected ';'"),
+        ] {
+            let message = text.parse::<CardQuery>().unwrap_err().to_string();
+            assert!(message.contains(error), "{text}: {message}");
+        }
+    }
+}
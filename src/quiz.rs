@@ -0,0 +1,143 @@
+//! A quiz on counting the sets on a board at a glance, for practice at judging when to deal more.
+//!
+//! Each board is answered with a guess at how many sets it holds. Guesses earn points for being
+//! close, and a bonus for being exactly right quickly.
+
+use crate::{core::Card, solver::find_sets};
+use std::time::Duration;
+
+/// How answers are scored.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CountRules {
+    /// Points for an exact count, less one for each set the guess is out by.
+    pub exact: u32,
+    /// How soon an exact count must be given for the bonus.
+    pub quick: Duration,
+    pub quick_bonus: u32,
+}
+
+impl Default for CountRules {
+    /// Three points for an exact count, and one more for giving it within five seconds.
+    fn default() -> Self {
+        Self {
+            exact: 3,
+            quick: Duration::from_secs(5),
+            quick_bonus: 1,
+        }
+    }
+}
+
+/// One board answered.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CountAnswer {
+    /// How many sets were on the board.
+    pub sets: usize,
+    pub guess: usize,
+    /// How long the answer took, from when the board was shown.
+    pub took: Duration,
+    pub points: u32,
+}
+
+/// A round of boards to count the sets on, one after another.
+#[derive(Debug, Clone)]
+pub struct CountQuiz {
+    boards: Vec<Vec<Card>>,
+    rules: CountRules,
+    answers: Vec<CountAnswer>,
+}
+
+impl CountQuiz {
+    pub fn new(boards: Vec<Vec<Card>>, rules: CountRules) -> Self {
+        Self {
+            boards,
+            rules,
+            answers: Vec::new(),
+        }
+    }
+
+    /// The board to count now, or none once every board has been answered.
+    pub fn board(&self) -> Option<&[Card]> {
+        self.boards.get(self.answers.len()).map(Vec::as_slice)
+    }
+
+    /// How many boards there are in all.
+    pub fn boards(&self) -> usize {
+        self.boards.len()
+    }
+
+    pub fn answers(&self) -> &[CountAnswer] {
+        &self.answers
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.answers.len() == self.boards.len()
+    }
+
+    /// Answer the current board, moving on to the next, or none if the quiz is over.
+    pub fn answer(&mut self, guess: usize, took: Duration) -> Option<CountAnswer> {
+        let sets = find_sets(self.board()?).len();
+        let off = sets.abs_diff(guess);
+        let mut points = self
+            .rules
+            .exact
+            .saturating_sub(u32::try_from(off).unwrap_or(u32::MAX));
+        if off == 0 && took <= self.rules.quick {
+            points += self.rules.quick_bonus;
+        }
+        let answer = CountAnswer {
+            sets,
+            guess,
+            took,
+            points,
+        };
+        self.answers.push(answer);
+        Some(answer)
+    }
+
+    /// Points earned so far.
+    pub fn points(&self) -> u32 {
+        self.answers.iter().map(|answer| answer.points).sum()
+    }
+
+    /// The points for counting every board exactly and quickly.
+    pub fn max_points(&self) -> u32 {
+        (self.rules.exact + self.rules.quick_bonus) * self.boards.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::cards;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn answers_score_closeness_and_speed() {
+        let two_sets = cards("1R#D 2G~S 3P=O 1R#S 1R#O 2G=O");
+        let mut quiz = CountQuiz::new(
+            vec![
+                two_sets.clone(),
+                two_sets.clone(),
+                two_sets,
+                cards("1R#D 2R~S 3P=O"),
+            ],
+            CountRules::default(),
+        );
+        let secs = Duration::from_secs;
+        let points: Vec<_> = [(2, secs(3)), (2, secs(9)), (5, secs(1)), (0, secs(1))]
+            .into_iter()
+            .map(|(guess, took)| quiz.answer(guess, took).unwrap().points)
+            .collect();
+        // Exact and quick, exact, out by three, and exact for a board without a set
+        assert_eq!(points, [4, 3, 0, 4]);
+        assert_eq!(quiz.answers()[2].sets, 2);
+        assert!(quiz.is_over());
+        assert_eq!(quiz.board(), None);
+        assert_eq!(quiz.answer(1, secs(1)), None);
+        assert_eq!((quiz.points(), quiz.max_points()), (11, 16));
+    }
+}
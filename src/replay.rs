@@ -0,0 +1,172 @@
+//! Stepping through a recorded game, for replay viewers that jump back and forth.
+//!
+//! A record keeps only the moves, which is all a save or a network message needs, but rebuilding
+//! the game at a late move means playing every move before it. A [`Replay`] plays the record once
+//! and keeps a copy of the game every so many moves, so seeking anywhere plays at most that many
+//! moves from the nearest copy.
+
+use crate::{
+    core::{Deck, Triple},
+    game::{ClaimError, GameState, PlayerId},
+    sgn::Move,
+};
+use std::fmt;
+
+/// A recorded game with checkpoints to seek from.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    moves: Vec<Move>,
+    /// Moves between checkpoints.
+    every: usize,
+    /// The game after each multiple of `every` moves, starting with the opening board.
+    checkpoints: Vec<GameState>,
+}
+
+impl Replay {
+    /// Play the moves on a game dealt from the deck, keeping a checkpoint every `every` moves.
+    ///
+    /// Hints do not change the board, so they are steps in the replay that leave it as it was.
+    pub fn new(deck: Deck, moves: Vec<Move>, every: usize) -> Result<Self, ReplayError> {
+        let every = every.max(1);
+        let mut game = GameState::new(deck);
+        let mut checkpoints = vec![game.clone()];
+        for (index, played) in moves.iter().enumerate() {
+            play(&mut game, played).map_err(|error| ReplayError { index, error })?;
+            if (index + 1) % every == 0 {
+                checkpoints.push(game.clone());
+            }
+        }
+        Ok(Self {
+            moves,
+            every,
+            checkpoints,
+        })
+    }
+
+    /// Replay a game in Set Game Notation, dealing its deck the way the record says.
+    #[cfg(feature = "rand")]
+    pub fn from_record(record: &crate::GameRecord, every: usize) -> Result<Self, ReplayError> {
+        let deck = match &record.start {
+            crate::Start::Seed(seed) => Deck::new_from_seed(*seed),
+            // Records list the first card first, decks deal from the end
+            crate::Start::Deck(cards) => Deck(cards.iter().rev().copied().collect()),
+        };
+        Self::new(deck, record.moves.clone(), every)
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// How many moves there are to step through.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// The game after the first `moves` moves, or after every move if there are fewer.
+    pub fn seek(&self, moves: usize) -> GameState {
+        let moves = moves.min(self.moves.len());
+        let checkpoint = moves / self.every;
+        let mut game = self.checkpoints[checkpoint].clone();
+        for played in &self.moves[checkpoint * self.every..moves] {
+            // Every move was played once already, when the checkpoints were made
+            let _ = play(&mut game, played);
+        }
+        game
+    }
+
+    /// The game as it was left.
+    pub fn last(&self) -> GameState {
+        self.seek(self.moves.len())
+    }
+}
+
+fn play(game: &mut GameState, played: &Move) -> Result<(), ClaimError> {
+    match played {
+        Move::Set([a, b, c]) => {
+            game.try_claim(PlayerId::default(), Triple::from((*a, *b, *c)))?;
+        }
+        // Refused, and charged for by the scoring
+        Move::Misclaim([a, b, c]) => {
+            match game.try_claim(PlayerId::default(), Triple::from((*a, *b, *c))) {
+                Err(ClaimError::NotASet { .. }) => {}
+                // Records read from notation never hold one, as sets claimed are sets found
+                Ok(_) => return Err(ClaimError::Disallowed),
+                Err(error) => return Err(error),
+            }
+        }
+        Move::DealExtra => {
+            game.deal_extra();
+        }
+        Move::Hint(_) => {}
+    }
+    Ok(())
+}
+
+/// A move in the record could not be played.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ReplayError {
+    /// The move's position in the record, counting from 0.
+    pub index: usize,
+    pub error: ClaimError,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {}: {}", self.index + 1, self.error)
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::DECK, solver::find_first_set};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn seeking_matches_playing() {
+        // Take the first set on the board each turn, dealing more when there is none
+        let mut game = GameState::new(Deck(DECK.clone()));
+        let mut moves = Vec::new();
+        let mut boards = vec![game.board().cards().to_vec()];
+        while !game.is_over() {
+            let played = match find_first_set(game.board().cards()) {
+                Some(set) => Move::Set(set.cards()),
+                None => Move::DealExtra,
+            };
+            play(&mut game, &played).unwrap();
+            moves.push(played);
+            boards.push(game.board().cards().to_vec());
+        }
+        assert!(moves.len() > 20);
+
+        let replay = Replay::new(Deck(DECK.clone()), moves.clone(), 4).unwrap();
+        assert_eq!(replay.len(), moves.len());
+        for (index, board) in boards.iter().enumerate().rev() {
+            assert_eq!(
+                replay.seek(index).board().cards(),
+                &board[..],
+                "move {index}"
+            );
+        }
+        assert!(replay.last().is_over());
+        assert_eq!(replay.seek(usize::MAX).board(), replay.last().board());
+    }
+
+    #[test]
+    fn unplayable_moves_are_reported() {
+        let mut moves = vec![Move::Hint(DECK[80]), Move::DealExtra];
+        // Still in the deck
+        moves.push(Move::Set([DECK[0], DECK[1], DECK[2]]));
+        let error = Replay::new(Deck(DECK.clone()), moves, 1).unwrap_err();
+        assert_eq!(error.index, 2);
+        assert_eq!(error.error, ClaimError::NotOnBoard(DECK[0]));
+        assert!(error.to_string().starts_with("move 3: "));
+    }
+}
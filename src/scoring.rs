@@ -0,0 +1,162 @@
+//! How a game turns what each player did into points, as different communities score
+//! differently.
+//!
+//! The game keeps a [`Tally`] for each player as they play, and its [`ScoringPolicy`] works out
+//! their score from it. Bonuses for speed are only earned by claims made with a time, such as
+//! through [`GameState::try_claim_at`](crate::GameState::try_claim_at).
+
+use std::time::Duration;
+
+/// The points for sets, bonuses and misclaims.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScoringPolicy {
+    /// Points for each set found.
+    pub per_set: u32,
+    /// Points taken for each claim that was not a set.
+    pub misclaim: u32,
+    pub time_bonus: Option<TimeBonus>,
+    pub streak_bonus: Option<StreakBonus>,
+}
+
+impl Default for ScoringPolicy {
+    /// A point for each set, as in the card game.
+    fn default() -> Self {
+        Self {
+            per_set: 1,
+            misclaim: 0,
+            time_bonus: None,
+            streak_bonus: None,
+        }
+    }
+}
+
+/// Extra points for a set found soon after the player's previous one, or after the start of
+/// the clock for their first.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TimeBonus {
+    pub within: Duration,
+    pub points: u32,
+}
+
+/// Extra points for finding sets in a row, without a misclaim in between.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StreakBonus {
+    /// Sets in a row found before the bonus starts, e.g. 3 to pay from the fourth set on.
+    pub after: u32,
+    /// Extra points for each set once the streak is long enough.
+    pub points: u32,
+}
+
+/// Everything that counts towards a player's score, e.g. for a summary at the end of a game.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Tally {
+    pub sets: u32,
+    /// Claims that were not sets.
+    pub misclaims: u32,
+    /// Sets found since the last misclaim.
+    pub streak: u32,
+    /// Extra points earned for speed and streaks.
+    pub bonus: u32,
+    /// Points lost other than to misclaims, e.g. to [`Hints`](crate::Hints).
+    pub penalty: u32,
+    /// When the player last found a set, if it was claimed with a time.
+    pub last_set_at: Option<Duration>,
+}
+
+impl ScoringPolicy {
+    /// The player's score: points for sets and bonuses, less penalties, but never below zero.
+    pub fn score(&self, tally: &Tally) -> u32 {
+        let earned = tally
+            .sets
+            .saturating_mul(self.per_set)
+            .saturating_add(tally.bonus);
+        let lost = tally
+            .misclaims
+            .saturating_mul(self.misclaim)
+            .saturating_add(tally.penalty);
+        earned.saturating_sub(lost)
+    }
+
+    /// Count a set found, at `at` if the claim was made with a time.
+    pub(crate) fn found(&self, tally: &mut Tally, at: Option<Duration>) {
+        tally.sets += 1;
+        tally.streak += 1;
+        if let (Some(bonus), Some(at)) = (self.time_bonus, at) {
+            let since = at.saturating_sub(tally.last_set_at.unwrap_or_default());
+            if since <= bonus.within {
+                tally.bonus += bonus.points;
+            }
+        }
+        if let Some(bonus) = self.streak_bonus {
+            if tally.streak > bonus.after {
+                tally.bonus += bonus.points;
+            }
+        }
+        if at.is_some() {
+            tally.last_set_at = at;
+        }
+    }
+
+    /// Count a claim that was not a set, which breaks the streak.
+    pub(crate) fn misclaimed(&self, tally: &mut Tally) {
+        tally.misclaims += 1;
+        tally.streak = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bonuses_and_penalties() {
+        let policy = ScoringPolicy {
+            per_set: 2,
+            misclaim: 1,
+            time_bonus: Some(TimeBonus {
+                within: Duration::from_secs(10),
+                points: 3,
+            }),
+            streak_bonus: Some(StreakBonus {
+                after: 2,
+                points: 1,
+            }),
+        };
+        let mut tally = Tally::default();
+        let secs = |secs| Some(Duration::from_secs(secs));
+        // Quick, slow, unknown, then quick again and long enough for the streak bonus
+        for at in [secs(8), secs(30), None, secs(35)] {
+            policy.found(&mut tally, at);
+        }
+        assert_eq!(tally.bonus, 3 + 1 + 3 + 1);
+        assert_eq!(policy.score(&tally), 4 * 2 + 8);
+        policy.misclaimed(&mut tally);
+        policy.found(&mut tally, None);
+        assert_eq!(
+            tally,
+            Tally {
+                sets: 5,
+                misclaims: 1,
+                streak: 1,
+                bonus: 8,
+                penalty: 0,
+                last_set_at: secs(35),
+            }
+        );
+        assert_eq!(policy.score(&tally), 5 * 2 + 8 - 1);
+
+        // Scores never go below zero
+        tally.penalty = 100;
+        assert_eq!(policy.score(&tally), 0);
+        assert_eq!(ScoringPolicy::default().score(&Tally::default()), 0);
+    }
+}
@@ -0,0 +1,105 @@
+//! Conversion to and from the card codes of [Set with Friends](https://setwithfriends.com), the
+//! open source online Set game.
+//!
+//! Set with Friends writes a card as four digits from 0 to 2, for its color, shape, shading and
+//! number, indexing into its own orders of values (see `SetCard.js` in that project):
+//!
+//! | Digit | 0 | 1 | 2 |
+//! | ----- | - | - | - |
+//! | color | red | green | purple |
+//! | shape | squiggle | oval | diamond |
+//! | shading | solid | open | striped |
+//! | number | one | two | three |
+//!
+//! For example, two red striped ovals are `2R~O` here and `0121` there.
+
+use crate::core::{Card, CardProperties, Color, Count, Shade, Shape};
+use std::fmt;
+
+const SHAPES: [Shape; 3] = [Shape::Squiggle, Shape::Oval, Shape::Diamond];
+const SHADES: [Shade; 3] = [Shade::Solid, Shade::Open, Shade::Striped];
+
+/// The Set with Friends code for a card.
+pub fn encode(card: Card) -> String {
+    fn index<T: PartialEq>(values: &[T; 3], value: T) -> usize {
+        values
+            .iter()
+            .position(|v| *v == value)
+            .expect("every value is listed")
+    }
+
+    let digits = [
+        index(&Color::ALL, card.color()),
+        index(&SHAPES, card.shape()),
+        index(&SHADES, card.shade()),
+        index(&Count::ALL, card.count()),
+    ];
+    digits
+        .iter()
+        .map(|digit| (b'0' + *digit as u8) as char)
+        .collect()
+}
+
+/// The card for a Set with Friends code.
+pub fn decode(code: &str) -> Result<Card, DecodeError> {
+    let error = || DecodeError(code.to_owned());
+    let digits: [usize; 4] = code
+        .chars()
+        .map(|digit| match digit {
+            '0'..='2' => Ok(digit as usize - '0' as usize),
+            _ => Err(error()),
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .try_into()
+        .map_err(|_| error())?;
+    let [color, shape, shade, count] = digits;
+    Ok(Card::from(CardProperties {
+        color: Color::ALL[color],
+        count: Count::ALL[count],
+        shade: SHADES[shade],
+        shape: SHAPES[shape],
+    }))
+}
+
+/// The input was not a Set with Friends card code.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DecodeError(String);
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid Set with Friends card '{}', expected four digits from 0 to 2 such as '0121'",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DECK;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn setwithfriends_codes() {
+        let card: Card = "2R~O".parse().unwrap();
+        assert_eq!(encode(card), "0121");
+        assert_eq!(decode("0121"), Ok(card));
+        assert_eq!(decode("2200"), Ok("1P#D".parse().unwrap()));
+
+        let mut codes: Vec<_> = DECK.iter().map(|card| encode(*card)).collect();
+        for (card, code) in DECK.iter().zip(&codes) {
+            assert_eq!(decode(code), Ok(*card));
+        }
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), DECK.len());
+
+        for invalid in ["", "012", "01210", "0123", "a121", "０121"] {
+            assert_eq!(decode(invalid), Err(DecodeError(invalid.to_owned())));
+        }
+    }
+}
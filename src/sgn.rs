@@ -0,0 +1,365 @@
+//! Set Game Notation (SGN), a plain text record of a whole game that is easy to read and write
+//! by hand.
+//!
+//! The first line gives the version of the notation, so that records from older versions can
+//! still be read as it changes. The next says how the deck was shuffled, either by the seed given
+//! to [`Deck::new_from_seed`](crate::Deck::new_from_seed), followed by the version of the shuffle
+//! that dealt it, or as every card in the order dealt. Each following line is a move: a claimed
+//! set, three cards claimed that were not a set, a card shown as a hint, or `+3` for three extra
+//! cards. Blank lines are ignored, as is anything after a `;`, so records can be annotated.
+//!
+//! ```text
+//! sgn 3
+//! seed 42
+//! shuffle 1
+//! S: 3G=D 2P~S 1R#O ; spotted the counts first
+//! +3
+//! X: 3P~O 1R~S 2G#S
+//! H: 1R~S
+//! S: 3P~O 1R~S 2G~D
+//! ```
+
+use crate::core::{Card, Triple, SHUFFLE_VERSION};
+use std::{fmt, str::FromStr};
+
+/// The version of the notation written, and the newest that can be read.
+///
+/// Records from before the version line are read as version 1, which reads the same. Version 2
+/// gives the version of the shuffle after the seed, which older records were all dealt with the
+/// first version of. Version 3 adds claims that were not sets, which cost points.
+pub const SGN_VERSION: u32 = 3;
+
+/// A game in Set Game Notation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GameRecord {
+    pub start: Start,
+    pub moves: Vec<Move>,
+}
+
+/// How the deck was shuffled.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Start {
+    Seed(u64),
+    /// Every card in the order dealt, first card first.
+    Deck(Vec<Card>),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Move {
+    /// A claimed set, written `S: 2R~D 1G#O 3P=S`.
+    Set([Card; 3]),
+    /// Three cards claimed that were not a set, written `X: 2R~D 1G#O 3P#S`.
+    Misclaim([Card; 3]),
+    /// Three extra cards dealt, written `+3`.
+    DealExtra,
+    /// A card in a set shown as a hint, written `H: 1R#D`.
+    Hint(Card),
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "sgn {SGN_VERSION}")?;
+        match &self.start {
+            Start::Seed(seed) => writeln!(f, "seed {seed}\nshuffle {SHUFFLE_VERSION}")?,
+            Start::Deck(cards) => {
+                let cards: Vec<_> = cards.iter().map(Card::to_string).collect();
+                writeln!(f, "deck {}", cards.join(" "))?
+            }
+        }
+        for line in &self.moves {
+            match line {
+                Move::Set([a, b, c]) => writeln!(f, "S: {a} {b} {c}")?,
+                Move::Misclaim([a, b, c]) => writeln!(f, "X: {a} {b} {c}")?,
+                Move::DealExtra => writeln!(f, "+3")?,
+                Move::Hint(card) => writeln!(f, "H: {card}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = ParseGameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split(';').next().unwrap_or_default().trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .peekable();
+        let error = |line, message: String| ParseGameError { line, message };
+        let cards = |line, cards: &str| -> Result<Vec<Card>, ParseGameError> {
+            cards
+                .split_whitespace()
+                .map(|card| card.parse().map_err(|e| error(line, format!("{e}"))))
+                .collect()
+        };
+        let three = |line, claimed: &str| -> Result<[Card; 3], ParseGameError> {
+            cards(line, claimed)?.try_into().map_err(|claimed: Vec<_>| {
+                error(
+                    line,
+                    format!("expected 3 cards in a claim, got {}", claimed.len()),
+                )
+            })
+        };
+
+        // Records from before the version line read as version 1
+        let mut version = 1;
+        if let Some((number, found)) = lines
+            .peek()
+            .and_then(|(number, line)| Some((*number, line.strip_prefix("sgn ")?)))
+        {
+            version = found
+                .trim()
+                .parse()
+                .ok()
+                .filter(|version| *version > 0)
+                .ok_or_else(|| error(number, format!("invalid version '{}'", found.trim())))?;
+            if version > SGN_VERSION {
+                return Err(error(
+                    number,
+                    format!(
+                        "unsupported version {version} from a newer release, expected up to \
+                         {SGN_VERSION}"
+                    ),
+                ));
+            }
+            lines.next();
+        }
+        let (number, first) = lines
+            .next()
+            .ok_or_else(|| error(1, "expected 'seed' or 'deck'".to_owned()))?;
+        let start = match first.split_once(' ') {
+            Some(("seed", seed)) => Start::Seed(
+                seed.trim()
+                    .parse()
+                    .map_err(|_| error(number, format!("invalid seed '{}'", seed.trim())))?,
+            ),
+            Some(("deck", deck)) => Start::Deck(cards(number, deck)?),
+            _ => {
+                return Err(error(
+                    number,
+                    format!("expected 'seed' or 'deck', got '{first}'"),
+                ))
+            }
+        };
+        if let Start::Seed(_) = start {
+            // Versions before 2 were all dealt with the first shuffle
+            let (number, shuffle) = match version {
+                1 => (number, 1),
+                _ => {
+                    let (number, line) = lines.next().ok_or_else(|| {
+                        error(
+                            number + 1,
+                            "expected 'shuffle <n>' after the seed".to_owned(),
+                        )
+                    })?;
+                    let shuffle = line
+                        .strip_prefix("shuffle ")
+                        .and_then(|shuffle| shuffle.trim().parse().ok())
+                        .ok_or_else(|| {
+                            error(number, format!("expected 'shuffle <n>', got '{line}'"))
+                        })?;
+                    (number, shuffle)
+                }
+            };
+            if shuffle != SHUFFLE_VERSION {
+                return Err(error(
+                    number,
+                    format!(
+                        "dealt with shuffle version {shuffle}, but this release shuffles with \
+                         version {SHUFFLE_VERSION}, so the seed deals other cards"
+                    ),
+                ));
+            }
+        }
+
+        let moves = lines
+            .map(|(number, line)| match line {
+                "+3" => Ok(Move::DealExtra),
+                _ if line.starts_with("H:") => match cards(number, &line[2..])?[..] {
+                    [card] => Ok(Move::Hint(card)),
+                    ref hint => Err(error(
+                        number,
+                        format!("expected 1 card in a hint, got {}", hint.len()),
+                    )),
+                },
+                _ if line.starts_with("S:") => Ok(Move::Set(three(number, &line[2..])?)),
+                _ if line.starts_with("X:") => {
+                    let claimed @ [a, b, c] = three(number, &line[2..])?;
+                    // A set claimed is a set found, whatever the record says
+                    if a != b && Triple::from((a, b, c)).is_set() {
+                        return Err(error(
+                            number,
+                            format!("{a} {b} {c} is a set, so it was not a misclaim"),
+                        ));
+                    }
+                    Ok(Move::Misclaim(claimed))
+                }
+                _ => Err(error(
+                    number,
+                    format!(
+                        "expected a move such as 'S: 2R~D 1G#O 3P=S', 'X: 2R~D 1G#O 3P#S', \
+                         'H: 1R#D' or '+3', got '{line}'"
+                    ),
+                )),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { start, moves })
+    }
+}
+
+/// The input was not a game in Set Game Notation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseGameError {
+    /// The line with the mistake, counting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseGameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseGameError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::DECK, test_util::triple};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_game_record() {
+        let record: GameRecord = "
+            seed 42
+            S: 2R~D 1G#O 3P=S ; spotted the counts first
+
+            +3
+            ; a comment on its own
+            X: 1R#D 1R~O 1R~O
+            S:1R#D 1R~O   1R=S
+        "
+        .parse()
+        .unwrap();
+        assert_eq!(
+            record,
+            GameRecord {
+                start: Start::Seed(42),
+                moves: vec![
+                    Move::Set(triple("2R~D 1G#O 3P=S").cards()),
+                    Move::DealExtra,
+                    Move::Misclaim(triple("1R#D 1R~O 1R~O").cards()),
+                    Move::Set(triple("1R#D 1R~O 1R=S").cards()),
+                ],
+            }
+        );
+        assert_eq!(
+            record.to_string(),
+            "sgn 3\nseed 42\nshuffle 1\nS: 2R~D 1G#O 3P=S\n+3\nX: 1R#D 1R~O 1R~O\n\
+             S: 1R#D 1R~O 1R=S\n"
+        );
+    }
+
+    #[test]
+    fn older_versions_are_read() {
+        let unversioned: GameRecord = include_str!("../fixtures/sgn/unversioned.sgn")
+            .parse()
+            .unwrap();
+        let v1: GameRecord = include_str!("../fixtures/sgn/v1.sgn").parse().unwrap();
+        let v2: GameRecord = include_str!("../fixtures/sgn/v2.sgn").parse().unwrap();
+        assert_eq!(unversioned, v1);
+        assert_eq!(v1, v2);
+        assert_eq!(v2.moves.len(), 4);
+        // Always written as the current version
+        assert_eq!(
+            unversioned.to_string(),
+            include_str!("../fixtures/sgn/v2.sgn").replace("sgn 2", "sgn 3")
+        );
+
+        let v3: GameRecord = include_str!("../fixtures/sgn/v3.sgn").parse().unwrap();
+        assert_eq!(
+            v3.moves[2],
+            Move::Misclaim(triple("3P~O 1R~S 2G#S").cards())
+        );
+        assert_eq!(v3.moves.len(), 5);
+        assert_eq!(v3.to_string(), include_str!("../fixtures/sgn/v3.sgn"));
+    }
+
+    #[test]
+    fn game_record_roundtrip() {
+        let records = [
+            GameRecord {
+                start: Start::Seed(u64::MAX),
+                moves: vec![],
+            },
+            GameRecord {
+                start: Start::Deck(DECK.iter().rev().copied().collect()),
+                moves: vec![
+                    Move::DealExtra,
+                    Move::Hint("2G~S".parse().unwrap()),
+                    Move::Set(triple("3P=O 2G~S 1R#D").cards()),
+                    Move::DealExtra,
+                ],
+            },
+        ];
+        for record in records {
+            assert_eq!(record.to_string().parse(), Ok(record));
+        }
+    }
+
+    #[test]
+    fn parse_game_record_errors() {
+        let error = |s: &str| s.parse::<GameRecord>().unwrap_err().to_string();
+        assert_eq!(error(""), "line 1: expected 'seed' or 'deck'");
+        assert_eq!(
+            error("sgn 4\nseed 1"),
+            "line 1: unsupported version 4 from a newer release, expected up to 3"
+        );
+        assert_eq!(
+            error("sgn 2\nseed 1\nS: 2R~D 1G#O 3P=S"),
+            "line 3: expected 'shuffle <n>', got 'S: 2R~D 1G#O 3P=S'"
+        );
+        assert_eq!(
+            error("sgn 2\nseed 1"),
+            "line 3: expected 'shuffle <n>' after the seed"
+        );
+        assert_eq!(
+            error("sgn 2\nseed 1\nshuffle 2"),
+            "line 3: dealt with shuffle version 2, but this release shuffles with version 1, so \
+             the seed deals other cards"
+        );
+        // Decks are dealt as they are, without a shuffle
+        assert!("sgn 2\ndeck 1R#D".parse::<GameRecord>().is_ok());
+        assert_eq!(error("; note\nsgn one"), "line 2: invalid version 'one'");
+        assert_eq!(
+            error("\nshuffle 4"),
+            "line 2: expected 'seed' or 'deck', got 'shuffle 4'"
+        );
+        assert_eq!(error("seed -1"), "line 1: invalid seed '-1'");
+        assert_eq!(
+            error("deck 1R#D 9R#D"),
+            "line 1: invalid card '9R#D', expected notation such as '2R~O'"
+        );
+        assert_eq!(
+            error("seed 1\nS: 1R#D 2G~S"),
+            "line 2: expected 3 cards in a claim, got 2"
+        );
+        assert_eq!(
+            error("seed 1\nX: 1R#D 2G~S 3P=O"),
+            "line 2: 1R#D 2G~S 3P=O is a set, so it was not a misclaim"
+        );
+        assert_eq!(
+            error("seed 1\n+2"),
+            "line 2: expected a move such as 'S: 2R~D 1G#O 3P=S', 'X: 2R~D 1G#O 3P#S', 'H: 1R#D' \
+             or '+3', got '+2'"
+        );
+        assert_eq!(
+            error("seed 1\nH: 1R#D 2G~S"),
+            "line 2: expected 1 card in a hint, got 2"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Finding the sets among the cards on a board.
+//!
+//! Any two distinct cards complete to exactly one set, so rather than trying every triple, each
+//! pair of cards is completed and the board checked for the third card. A set is found once, from
+//! its first two cards in board order, so sets come out in the order of their cards on the board.
+
+use crate::core::{Card, Triple, DECK_SIZE};
+use std::{cmp::Reverse, fmt, str::FromStr};
+
+/// Which sets on a board come first.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SetOrder {
+    /// By the positions of their cards on the board.
+    #[default]
+    Board,
+    /// The easiest to spot first, by [`Triple::difficulty`], e.g. for hints.
+    Easiest,
+    /// The hardest to spot first, e.g. for practice.
+    Hardest,
+}
+
+impl fmt::Display for SetOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Board => "board",
+            Self::Easiest => "easiest",
+            Self::Hardest => "hardest",
+        })
+    }
+}
+
+impl FromStr for SetOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "board" => Ok(Self::Board),
+            "easiest" => Ok(Self::Easiest),
+            "hardest" => Ok(Self::Hardest),
+            _ => Err(format!(
+                "expected 'board', 'easiest' or 'hardest', got '{s}'"
+            )),
+        }
+    }
+}
+
+/// A pair of cards the solver examined, and whether the card completing it was on the board.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PairExamined {
+    /// The positions of the pair on the board.
+    pub pair: [usize; 2],
+    /// The card that makes a set with the pair.
+    pub completion: Card,
+    /// The position of the completion, if it is on the board after the pair.
+    pub hit: Option<usize>,
+}
+
+/// Every set on the board, in board order. Each card is expected on the board once.
+pub fn find_sets(board: &[Card]) -> Vec<Triple> {
+    sets(board).collect()
+}
+
+/// Every set on the board in the given order, with sets as hard to spot as each other in board
+/// order.
+pub fn find_sets_ordered(board: &[Card], order: SetOrder) -> Vec<Triple> {
+    find_sets_traced(board, order, |_| {})
+}
+
+/// Every set on the board in the given order, passing each pair of cards to `examined` as the
+/// solver examines it, e.g. to trace how the sets were found.
+pub fn find_sets_traced(
+    board: &[Card],
+    order: SetOrder,
+    mut examined: impl FnMut(PairExamined),
+) -> Vec<Triple> {
+    let mut sets: Vec<_> =
+        completed(examine(board).inspect(|pair| examined(*pair)), board).collect();
+    match order {
+        SetOrder::Board => {}
+        SetOrder::Easiest => sets.sort_by_key(Triple::difficulty),
+        SetOrder::Hardest => sets.sort_by_key(|set| Reverse(set.difficulty())),
+    }
+    sets
+}
+
+/// The first set on the board in board order, if there is one.
+pub fn find_first_set(board: &[Card]) -> Option<Triple> {
+    sets(board).next()
+}
+
+fn sets(board: &[Card]) -> impl Iterator<Item = Triple> + '_ {
+    completed(examine(board), board)
+}
+
+/// The sets made by the pairs whose completions were on the board.
+fn completed<'a>(
+    pairs: impl Iterator<Item = PairExamined> + 'a,
+    board: &'a [Card],
+) -> impl Iterator<Item = Triple> + 'a {
+    pairs.filter_map(
+        |PairExamined {
+             pair: [a, b], hit, ..
+         }| { hit.map(|c| Triple::from((board[a], board[b], board[c]))) },
+    )
+}
+
+/// Every pair of distinct cards on the board, completed and looked up.
+fn examine(board: &[Card]) -> impl Iterator<Item = PairExamined> + '_ {
+    // Where each card first is on the board, by index
+    let mut positions = [usize::MAX; DECK_SIZE as usize];
+    for (position, card) in board.iter().enumerate().rev() {
+        positions[u8::from(*card) as usize] = position;
+    }
+    board.iter().enumerate().flat_map(move |(i, a)| {
+        board[i + 1..]
+            .iter()
+            .enumerate()
+            .filter(move |(_, b)| a != *b)
+            .map(move |(j, b)| {
+                let completion = a.third(*b);
+                let position = positions[u8::from(completion) as usize];
+                let j = i + 1 + j;
+                PairExamined {
+                    pair: [i, j],
+                    completion,
+                    hit: (position != usize::MAX && position > j).then_some(position),
+                }
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::Board, core::DECK, test_util::cards, test_vectors::TEST_VECTORS};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sets_match_test_vectors() {
+        for vector in TEST_VECTORS {
+            let board = vector.cards();
+            let found: Vec<_> = find_sets(&board).iter().map(Triple::cards).collect();
+            assert_eq!(vector.check(&found), Ok(()));
+            // Test vectors list their sets in board order too
+            assert_eq!(found, vector.sets(), "{}", vector.name);
+            assert_eq!(
+                find_first_set(&board).map(|set| set.cards()),
+                found.first().copied(),
+                "{}",
+                vector.name
+            );
+            assert_eq!(Board::from(board.clone()).sets(), find_sets(&board));
+        }
+    }
+
+    #[test]
+    fn sets_are_ordered_by_difficulty() {
+        for vector in TEST_VECTORS {
+            let board = vector.cards();
+            assert_eq!(
+                find_sets_ordered(&board, SetOrder::Board),
+                find_sets(&board)
+            );
+            // Where each set is in board order, so that ties can be seen to keep it
+            let in_board_order = find_sets(&board);
+            let keys = |order| -> Vec<_> {
+                find_sets_ordered(&board, order)
+                    .iter()
+                    .map(|set| {
+                        let position = in_board_order.iter().position(|found| found == set);
+                        (set.difficulty(), position.unwrap())
+                    })
+                    .collect()
+            };
+            let easiest = keys(SetOrder::Easiest);
+            assert_eq!(easiest.len(), in_board_order.len(), "{}", vector.name);
+            assert!(easiest.is_sorted(), "{}", vector.name);
+            let hardest = keys(SetOrder::Hardest);
+            assert!(
+                hardest
+                    .is_sorted_by_key(|(difficulty, position)| (Reverse(*difficulty), *position)),
+                "{}",
+                vector.name
+            );
+        }
+        assert_eq!(SetOrder::default().to_string().parse(), Ok(SetOrder::Board));
+        assert!("random".parse::<SetOrder>().is_err());
+    }
+
+    #[test]
+    fn traced_pairs_are_the_ones_that_found_the_sets() {
+        for vector in TEST_VECTORS {
+            let board = vector.cards();
+            let mut examined = Vec::new();
+            let sets = find_sets_traced(&board, SetOrder::Board, |pair| examined.push(pair));
+            assert_eq!(sets, find_sets(&board));
+            assert_eq!(examined.len(), board.len() * (board.len() - 1) / 2);
+            let hits: Vec<_> = examined
+                .iter()
+                .filter_map(
+                    |PairExamined {
+                         pair: [a, b], hit, ..
+                     }| {
+                        hit.map(|c| Triple::from((board[*a], board[*b], board[c])))
+                    },
+                )
+                .collect();
+            assert_eq!(hits, sets, "{}", vector.name);
+        }
+
+        // A completion repeated later on the board is only looked up where it first is
+        let board = cards("1R#D 2G~S 3P=O 1R#D");
+        let mut examined = Vec::new();
+        find_sets_traced(&board, SetOrder::Board, |pair| examined.push(pair));
+        let completion = |[a, b]: [usize; 2]| board[a].third(board[b]);
+        assert_eq!(
+            examined,
+            [[0, 1], [0, 2], [1, 2], [1, 3], [2, 3]]
+                .map(|pair| PairExamined {
+                    pair,
+                    completion: completion(pair),
+                    hit: (pair == [0, 1]).then_some(2),
+                })
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn repeated_cards_are_not_sets() {
+        assert_eq!(find_first_set(&cards("1R#D 1R#D 1R#D")), None);
+        assert_eq!(find_sets(&[]), vec![]);
+        assert_eq!(find_sets(&DECK).len(), 1080);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sets_match_checking_every_triple() {
+        use crate::core::{is_set_batch, Deck};
+
+        // Sizes from a board with one set possible to one that always holds a set
+        for seed in 0..1000 {
+            let mut deck = Deck::new_from_seed(seed);
+            let size = [3, 9, 12, 15, 18, 21][seed as usize % 6];
+            let board: Vec<_> = std::iter::from_fn(|| deck.deal()).take(size).collect();
+            let mut triples = Vec::new();
+            for (i, a) in board.iter().enumerate() {
+                for (j, b) in board.iter().enumerate().skip(i + 1) {
+                    triples.extend(board[j + 1..].iter().map(|c| (*a, *b, *c)));
+                }
+            }
+            let found: Vec<_> = find_sets(&board).iter().map(Triple::cards).collect();
+            let checked = |is_set: &dyn Fn(usize, Triple) -> bool| -> Vec<_> {
+                triples
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, triple)| is_set(*i, Triple::from(**triple)))
+                    .map(|(_, (a, b, c))| [*a, *b, *c])
+                    .collect()
+            };
+            let batch = is_set_batch(&triples);
+            assert_eq!(found, checked(&|_, triple| triple.is_set()), "seed {seed}");
+            assert_eq!(found, checked(&|i, _| batch[i]), "seed {seed}");
+            assert_eq!(
+                found,
+                checked(&|_, triple| triple.broken_attributes().next().is_none()),
+                "seed {seed}"
+            );
+            assert_eq!(
+                find_first_set(&board).map(|set| set.cards()),
+                found.first().copied(),
+                "seed {seed}"
+            );
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Suggestions for editing a board one card at a time, for puzzle authors working by hand.
+//!
+//! Each suggestion replaces one card on the board with a card from the rest of the deck, such
+//! that the board then has the property asked for, e.g. no set at all, or exactly one.
+
+use crate::{
+    card_set::CardSet,
+    core::Card,
+    solver::{find_first_set, find_sets},
+};
+use std::{fmt, str::FromStr};
+
+/// What a board should be like after a swap.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SwapTarget {
+    /// No set on the board.
+    NoSet,
+    /// Exactly one set on the board.
+    OneSet,
+    /// More sets through the card than before, e.g. to make a card that fits nothing useful.
+    SetThrough(Card),
+}
+
+/// Replacing the card at a position on the board with another.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Swap {
+    pub position: usize,
+    pub removed: Card,
+    pub added: Card,
+}
+
+impl Swap {
+    /// The board after the swap.
+    pub fn apply(&self, cards: &[Card]) -> Vec<Card> {
+        let mut cards = cards.to_vec();
+        cards[self.position] = self.added;
+        cards
+    }
+}
+
+/// Every single-card swap that gives the board the target property, by position on the board
+/// and then by the card added.
+pub fn suggest_swaps(cards: &[Card], target: SwapTarget) -> Vec<Swap> {
+    let unused = CardSet::from(cards).complement();
+    let before = match target {
+        SwapTarget::SetThrough(card) => sets_through(cards, card),
+        _ => 0,
+    };
+    let reaches = |board: &[Card]| match target {
+        SwapTarget::NoSet => find_first_set(board).is_none(),
+        SwapTarget::OneSet => find_sets(board).len() == 1,
+        SwapTarget::SetThrough(card) => sets_through(board, card) > before,
+    };
+    let mut board = cards.to_vec();
+    let mut swaps = Vec::new();
+    for (position, removed) in cards.iter().copied().enumerate() {
+        for added in unused {
+            board[position] = added;
+            if reaches(&board) {
+                swaps.push(Swap {
+                    position,
+                    removed,
+                    added,
+                });
+            }
+        }
+        board[position] = removed;
+    }
+    swaps
+}
+
+/// How many sets on the board include the card.
+fn sets_through(cards: &[Card], card: Card) -> usize {
+    let on_board = CardSet::from(cards);
+    if !on_board.contains(card) {
+        return 0;
+    }
+    let pairs = cards
+        .iter()
+        .filter(|other| **other != card)
+        .filter(|other| {
+            let third = card.third(**other);
+            third != card && on_board.contains(third)
+        })
+        .count();
+    // Each set was counted from both of its other cards
+    pairs / 2
+}
+
+/// `no-set`, `one-set` or `through=<card>`, e.g. `through=2R~O`.
+impl fmt::Display for SwapTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoSet => f.write_str("no-set"),
+            Self::OneSet => f.write_str("one-set"),
+            Self::SetThrough(card) => write!(f, "through={card}"),
+        }
+    }
+}
+
+impl FromStr for SwapTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "no-set" => Ok(Self::NoSet),
+            "one-set" => Ok(Self::OneSet),
+            _ => match s.strip_prefix("through=") {
+                Some(card) => card
+                    .parse()
+                    .map(Self::SetThrough)
+                    .map_err(|error| error.to_string()),
+                None => Err(format!(
+                    "expected 'no-set', 'one-set' or 'through=<card>', got '{s}'"
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::cards;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn swaps_reach_the_target() {
+        // One set, 1R#D 2G~S 3P=O, and nothing through 1P#S
+        let board = cards("1R#D 2G~S 3P=O 1R#S 1P#S 2R=D");
+        assert_eq!(find_sets(&board).len(), 1);
+        for target in [SwapTarget::NoSet, SwapTarget::OneSet] {
+            let swaps = suggest_swaps(&board, target);
+            assert!(!swaps.is_empty(), "{target}");
+            for swap in &swaps {
+                let after = swap.apply(&board);
+                assert_eq!(after[swap.position], swap.added);
+                let expected = if target == SwapTarget::NoSet { 0 } else { 1 };
+                assert_eq!(find_sets(&after).len(), expected, "{target} {swap:?}");
+            }
+        }
+        // Breaking the one set means swapping out one of its cards
+        assert!(suggest_swaps(&board, SwapTarget::NoSet)
+            .iter()
+            .all(|swap| swap.position < 3));
+
+        let through: Card = "1P#S".parse().unwrap();
+        let swaps = suggest_swaps(&board, SwapTarget::SetThrough(through));
+        assert!(swaps.contains(&Swap {
+            position: 5,
+            removed: "2R=D".parse().unwrap(),
+            added: "1G#S".parse().unwrap(),
+        }));
+        assert!(swaps.iter().all(|swap| swap.removed != through));
+    }
+
+    #[test]
+    fn targets_round_trip() {
+        for target in ["no-set", "one-set", "through=2R~O"] {
+            assert_eq!(target.parse::<SwapTarget>().unwrap().to_string(), target);
+        }
+        assert!("two-sets".parse::<SwapTarget>().is_err());
+        assert!("through=9R~O".parse::<SwapTarget>().is_err());
+    }
+}
@@ -0,0 +1,30 @@
+//! Fixtures for the tests, written in card notation.
+
+use crate::{
+    core::{Card, Deck, Triple},
+    game::GameState,
+};
+
+/// Twelve cards holding the set 1R#D 2G~S 3P=O among others.
+pub(crate) const BOARD: &str = "1R#D 2G~S 3P=O 1R#S 1R#O 2R#D 3R#D 1G#D 1P#D 2G#D 2P#D 3G#D";
+
+/// Cards in notation, separated by spaces.
+pub(crate) fn cards(notation: &str) -> Vec<Card> {
+    notation
+        .split_whitespace()
+        .map(|card| card.parse().unwrap())
+        .collect()
+}
+
+/// Three cards in notation, separated by spaces.
+pub(crate) fn triple(notation: &str) -> Triple {
+    let [a, b, c] = cards(notation).try_into().unwrap();
+    Triple::from((a, b, c))
+}
+
+/// A game whose board is the first twelve cards given, dealing the rest in order.
+pub(crate) fn game(notation: &str) -> GameState {
+    let mut deck = cards(notation);
+    deck.reverse();
+    GameState::new(Deck(deck))
+}
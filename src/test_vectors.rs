@@ -0,0 +1,261 @@
+//! Boards with known solutions, for checking solvers against, inside this crate or out.
+//!
+//! Each board's sets are written out here rather than found when the tests run. This crate's
+//! tests check them by trying every triple on the board against the rules as written, each
+//! symbol of the cards' notation all the same or all different, without the card arithmetic the
+//! solver uses. They include boards without a set, the largest such board, and boards holding as
+//! many sets as their size allows.
+//!
+//! Sets are listed in the order of their first card on the board, then their second and third,
+//! with the cards of each set in board order. [`TestVector::check`] compares sets found in any
+//! order.
+
+use crate::core::Card;
+
+/// A board and every set on it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TestVector {
+    pub name: &'static str,
+    /// What makes the board interesting.
+    pub about: &'static str,
+    /// The cards in notation, separated by spaces.
+    pub board: &'static str,
+    /// Each set as three cards in notation, separated by spaces.
+    pub sets: &'static [&'static str],
+}
+
+impl TestVector {
+    pub fn cards(&self) -> Vec<Card> {
+        parse(self.board)
+    }
+
+    pub fn sets(&self) -> Vec<[Card; 3]> {
+        self.sets
+            .iter()
+            .map(|set| {
+                parse(set)
+                    .try_into()
+                    .expect("test vector sets have three cards")
+            })
+            .collect()
+    }
+
+    /// Whether `found` holds exactly the board's sets, in any order, with a description of the
+    /// first difference if not.
+    pub fn check(&self, found: &[[Card; 3]]) -> Result<(), String> {
+        let expected = self.sets();
+        let sorted_expected: Vec<_> = expected.iter().map(sorted).collect();
+        let sorted_found: Vec<_> = found.iter().map(sorted).collect();
+        if let Some(set) = expected
+            .iter()
+            .find(|set| !sorted_found.contains(&sorted(set)))
+        {
+            return Err(format!("{}: missed {}", self.name, notation(set)));
+        }
+        if let Some(set) = found
+            .iter()
+            .find(|set| !sorted_expected.contains(&sorted(set)))
+        {
+            return Err(format!(
+                "{}: {} is not a set on the board",
+                self.name,
+                notation(set)
+            ));
+        }
+        if found.len() != expected.len() {
+            return Err(format!("{}: found some sets more than once", self.name));
+        }
+        Ok(())
+    }
+}
+
+fn parse(cards: &str) -> Vec<Card> {
+    cards
+        .split_whitespace()
+        .map(|card| card.parse().expect("test vectors are valid notation"))
+        .collect()
+}
+
+/// The set's cards in ascending order, to compare sets found in any order.
+fn sorted(set: &[Card; 3]) -> [Card; 3] {
+    let mut set = *set;
+    set.sort();
+    set
+}
+
+fn notation([a, b, c]: &[Card; 3]) -> String {
+    format!("{a} {b} {c}")
+}
+
+/// Every test vector, smallest boards first.
+pub static TEST_VECTORS: &[TestVector] = &[
+    TestVector {
+        name: "one_set",
+        about: "A set of three cards, all different in every attribute.",
+        board: "1R#D 2G~S 3P=O",
+        sets: &["1R#D 2G~S 3P=O"],
+    },
+    TestVector {
+        name: "no_set",
+        about: "Three cards that are not a set, two sharing a color the third lacks.",
+        board: "1R#D 2R~S 3P=O",
+        sets: &[],
+    },
+    TestVector {
+        name: "opening_board",
+        about: "The opening board of seed 42, with a single set.",
+        board: "3G=D 3G~D 2P~S 1R#O 1G#O 1R=S 1P=S 1R~S 3R#S 2R#S 2P=D 2G~D",
+        sets: &["3G=D 2P~S 1R#O"],
+    },
+    TestVector {
+        name: "setless_12",
+        about: "Twelve cards without a set, so more must be dealt.",
+        board: "1R#D 1R#S 1R~D 1R~S 1G#D 1G#S 1G~D 1G~S 2R#D 2R#S 2R~D 2R~S",
+        sets: &[],
+    },
+    TestVector {
+        name: "setless_20",
+        about: "Twenty cards without a set, the most there can be; any 21 cards hold one.",
+        board: "1R#D 1R#S 1R~D 1R~S 1G#D 1G#S 1G~D 1G~S 2R#D 2R#S 2R~O 2R=O \
+            2G#O 2P#O 3R~O 3G#O 3G~D 3G~S 3G=O 3P~O",
+        sets: &[],
+    },
+    TestVector {
+        name: "plane_9",
+        about: "Nine cards forming a plane, with twelve sets, the most nine cards can hold.",
+        board: "1G=D 1P#D 1R~D 2G=S 2P#S 2R~S 3G=O 3P#O 3R~O",
+        sets: &[
+            "1G=D 1P#D 1R~D",
+            "1G=D 2G=S 3G=O",
+            "1G=D 2P#S 3R~O",
+            "1G=D 2R~S 3P#O",
+            "1P#D 2G=S 3R~O",
+            "1P#D 2P#S 3P#O",
+            "1P#D 2R~S 3G=O",
+            "1R~D 2G=S 3P#O",
+            "1R~D 2P#S 3G=O",
+            "1R~D 2R~S 3R~O",
+            "2G=S 2P#S 2R~S",
+            "3G=O 3P#O 3R~O",
+        ],
+    },
+    TestVector {
+        name: "most_sets_12",
+        about: "Twelve cards with fourteen sets, the most twelve cards can hold.",
+        board: "3R=O 2R=D 1R=O 3R=S 2R=S 3R~S 1R#D 3R=D 2R=O 1R=S 2R#D 1R=D",
+        sets: &[
+            "3R=O 2R=D 1R=S",
+            "3R=O 1R=O 2R=O",
+            "3R=O 3R=S 3R=D",
+            "3R=O 2R=S 1R=D",
+            "2R=D 1R=O 3R=S",
+            "2R=D 2R=S 2R=O",
+            "2R=D 3R=D 1R=D",
+            "1R=O 2R=S 3R=D",
+            "1R=O 3R~S 2R#D",
+            "1R=O 1R=S 1R=D",
+            "3R=S 2R=S 1R=S",
+            "3R=S 2R=O 1R=D",
+            "3R~S 1R#D 2R=O",
+            "3R=D 2R=O 1R=S",
+        ],
+    },
+    TestVector {
+        name: "extra_deal_15",
+        about: "Fifteen cards, as after dealing three more.",
+        board: "2R=S 1P~D 3G=S 2R~D 2R#O 2R=O 3P=D 3P=S 2G=S 3G~D 1G~O 3R#S \
+            2G~D 3G=O 3R~D",
+        sets: &[
+            "2R=S 2R~D 2R#O",
+            "1P~D 3G=S 2R#O",
+            "1P~D 2R~D 3G~D",
+            "1P~D 2G~D 3R~D",
+        ],
+    },
+    TestVector {
+        name: "after_21",
+        about: "Twenty one cards, which always hold a set.",
+        board: "3G~D 3G#D 1R=D 2P~D 3P#O 1P~S 1G#S 3G#O 1G#D 3R=S 1R~D 1R#S \
+            2R#D 3P~D 3G#S 2R~S 1P=O 3R=D 2P#O 1P=D 1R#D",
+        sets: &[
+            "3G~D 2P~D 1R~D",
+            "3G~D 3P#O 3R=S",
+            "3G~D 2R#D 1P=D",
+            "3G#D 1R=D 2P~D",
+            "3G#D 3G#O 3G#S",
+            "3G#D 1R#S 2P#O",
+            "3G#D 3P~D 3R=D",
+            "3G#D 2R~S 1P=O",
+            "1R=D 1R~D 1R#D",
+            "2P~D 1G#D 3R=D",
+            "3P#O 1G#S 2R#D",
+            "1G#S 1R~D 1P=O",
+            "3G#O 3R=S 3P~D",
+            "3G#O 2R~S 1P=D",
+            "1G#D 1R~D 1P=D",
+            "3R=S 1R#S 2R~S",
+            "1R~D 2R#D 3R=D",
+            "3G#S 2P#O 1R#D",
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Whether each symbol of the cards' notation is the same on all three or different on all.
+    fn by_the_rules(cards: [Card; 3]) -> bool {
+        let notation = cards.map(|card| card.to_string().chars().collect::<Vec<_>>());
+        (0..4).all(|i| {
+            let [a, b, c] = [notation[0][i], notation[1][i], notation[2][i]];
+            (a == b && b == c) || (a != b && b != c && a != c)
+        })
+    }
+
+    #[test]
+    fn vectors_follow_the_rules() {
+        for vector in TEST_VECTORS {
+            let cards = vector.cards();
+            let mut unique = cards.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), cards.len(), "{}", vector.name);
+            let mut expected = Vec::new();
+            for (i, a) in cards.iter().enumerate() {
+                for (j, b) in cards.iter().enumerate().skip(i + 1) {
+                    for c in &cards[j + 1..] {
+                        if by_the_rules([*a, *b, *c]) {
+                            expected.push([*a, *b, *c]);
+                        }
+                    }
+                }
+            }
+            assert_eq!(vector.sets(), expected, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn check_reports_differences() {
+        let vector = TEST_VECTORS
+            .iter()
+            .find(|vector| vector.name == "extra_deal_15")
+            .unwrap();
+        let mut found = vector.sets();
+        found.reverse();
+        found[0].reverse();
+        assert_eq!(vector.check(&found), Ok(()));
+        let missed = found.pop().unwrap();
+        assert_eq!(
+            vector.check(&found),
+            Err(format!("extra_deal_15: missed {}", notation(&missed)))
+        );
+        found.push(missed);
+        found.push(missed);
+        assert_eq!(
+            vector.check(&found),
+            Err("extra_deal_15: found some sets more than once".to_owned())
+        );
+    }
+}